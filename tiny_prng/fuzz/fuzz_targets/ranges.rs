@@ -0,0 +1,34 @@
+//! Checks that the bounded integers and the ranged reals stay in their bounds.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use tiny_prng::pcg::PcgXslRr;
+use tiny_prng::Generator;
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    seed: u128,
+    a: i128,
+    b: i128,
+    bound: u64,
+    x: f64,
+    y: f64,
+}
+
+fuzz_target!(|input: Input| {
+    let mut g = PcgXslRr::with_seed(input.seed);
+    let (lower, upper) = (input.a.min(input.b), input.a.max(input.b));
+    let v: i128 = g.generate_range(lower..=upper);
+    assert!((lower..=upper).contains(&v));
+    if input.bound > 0 {
+        assert!(g.generate_below(input.bound) < input.bound);
+    }
+    let (lower, upper) = (input.x.min(input.y), input.x.max(input.y));
+    // the width of the range must be a finite double
+    if lower < upper && (upper - lower).is_finite() {
+        let v = g.generate_real_in_range(lower, upper);
+        assert!(lower <= v && v < upper, "{} not in [{}, {})", v, lower, upper);
+    }
+});
@@ -0,0 +1,36 @@
+//! Seeds every generator from a seed sequence of any bytes and its children.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tiny_prng::seed::{FromSeedSequence, SeedSequence};
+use tiny_prng::{mt, mt64, pcg, xorshift, Generator};
+
+fn draw<G: Generator + FromSeedSequence>(seq: &SeedSequence) {
+    let mut g = G::from_seed_sequence(seq);
+    for _ in 0..8 {
+        g.generate_u64();
+    }
+}
+
+fuzz_target!(|data: (&[u8], u8)| {
+    let (bytes, children) = data;
+    let mut root = SeedSequence::from_bytes(bytes);
+    for seq in std::iter::once(root.clone()).chain(root.spawn(children as usize % 8)) {
+        draw::<mt::Mt19937>(&seq);
+        draw::<mt64::Mt19937>(&seq);
+        draw::<xorshift::Xorshift32>(&seq);
+        draw::<xorshift::Xorshift64>(&seq);
+        draw::<xorshift::Xorshift128>(&seq);
+        draw::<xorshift::Xorshift64star>(&seq);
+        draw::<xorshift::Xorshift1024star>(&seq);
+        draw::<xorshift::Xoshiro256plusplus>(&seq);
+        draw::<pcg::PcgXshRr6432>(&seq);
+        draw::<pcg::PcgXshRs6432>(&seq);
+        draw::<pcg::PcgXslRr6432Mcg>(&seq);
+        draw::<pcg::PcgXslRr>(&seq);
+        draw::<pcg::PcgXslRrMcg>(&seq);
+        draw::<pcg::Pcg32>(&seq);
+        draw::<pcg::Pcg64>(&seq);
+    }
+});
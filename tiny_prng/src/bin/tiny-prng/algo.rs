@@ -0,0 +1,107 @@
+//! The generators selectable with `--algo`.
+
+use tiny_prng::seed::{FromSeedSequence, SeedSequence};
+use tiny_prng::{mt, mt64, pcg, xorshift, Generator};
+
+/// A generator with its names and native word width.
+pub struct Algo {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    /// the bits of the native output, 32 or 64
+    pub width: u32,
+    pub description: &'static str,
+    build: fn(&SeedSequence) -> Box<dyn Generator>,
+}
+
+impl Algo {
+    /// construct the generator from the seed sequence as `FromSeedSequence` does
+    pub fn build(&self, seq: &SeedSequence) -> Box<dyn Generator> {
+        (self.build)(seq)
+    }
+}
+
+fn boxed<G: Generator + FromSeedSequence + 'static>(seq: &SeedSequence) -> Box<dyn Generator> {
+    Box::new(G::from_seed_sequence(seq))
+}
+
+pub const DEFAULT: &str = "pcg-xsl-rr";
+
+pub const ALGOS: &[Algo] = &[
+    Algo { name: "mt19937", aliases: &["mt"], width: 32, description: "Mersenne Twister MT19937", build: boxed::<mt::Mt19937> },
+    Algo { name: "mt19937-64", aliases: &["mt64"], width: 64, description: "Mersenne Twister MT19937-64", build: boxed::<mt64::Mt19937> },
+    Algo { name: "xorshift32", aliases: &[], width: 32, description: "xorshift32", build: boxed::<xorshift::Xorshift32> },
+    Algo { name: "xorshift64", aliases: &[], width: 64, description: "xorshift64", build: boxed::<xorshift::Xorshift64> },
+    Algo { name: "xorshift128", aliases: &[], width: 32, description: "xorshift128", build: boxed::<xorshift::Xorshift128> },
+    Algo { name: "xorshift64star", aliases: &["xorshift64*"], width: 64, description: "xorshift64*", build: boxed::<xorshift::Xorshift64star> },
+    Algo { name: "xorshift1024star", aliases: &["xorshift1024*"], width: 64, description: "xorshift1024*", build: boxed::<xorshift::Xorshift1024star> },
+    Algo { name: "xoshiro256plusplus", aliases: &["xoshiro256++"], width: 64, description: "xoshiro256++", build: boxed::<xorshift::Xoshiro256plusplus> },
+    Algo { name: "pcg-xsh-rr-64-32", aliases: &[], width: 32, description: "PCG-XSH-RR-64/32 (LCG)", build: boxed::<pcg::PcgXshRr6432> },
+    Algo { name: "pcg-xsh-rs-64-32", aliases: &[], width: 32, description: "PCG-XSH-RS-64/32 (LCG)", build: boxed::<pcg::PcgXshRs6432> },
+    Algo { name: "pcg-xsl-rr-64-32-mcg", aliases: &[], width: 32, description: "PCG-XSL-RR-64/32 (MCG)", build: boxed::<pcg::PcgXslRr6432Mcg> },
+    Algo { name: "pcg-xsl-rr", aliases: &["pcg-xsl-rr-128-64"], width: 64, description: "PCG-XSL-RR-128/64 (LCG)", build: boxed::<pcg::PcgXslRr> },
+    Algo { name: "pcg32", aliases: &[], width: 32, description: "PCG-XSH-RR-64/32 (LCG, the constants of pcg-c)", build: boxed::<pcg::Pcg32> },
+    Algo { name: "pcg64", aliases: &[], width: 64, description: "PCG-XSL-RR-128/64 (LCG, the constants of pcg-c)", build: boxed::<pcg::Pcg64> },
+    Algo { name: "pcg-xsl-rr-mcg", aliases: &["pcg-xsl-rr-128-64-mcg"], width: 64, description: "PCG-XSL-RR-128/64 (MCG)", build: boxed::<pcg::PcgXslRrMcg> },
+];
+
+/// the generator by its name or alias, ignoring the case
+pub fn find(name: &str) -> Result<&'static Algo, String> {
+    let name = name.to_ascii_lowercase();
+    ALGOS
+        .iter()
+        .find(|a| a.name == name || a.aliases.contains(&name.as_str()))
+        .ok_or_else(|| format!("unknown algorithm: {} (see --list)", name))
+}
+
+/// the table of the generators for `--list`
+pub fn list() -> String {
+    ALGOS
+        .iter()
+        .map(|a| {
+            let names = std::iter::once(a.name).chain(a.aliases.iter().copied()).collect::<Vec<_>>().join(", ");
+            format!("{:<40} {:>2}bit  {}\n", names, a.width, a.description)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find() {
+        assert_eq!("pcg64", find("PCG64").unwrap().name);
+        assert_eq!("pcg-xsl-rr", find("pcg-xsl-rr-128-64").unwrap().name);
+        assert_eq!("xorshift64star", find("xorshift64*").unwrap().name);
+        assert!(find("randu").is_err());
+        assert!(find(DEFAULT).is_ok());
+    }
+
+    #[test]
+    fn test_names_unique() {
+        let mut names: Vec<&str> = ALGOS.iter().flat_map(|a| std::iter::once(a.name).chain(a.aliases.iter().copied())).collect();
+        let len = names.len();
+        names.sort();
+        names.dedup();
+        assert_eq!(len, names.len());
+    }
+
+    #[test]
+    fn test_build_matches_library() {
+        let seq = SeedSequence::from_u128(0x1818729);
+        let mut g = find("pcg-xsl-rr").unwrap().build(&seq);
+        let mut p = pcg::PcgXslRr::from_seed_sequence(&seq);
+        assert_eq!(p.generate(), g.generate_u64());
+        for a in ALGOS {
+            let mut g = a.build(&seq);
+            assert_ne!(g.generate_u64(), g.generate_u64(), "{}", a.name);
+        }
+    }
+
+    #[test]
+    fn test_widths_match_vectors() {
+        for a in ALGOS {
+            assert_eq!(Some(a.width), tiny_prng::vectors::width(a.name), "{}", a.name);
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! A minimal parser of the command line, as the crate has no dependencies.
+
+use std::str::FromStr;
+
+/// The options and the positional arguments on the command line.
+///
+/// An option is `--name value`, `--name=value` or `-n value`, and a flag is an option without a value.
+/// An argument starting with `-` which reads as a number, such as `-5`, is positional.
+#[derive(Debug, Default)]
+pub struct Args {
+    values: Vec<(String, String)>,
+    flags: Vec<String>,
+    pub positional: Vec<String>,
+}
+
+/// the name of the option without its dashes, or `None` for a positional argument
+fn option_name(arg: &str) -> Option<&str> {
+    match arg.strip_prefix("--").or_else(|| arg.strip_prefix('-')) {
+        Some(name) if !name.is_empty() && arg.parse::<f64>().is_err() => Some(name),
+        _ => None,
+    }
+}
+
+impl Args {
+    /// parse the arguments, where the names in `flags` take no value
+    pub fn parse(args: impl IntoIterator<Item = String>, flags: &[&str]) -> Result<Self, String> {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if arg == "--" {
+                parsed.positional.extend(args.by_ref());
+                break;
+            }
+            let Some(name) = option_name(&arg) else {
+                parsed.positional.push(arg);
+                continue;
+            };
+            match name.split_once('=') {
+                Some((name, value)) => parsed.values.push((name.to_string(), value.to_string())),
+                None if flags.contains(&name) => parsed.flags.push(name.to_string()),
+                None => {
+                    let value = args.next().ok_or_else(|| format!("missing value of {}", arg))?;
+                    parsed.values.push((name.to_string(), value));
+                }
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// whether the flag under any of the names is given
+    pub fn flag(&self, names: &[&str]) -> bool {
+        self.flags.iter().any(|f| names.contains(&f.as_str()))
+    }
+
+    /// the raw value of the option under any of the names, the last one if given repeatedly
+    pub fn value_str(&self, names: &[&str]) -> Option<&str> {
+        self.values.iter().rev().find(|(n, _)| names.contains(&n.as_str())).map(|(_, v)| v.as_str())
+    }
+
+    /// the value of the option parsed as `T`
+    pub fn value<T: FromStr>(&self, names: &[&str]) -> Result<Option<T>, String> {
+        self.value_str(names)
+            .map(|v| v.parse().map_err(|_| format!("invalid value of --{}: {}", names[0], v)))
+            .transpose()
+    }
+
+    /// fail on an option or a flag which is none of the known names
+    pub fn check_known(&self, known: &[&str]) -> Result<(), String> {
+        let names = self.values.iter().map(|(n, _)| n).chain(self.flags.iter());
+        match names.into_iter().find(|n| !known.contains(&n.as_str())) {
+            Some(n) => Err(format!("unknown option: {}", n)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// parse an unsigned integer in decimal or with a `0x`, `0o` or `0b` prefix, allowing `_` separators
+pub fn parse_u128(s: &str) -> Result<u128, String> {
+    let digits = s.replace('_', "");
+    let (radix, digits) = match digits.get(..2) {
+        Some("0x" | "0X") => (16, &digits[2..]),
+        Some("0o" | "0O") => (8, &digits[2..]),
+        Some("0b" | "0B") => (2, &digits[2..]),
+        _ => (10, &digits[..]),
+    };
+    u128::from_str_radix(digits, radix).map_err(|_| format!("invalid integer: {}", s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Args {
+        Args::parse(args.iter().map(|s| s.to_string()), &["list", "h"]).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let a = parse(&["--algo", "mt19937", "-n=5", "--list", "x", "-5", "--seed", "1", "--seed", "2", "--", "--y"]);
+        assert_eq!(Some("mt19937"), a.value_str(&["algo", "a"]));
+        assert_eq!(Some(5), a.value::<u64>(&["count", "n"]).unwrap());
+        assert_eq!(Some("2"), a.value_str(&["seed"]));
+        assert!(a.flag(&["list"]));
+        assert!(!a.flag(&["h"]));
+        assert_eq!(vec!["x", "-5", "--y"], a.positional);
+        assert!(a.check_known(&["algo", "n", "seed", "list"]).is_ok());
+        assert_eq!(Err("unknown option: list".to_string()), a.check_known(&["algo", "n", "seed"]));
+        assert!(a.value::<u64>(&["algo"]).is_err());
+    }
+
+    #[test]
+    fn test_missing_value() {
+        let r = Args::parse(["--seed".to_string()], &[]);
+        assert_eq!("missing value of --seed", r.unwrap_err());
+    }
+
+    #[test]
+    fn test_parse_u128() {
+        assert_eq!(Ok(1818729), parse_u128("1818729"));
+        assert_eq!(Ok(0xdead_beef), parse_u128("0xdead_beef"));
+        assert_eq!(Ok(5), parse_u128("0b101"));
+        assert_eq!(Ok(u128::MAX), parse_u128("0xffffffffffffffffffffffffffffffff"));
+        assert!(parse_u128("0x").is_err());
+        assert!(parse_u128("-1").is_err());
+        assert!(parse_u128("desert").is_err());
+    }
+}
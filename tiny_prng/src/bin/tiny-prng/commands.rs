@@ -0,0 +1,154 @@
+//! The subcommands drawing lines, integers and reals with a generator.
+
+use std::io::{BufRead, Write};
+use tiny_prng::distributions::{Distribution, Normal};
+use tiny_prng::generator::UniformInt;
+use tiny_prng::{seq, Generator};
+use crate::args::Args;
+use crate::Error;
+
+/// the items from the positional arguments after the subcommand, or the lines of the input without them
+fn items(args: &Args, input: &mut dyn BufRead) -> Result<Vec<Vec<u8>>, Error> {
+    match args.positional.len() > 1 {
+        true => Ok(args.positional[1..].iter().map(|s| s.as_bytes().to_vec()).collect()),
+        false => Ok(input.split(b'\n').collect::<Result<_, _>>()?),
+    }
+}
+
+fn write_lines<'a>(lines: impl IntoIterator<Item = &'a Vec<u8>>, out: &mut dyn Write) -> Result<(), Error> {
+    for line in lines {
+        out.write_all(line)?;
+        out.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+/// `shuffle [ITEM...]`: write the items or the input lines in a random order
+pub fn shuffle(args: &Args, g: &mut dyn Generator, input: &mut dyn BufRead, out: &mut dyn Write) -> Result<(), Error> {
+    let mut lines = items(args, input)?;
+    seq::shuffle(g, &mut lines);
+    write_lines(&lines, out)
+}
+
+/// `sample -k K [ITEM...]`: write `K` of the items or the input lines, all of them if fewer, in a random order
+pub fn sample(args: &Args, g: &mut dyn Generator, input: &mut dyn BufRead, out: &mut dyn Write) -> Result<(), Error> {
+    let k = args.value::<usize>(&["k"])?.ok_or_else(|| Error::Usage("sample needs -k".to_string()))?;
+    if args.positional.len() > 1 {
+        let items = items(args, input)?;
+        return write_lines(seq::choose_multiple(g, &items, k), out);
+    }
+    // the input is held in memory only as far as the reservoir
+    let mut error = None;
+    let lines = input.split(b'\n').map_while(|line| line.map_err(|e| error = Some(e)).ok());
+    let chosen = seq::reservoir_sample(g, lines, k);
+    match error {
+        Some(e) => Err(e.into()),
+        None => write_lines(&chosen, out),
+    }
+}
+
+/// `range LOW HIGH [--count N]`: write integers uniformly distributed in `[LOW, HIGH]`, such as dice rolls
+pub fn range(args: &Args, g: &mut dyn Generator, out: &mut dyn Write) -> Result<(), Error> {
+    let [_, low, high] = &args.positional[..] else {
+        return Err(Error::Usage("range needs LOW and HIGH".to_string()));
+    };
+    let parse = |s: &String| s.parse::<i64>().map_err(|_| Error::Usage(format!("invalid integer: {}", s)));
+    let (low, high) = (parse(low)?, parse(high)?);
+    if low > high {
+        return Err(Error::Usage(format!("empty range: {} > {}", low, high)));
+    }
+    for _ in 0..args.value::<u64>(&["count", "n"])?.unwrap_or(1) {
+        writeln!(out, "{}", i64::sample_inclusive(g, low, high))?;
+    }
+    Ok(())
+}
+
+/// `normal [--mean M] [--sd S] [--count N]`: write reals from the normal distribution
+pub fn normal(args: &Args, g: &mut dyn Generator, out: &mut dyn Write) -> Result<(), Error> {
+    if let Some(arg) = args.positional.get(1) {
+        return Err(Error::Usage(format!("unexpected argument: {}", arg)));
+    }
+    let mean = args.value(&["mean"])?.unwrap_or(0.0);
+    let sd = args.value(&["sd"])?.unwrap_or(1.0);
+    let normal = Normal::new(mean, sd).map_err(|e| Error::Usage(e.to_string()))?;
+    for _ in 0..args.value::<u64>(&["count", "n"])?.unwrap_or(1) {
+        writeln!(out, "{}", normal.sample(g))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tiny_prng::xorshift::Xorshift64;
+
+    fn parse(args: &[&str]) -> Args {
+        Args::parse(args.iter().map(|s| s.to_string()), &[]).unwrap()
+    }
+
+    fn lines(out: &[u8]) -> Vec<&str> {
+        std::str::from_utf8(out).unwrap().lines().collect()
+    }
+
+    #[test]
+    fn test_shuffle() {
+        let mut out = vec![];
+        let mut input = &b"a\nb\nc\nd\ne\n"[..];
+        shuffle(&parse(&["shuffle"]), &mut Xorshift64::with_seed(1), &mut input, &mut out).ok().unwrap();
+        let mut shuffled = lines(&out);
+        assert_eq!(5, shuffled.len());
+        shuffled.sort();
+        assert_eq!(vec!["a", "b", "c", "d", "e"], shuffled);
+
+        // the items on the command line take the place of the input
+        let mut out = vec![];
+        shuffle(&parse(&["shuffle", "x", "y"]), &mut Xorshift64::with_seed(1), &mut &b"a\n"[..], &mut out).ok().unwrap();
+        let mut shuffled = lines(&out);
+        shuffled.sort();
+        assert_eq!(vec!["x", "y"], shuffled);
+    }
+
+    #[test]
+    fn test_sample() {
+        let input: String = (0..100).map(|i| format!("{}\n", i)).collect();
+        let mut out = vec![];
+        sample(&parse(&["sample", "-k", "10"]), &mut Xorshift64::with_seed(1), &mut input.as_bytes(), &mut out).ok().unwrap();
+        let mut chosen: Vec<u32> = lines(&out).iter().map(|l| l.parse().unwrap()).collect();
+        chosen.sort();
+        chosen.dedup();
+        assert_eq!(10, chosen.len());
+        assert!(chosen.iter().all(|&c| c < 100));
+
+        let mut out = vec![];
+        sample(&parse(&["sample", "-k", "5", "a", "b"]), &mut Xorshift64::with_seed(1), &mut &b""[..], &mut out).ok().unwrap();
+        assert_eq!(2, lines(&out).len());
+        assert!(matches!(sample(&parse(&["sample"]), &mut Xorshift64::with_seed(1), &mut &b""[..], &mut out), Err(Error::Usage(_))));
+    }
+
+    #[test]
+    fn test_range() {
+        let mut out = vec![];
+        range(&parse(&["range", "1", "6", "--count", "600"]), &mut Xorshift64::with_seed(1), &mut out).ok().unwrap();
+        let rolls: Vec<i64> = lines(&out).iter().map(|l| l.parse().unwrap()).collect();
+        assert_eq!(600, rolls.len());
+        assert!((1..=6).all(|face| rolls.contains(&face)));
+        assert!(rolls.iter().all(|r| (1..=6).contains(r)));
+
+        let mut out = vec![];
+        range(&parse(&["range", "-5", "-5", "-n", "3"]), &mut Xorshift64::with_seed(1), &mut out).ok().unwrap();
+        assert_eq!(vec!["-5", "-5", "-5"], lines(&out));
+        assert!(matches!(range(&parse(&["range", "6", "1"]), &mut Xorshift64::with_seed(1), &mut out), Err(Error::Usage(_))));
+        assert!(matches!(range(&parse(&["range", "1"]), &mut Xorshift64::with_seed(1), &mut out), Err(Error::Usage(_))));
+    }
+
+    #[test]
+    fn test_normal() {
+        let mut out = vec![];
+        normal(&parse(&["normal", "--mean", "10", "--sd", "0.5", "-n", "1000"]), &mut Xorshift64::with_seed(1), &mut out).ok().unwrap();
+        let samples: Vec<f64> = lines(&out).iter().map(|l| l.parse().unwrap()).collect();
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        assert_eq!(1000, samples.len());
+        assert!((mean - 10.0).abs() < 0.1);
+        assert!(matches!(normal(&parse(&["normal", "--sd", "-1"]), &mut Xorshift64::with_seed(1), &mut out), Err(Error::Usage(_))));
+    }
+}
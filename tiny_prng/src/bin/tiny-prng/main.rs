@@ -0,0 +1,247 @@
+//! `tiny-prng` writes the outputs of the generators in the crate to stdout.
+//!
+//! The raw binary stream feeds the external test batteries such as PractRand, TestU01 and dieharder,
+//! the text formats are for a quick look, and the subcommands shuffle, sample and draw numbers
+//! reproducibly for scripts:
+//!
+//! ```text
+//! tiny-prng --algo pcg-xsl-rr --seed 0x1818729 | RNG_test stdin64
+//! tiny-prng --algo pcg32 --raw-seed 42,54 --format hex --count 3
+//! tiny-prng shuffle --seed 42 < lines.txt
+//! tiny-prng range 1 6 --count 100
+//! ```
+
+use std::io::{self, ErrorKind, Write};
+use std::process::ExitCode;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tiny_prng::seed::SeedSequence;
+use tiny_prng::{vectors, Generator};
+
+mod algo;
+mod args;
+mod commands;
+mod stream;
+
+use args::Args;
+use stream::Stream;
+
+const USAGE: &str = "\
+usage: tiny-prng [stream] [options]
+       tiny-prng shuffle [options] [ITEM...]
+       tiny-prng sample -k K [options] [ITEM...]
+       tiny-prng range LOW HIGH [--count N] [options]
+       tiny-prng normal [--mean M] [--sd S] [--count N] [options]
+
+Writes the outputs of a generator to stdout, as raw words by default.
+shuffle writes the items, or the lines of stdin without them, in a random order,
+sample writes K of them, range writes integers in [LOW, HIGH] and normal writes normal reals,
+one per line and a single one unless --count is given.
+
+options:
+  -a, --algo NAME           the generator (default: pcg-xsl-rr), listed by --list
+  -s, --seed N              the seed, a decimal or 0x-prefixed integer of up to 128 bits
+      --seed-text TEXT      the seed, a string hashed platform-independently
+      --raw-seed N[,N...]   the native seed words of the generator, as in the known-answer files
+      --list                list the generators
+  -h, --help                show this help
+
+options of stream:
+  -n, --count N             the number of the outputs (default: unbounded)
+  -w, --width 32|64         the bits of a word (default: the native width of the generator)
+  -e, --endian little|big   the byte order of the raw words (default: little)
+  -f, --format FORMAT       raw, dec, hex or float (default: raw)
+
+The generator is seeded through SeedSequence, so `--seed N` draws from
+`G::from_seed_sequence(&SeedSequence::from_u128(N))` and `--seed-text T` from `G::from_bytes(T)`.
+`--raw-seed` skips SeedSequence and seeds the generator itself as the files in vectors/ list,
+so `--algo pcg32 --raw-seed 42,54` writes the outputs of the pcg-c demo.
+Without a seed, the seed is taken from the clock and the process ID.
+";
+
+const FLAGS: &[&str] = &["list", "help", "h"];
+/// the options of every command
+const COMMON: &[&str] = &["algo", "a", "seed", "s", "seed-text", "raw-seed", "list", "help", "h"];
+
+/// the subcommands and their own options
+const COMMANDS: &[(&str, &[&str])] = &[
+    ("stream", &["count", "n", "width", "w", "endian", "e", "format", "f"]),
+    ("shuffle", &[]),
+    ("sample", &["k"]),
+    ("range", &["count", "n"]),
+    ("normal", &["count", "n", "mean", "sd"]),
+];
+
+/// The failure of the command.
+enum Error {
+    /// an invalid command line
+    Usage(String),
+    Io(io::Error),
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<String> for Error {
+    fn from(e: String) -> Self {
+        Error::Usage(e)
+    }
+}
+
+/// the seed sequence from `--seed` or `--seed-text`, or from the clock and the process ID without them
+fn seed_sequence(args: &Args) -> Result<SeedSequence, Error> {
+    match (args.value_str(&["seed", "s"]), args.value_str(&["seed-text"])) {
+        (Some(_), Some(_)) => Err(Error::Usage("--seed and --seed-text are exclusive".to_string())),
+        (Some(seed), None) => Ok(SeedSequence::from_u128(args::parse_u128(seed)?)),
+        (None, Some(text)) => Ok(SeedSequence::from_bytes(text)),
+        (None, None) => {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+            let words = [now as u32, (now >> 32) as u32, (now >> 64) as u32, std::process::id()];
+            Ok(SeedSequence::new(&words))
+        }
+    }
+}
+
+/// the generator seeded natively with `--raw-seed`, or through the seed sequence without it
+fn generator(args: &Args, algo: &algo::Algo) -> Result<Box<dyn Generator>, Error> {
+    let raw = match args.value_str(&["raw-seed"]) {
+        Some(raw) => raw,
+        None => return Ok(algo.build(&seed_sequence(args)?)),
+    };
+    if args.value_str(&["seed", "s", "seed-text"]).is_some() {
+        return Err(Error::Usage("--raw-seed, --seed and --seed-text are exclusive".to_string()));
+    }
+    let words = raw.split(',').map(args::parse_u128).collect::<Result<Vec<_>, _>>()?;
+    Ok(vectors::build(algo.name, &words)?)
+}
+
+/// the shape of the stream from the options
+fn stream(args: &Args, algo: &algo::Algo) -> Result<Stream, Error> {
+    let format = args.value_str(&["format", "f"]).unwrap_or("raw");
+    let endian = args.value_str(&["endian", "e"]).unwrap_or("little");
+    let width = args.value::<u32>(&["width", "w"])?.unwrap_or(algo.width);
+    if width != 32 && width != 64 {
+        return Err(Error::Usage(format!("invalid width: {} (32 or 64)", width)));
+    }
+    Ok(Stream {
+        format: format.parse().map_err(|_| format!("invalid format: {}", format))?,
+        width,
+        endian: endian.parse().map_err(|_| format!("invalid byte order: {}", endian))?,
+        count: args.value(&["count", "n"])?,
+    })
+}
+
+fn run(args: Vec<String>) -> Result<(), Error> {
+    let mut args = Args::parse(args, FLAGS)?;
+    if args.flag(&["help", "h"]) {
+        print!("{}", USAGE);
+        return Ok(());
+    }
+    if args.flag(&["list"]) {
+        print!("{}", algo::list());
+        return Ok(());
+    }
+    // the stream is the command without a name
+    let (command, options) = match COMMANDS.iter().find(|(c, _)| args.positional.first().map(String::as_str) == Some(c)) {
+        Some(&found) => found,
+        None => {
+            args.positional.insert(0, "stream".to_string());
+            COMMANDS[0]
+        }
+    };
+    args.check_known(&[COMMON, options].concat())?;
+    let algo = algo::find(args.value_str(&["algo", "a"]).unwrap_or(algo::DEFAULT))?;
+    let mut g = generator(&args, algo)?;
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    match command {
+        "shuffle" => commands::shuffle(&args, g.as_mut(), &mut io::stdin().lock(), &mut out)?,
+        "sample" => commands::sample(&args, g.as_mut(), &mut io::stdin().lock(), &mut out)?,
+        "range" => commands::range(&args, g.as_mut(), &mut out)?,
+        "normal" => commands::normal(&args, g.as_mut(), &mut out)?,
+        _ => {
+            if let Some(arg) = args.positional.get(1) {
+                return Err(Error::Usage(format!("unexpected argument: {}", arg)));
+            }
+            stream::write(g.as_mut(), &stream(&args, algo)?, &mut out)?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run(std::env::args().skip(1).collect()) {
+        Ok(()) => ExitCode::SUCCESS,
+        // the reader closed the pipe, as `head` does, which ends an unbounded stream
+        Err(Error::Io(e)) if e.kind() == ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(Error::Io(e)) => {
+            eprintln!("tiny-prng: {}", e);
+            ExitCode::FAILURE
+        }
+        Err(Error::Usage(e)) => {
+            eprintln!("tiny-prng: {}\n\n{}", e, USAGE);
+            ExitCode::from(2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stream::{Endian, Format};
+
+    fn parse(args: &[&str]) -> Args {
+        Args::parse(args.iter().map(|s| s.to_string()), FLAGS).unwrap()
+    }
+
+    #[test]
+    fn test_seed_sequence() {
+        let s = seed_sequence(&parse(&["--seed", "0x1818729"])).ok().unwrap();
+        assert_eq!(SeedSequence::from_u128(0x1818729), s);
+        let s = seed_sequence(&parse(&["--seed-text", "desert-42"])).ok().unwrap();
+        assert_eq!(SeedSequence::from_bytes("desert-42"), s);
+        assert!(seed_sequence(&parse(&["--seed", "1", "--seed-text", "a"])).is_err());
+        assert!(seed_sequence(&parse(&["--seed", "x"])).is_err());
+        assert!(seed_sequence(&parse(&[])).is_ok());
+    }
+
+    #[test]
+    fn test_raw_seed() {
+        let pcg32 = algo::find("pcg32").unwrap();
+        let mut g = generator(&parse(&["--raw-seed", "42,54"]), pcg32).ok().unwrap();
+        assert_eq!([0xa15c02b7, 0x7b47f409, 0xba1d3330], [g.generate_u32(), g.generate_u32(), g.generate_u32()]);
+        let xorshift128 = algo::find("xorshift128").unwrap();
+        let mut g = generator(&parse(&["--raw-seed", "0x54913331f123bb5159a55e5075bcd15"]), xorshift128).ok().unwrap();
+        assert_eq!(3701687786, g.generate_u32());
+        assert!(generator(&parse(&["--raw-seed", "42"]), pcg32).is_err());
+        assert!(generator(&parse(&["--raw-seed", "42,x"]), pcg32).is_err());
+        assert!(generator(&parse(&["--raw-seed", "1", "--seed", "1"]), xorshift128).is_err());
+        assert!(generator(&parse(&["--seed", "1"]), xorshift128).is_ok());
+    }
+
+    #[test]
+    fn test_stream() {
+        let pcg = algo::find("pcg32").unwrap();
+        let s = stream(&parse(&["-f", "hex", "-e", "big", "-n", "5"]), pcg).ok().unwrap();
+        assert_eq!(Stream { format: Format::Hex, width: 32, endian: Endian::Big, count: Some(5) }, s);
+        let s = stream(&parse(&["--width", "64"]), pcg).ok().unwrap();
+        assert_eq!(Stream { format: Format::Raw, width: 64, endian: Endian::Little, count: None }, s);
+        assert!(stream(&parse(&["--width", "16"]), pcg).is_err());
+        assert!(stream(&parse(&["--format", "octal"]), pcg).is_err());
+        assert!(stream(&parse(&["--endian", "middle"]), pcg).is_err());
+    }
+
+    #[test]
+    fn test_run_errors() {
+        let run = |args: &[&str]| run(args.iter().map(|s| s.to_string()).collect());
+        assert!(matches!(run(&["--algo", "randu"]), Err(Error::Usage(_))));
+        assert!(matches!(run(&["--bogus", "1"]), Err(Error::Usage(_))));
+        assert!(matches!(run(&["extra"]), Err(Error::Usage(_))));
+        assert!(matches!(run(&["stream", "extra"]), Err(Error::Usage(_))));
+        // the options belong to their commands
+        assert!(matches!(run(&["range", "1", "6", "--format", "hex"]), Err(Error::Usage(_))));
+        assert!(matches!(run(&["-k", "3"]), Err(Error::Usage(_))));
+    }
+}
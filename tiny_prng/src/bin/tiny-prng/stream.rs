@@ -0,0 +1,178 @@
+//! The output stream of a generator, as raw words or as text.
+
+use std::io::{self, Write};
+use tiny_prng::Generator;
+
+/// the number of the words generated at once
+const BLOCK: usize = 8192;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Raw,
+    Dec,
+    Hex,
+    Float,
+}
+
+impl std::str::FromStr for Format {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "raw" | "bin" => Ok(Format::Raw),
+            "dec" => Ok(Format::Dec),
+            "hex" => Ok(Format::Hex),
+            "float" | "real" => Ok(Format::Float),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endian {
+    Little,
+    Big,
+}
+
+impl std::str::FromStr for Endian {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "little" | "le" => Ok(Endian::Little),
+            "big" | "be" => Ok(Endian::Big),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The shape of the stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stream {
+    pub format: Format,
+    /// the bits of a word, 32 or 64
+    pub width: u32,
+    pub endian: Endian,
+    /// the number of the outputs, unbounded if `None`
+    pub count: Option<u64>,
+}
+
+macro_rules! write_words {
+    ($g:expr, $stream:expr, $out:expr, $word:ty, $fill:ident) => {{
+        let mut words = vec![0 as $word; BLOCK];
+        let mut bytes = Vec::with_capacity(BLOCK * size_of::<$word>());
+        let mut text = String::new();
+        let mut remaining = $stream.count;
+        while remaining != Some(0) {
+            let n = remaining.map_or(BLOCK, |r| r.min(BLOCK as u64) as usize);
+            let words = &mut words[..n];
+            $g.$fill(words);
+            match $stream.format {
+                Format::Raw => {
+                    bytes.clear();
+                    for w in words.iter() {
+                        match $stream.endian {
+                            Endian::Little => bytes.extend_from_slice(&w.to_le_bytes()),
+                            Endian::Big => bytes.extend_from_slice(&w.to_be_bytes()),
+                        }
+                    }
+                    $out.write_all(&bytes)?;
+                }
+                Format::Dec | Format::Hex => {
+                    text.clear();
+                    for w in words.iter() {
+                        match $stream.format {
+                            Format::Hex => writeln!(text, "{:01$x}", w, 2 * size_of::<$word>()),
+                            _ => writeln!(text, "{}", w),
+                        }
+                        .expect("writing to a string");
+                    }
+                    $out.write_all(text.as_bytes())?;
+                }
+                Format::Float => unreachable!(),
+            }
+            remaining = remaining.map(|r| r - n as u64);
+        }
+    }};
+}
+
+/// write the stream of the generator
+pub fn write<W: Write>(g: &mut dyn Generator, stream: &Stream, out: &mut W) -> io::Result<()> {
+    use std::fmt::Write as _;
+    match (stream.format, stream.width) {
+        (Format::Float, _) => {
+            let mut reals = vec![0.0; BLOCK];
+            let mut text = String::new();
+            let mut remaining = stream.count;
+            while remaining != Some(0) {
+                let n = remaining.map_or(BLOCK, |r| r.min(BLOCK as u64) as usize);
+                match stream.width {
+                    32 => reals[..n].iter_mut().for_each(|r| *r = g.generate_real_f32() as f64),
+                    _ => g.fill_f64(&mut reals[..n]),
+                }
+                text.clear();
+                for r in &reals[..n] {
+                    writeln!(text, "{}", r).expect("writing to a string");
+                }
+                out.write_all(text.as_bytes())?;
+                remaining = remaining.map(|r| r - n as u64);
+            }
+        }
+        (_, 32) => write_words!(g, stream, out, u32, fill_u32),
+        _ => write_words!(g, stream, out, u64, fill_u64),
+    }
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tiny_prng::pcg::PcgXshRr6432;
+    use tiny_prng::xorshift::Xorshift64;
+
+    fn stream(format: Format, width: u32, endian: Endian, count: u64) -> Stream {
+        Stream { format, width, endian, count: Some(count) }
+    }
+
+    #[test]
+    fn test_raw() {
+        let mut out = vec![];
+        write(&mut Xorshift64::with_seed(1), &stream(Format::Raw, 64, Endian::Little, 10000), &mut out).unwrap();
+        let mut x = Xorshift64::with_seed(1);
+        assert_eq!(80000, out.len());
+        assert!(out.chunks_exact(8).all(|c| u64::from_le_bytes(c.try_into().unwrap()) == x.generate()));
+
+        let mut out = vec![];
+        write(&mut PcgXshRr6432::with_seed(1), &stream(Format::Raw, 32, Endian::Big, 3), &mut out).unwrap();
+        let mut p = PcgXshRr6432::with_seed(1);
+        assert_eq!(12, out.len());
+        assert!(out.chunks_exact(4).all(|c| u32::from_be_bytes(c.try_into().unwrap()) == p.generate()));
+    }
+
+    #[test]
+    fn test_text() {
+        let mut out = vec![];
+        write(&mut Xorshift64::with_seed(1), &stream(Format::Dec, 64, Endian::Little, 2), &mut out).unwrap();
+        let mut x = Xorshift64::with_seed(1);
+        assert_eq!(format!("{}\n{}\n", x.generate(), x.generate()), String::from_utf8(out).unwrap());
+
+        let mut out = vec![];
+        write(&mut PcgXshRr6432::with_seed(1), &stream(Format::Hex, 32, Endian::Little, 100), &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(100, text.lines().count());
+        assert!(text.lines().all(|l| l.len() == 8));
+
+        let mut out = vec![];
+        write(&mut Xorshift64::with_seed(1), &stream(Format::Float, 64, Endian::Little, 100), &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let mut x = Xorshift64::with_seed(1);
+        assert!(text.lines().all(|l| l.parse::<f64>().unwrap() == x.generate_real()));
+    }
+
+    #[test]
+    fn test_empty() {
+        let mut out = vec![];
+        write(&mut Xorshift64::with_seed(1), &stream(Format::Raw, 64, Endian::Little, 0), &mut out).unwrap();
+        assert!(out.is_empty());
+    }
+}
@@ -0,0 +1,247 @@
+//! The `distributions` module provides non-uniform sampling on top of any generator in the crate.
+//!
+//! A distribution is a value implementing `Distribution<T>`, which draws samples from a `Generator`.
+//! Distributions with parameters validate them at construction and return an `Error` on invalid ones.
+//!
+//! ```rust
+//! use tiny_prng::distributions::{Distribution, Normal, StandardNormal};
+//! use tiny_prng::xorshift::Xorshift64;
+//!
+//! fn main(){
+//!     let mut x = Xorshift64::with_seed(0x1818729);
+//!     let z: f64 = StandardNormal.sample(&mut x);
+//!     let height = Normal::new(170.0, 6.0).unwrap();
+//!     println!("{} {}", z, height.sample(&mut x));
+//! }
+//! ```
+
+use std::fmt;
+use crate::Generator;
+
+mod binomial;
+mod discrete;
+mod exponential;
+mod gamma;
+mod multivariate;
+mod normal;
+mod poisson;
+mod weighted;
+mod zipf;
+
+pub use binomial::Binomial;
+pub use discrete::{Bernoulli, Geometric, Hypergeometric};
+pub use exponential::{Exponential, StandardExponential};
+pub use gamma::{Beta, ChiSquared, Gamma, StudentT};
+pub use multivariate::{Dirichlet, MultivariateNormal, UnitBall, UnitSimplex, UnitSphere};
+pub use normal::{LogNormal, Normal, Polar, StandardNormal};
+pub use poisson::Poisson;
+pub use weighted::{Weight, WeightedAlias, WeightedIndex};
+pub use zipf::Zipf;
+
+/// Types which draw values of `T` from a generator.
+pub trait Distribution<T> {
+    /// draw a sample with the generator
+    fn sample<G: Generator + ?Sized>(&self, g: &mut G) -> T;
+}
+
+/// The error on the construction of a distribution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// the named parameter is out of the domain of the distribution
+    InvalidParameter(&'static str),
+    /// no weights were given
+    EmptyWeights,
+    /// the weight at the index is negative, NaN or infinite, or makes the sum overflow
+    InvalidWeight(usize),
+    /// every weight is zero
+    AllWeightsZero,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidParameter(name) => write!(f, "invalid parameter: {}", name),
+            Error::EmptyWeights => write!(f, "no weights"),
+            Error::InvalidWeight(index) => write!(f, "invalid weight at index {}", index),
+            Error::AllWeightsZero => write!(f, "all weights are zero"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// natural logarithm of the gamma function for a positive argument
+/// (Stirling series after shifting the argument above 10)
+pub(crate) fn ln_gamma(x: f64) -> f64 {
+    let (mut x, mut shift) = (x, 0.0);
+    while x < 10.0 {
+        shift += x.ln();
+        x += 1.0;
+    }
+    let r = 1.0 / (x * x);
+    let series = (1.0 / 12.0 - r * (1.0 / 360.0 - r * (1.0 / 1260.0 - r / 1680.0))) / x;
+    (x - 0.5) * x.ln() - x + 0.5 * (2.0 * std::f64::consts::PI).ln() + series - shift
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::ln_gamma;
+
+    // Kolmogorov-Smirnov statistic of the samples against the continuous cdf
+    pub(crate) fn ks_statistic(samples: &mut [f64], cdf: impl Fn(f64) -> f64) -> f64 {
+        samples.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let n = samples.len() as f64;
+        let mut d: f64 = 0.0;
+        for (i, &x) in samples.iter().enumerate() {
+            let c = cdf(x);
+            d = d.max((i as f64 + 1.0) / n - c).max(c - i as f64 / n);
+        }
+        d
+    }
+
+    // critical value of the KS statistic at the significance level 0.001
+    pub(crate) fn ks_critical(n: usize) -> f64 {
+        1.949 / (n as f64).sqrt()
+    }
+
+    // chi-squared statistic of the observed counts against the expected counts
+    pub(crate) fn chi_squared(observed: &[usize], expected: &[f64]) -> f64 {
+        observed.iter().zip(expected).map(|(&o, &e)| (o as f64 - e).powi(2) / e).sum()
+    }
+
+    // upper 0.1% point of chi-squared distribution (Wilson-Hilferty approximation)
+    pub(crate) fn chi_squared_critical(df: usize) -> f64 {
+        let k = df as f64;
+        let z = 3.090232;
+        k * (1.0 - 2.0 / (9.0 * k) + z * (2.0 / (9.0 * k)).sqrt()).powi(3)
+    }
+
+    // error function (Abramowitz and Stegun 7.1.26, |error| < 1.5e-7)
+    pub(crate) fn erf(x: f64) -> f64 {
+        let t = 1.0 / (1.0 + 0.3275911 * x.abs());
+        let y = 1.0
+            - t * (0.254829592 + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))))
+                * (-x * x).exp();
+        match x >= 0.0 {
+            true => y,
+            false => -y,
+        }
+    }
+
+    pub(crate) fn normal_cdf(x: f64) -> f64 {
+        0.5 * (1.0 + erf(x / std::f64::consts::SQRT_2))
+    }
+
+    // regularized lower incomplete gamma function P(a, x), i.e. the cdf of Gamma(a, 1)
+    pub(crate) fn gamma_cdf(a: f64, x: f64) -> f64 {
+        if x <= 0.0 {
+            return 0.0;
+        }
+        let front = (a * x.ln() - x - ln_gamma(a)).exp();
+        if x < a + 1.0 {
+            // series expansion
+            let (mut term, mut sum, mut n) = (1.0 / a, 1.0 / a, a);
+            while term.abs() > sum.abs() * 1e-15 {
+                n += 1.0;
+                term *= x / n;
+                sum += term;
+            }
+            front * sum
+        } else {
+            // continued fraction of the upper function (modified Lentz)
+            let tiny = 1e-300;
+            let mut b = x + 1.0 - a;
+            let mut c = 1.0 / tiny;
+            let mut d = 1.0 / b;
+            let mut h = d;
+            for i in 1..1000 {
+                let an = -(i as f64) * (i as f64 - a);
+                b += 2.0;
+                d = an * d + b;
+                d = match d.abs() < tiny { true => tiny, false => d };
+                c = b + an / c;
+                c = match c.abs() < tiny { true => tiny, false => c };
+                d = 1.0 / d;
+                let delta = d * c;
+                h *= delta;
+                if (delta - 1.0).abs() < 1e-15 {
+                    break;
+                }
+            }
+            1.0 - front * h
+        }
+    }
+
+    pub(crate) fn mean_and_variance(samples: &[f64]) -> (f64, f64) {
+        let n = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / n;
+        let var = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        (mean, var)
+    }
+
+    // chi-squared goodness of fit of integer samples against the pmf; returns (statistic, critical value).
+    // The cells are merged from the left until each expects at least 5 samples.
+    pub(crate) fn discrete_fit(samples: &[u64], pmf: impl Fn(u64) -> f64) -> (f64, f64) {
+        let n = samples.len() as f64;
+        let max = *samples.iter().max().unwrap() as usize;
+        let mut counts = vec![0usize; max + 1];
+        for &x in samples {
+            counts[x as usize] += 1;
+        }
+        let (mut observed, mut expected) = (vec![], vec![]);
+        let (mut o, mut e, mut total) = (0, 0.0, 0.0);
+        for (k, &c) in counts.iter().enumerate() {
+            let p = pmf(k as u64);
+            total += p;
+            o += c;
+            e += p * n;
+            if e >= 5.0 {
+                observed.push(o);
+                expected.push(e);
+                (o, e) = (0, 0.0);
+            }
+        }
+        // the mass beyond the largest sample joins the last cell
+        e += (1.0 - total).max(0.0) * n;
+        match expected.last_mut() {
+            Some(last) if e < 5.0 => {
+                *last += e;
+                *observed.last_mut().unwrap() += o;
+            }
+            _ => {
+                observed.push(o);
+                expected.push(e);
+            }
+        }
+        (chi_squared(&observed, &expected), chi_squared_critical(observed.len().max(2) - 1))
+    }
+
+    pub(crate) fn ln_choose(n: u64, k: u64) -> f64 {
+        ln_gamma(n as f64 + 1.0) - ln_gamma(k as f64 + 1.0) - ln_gamma((n - k) as f64 + 1.0)
+    }
+
+    #[test]
+    fn test_gamma_cdf() {
+        // P(1, x) = 1 - exp(-x) and P(0.5, x) = erf(sqrt(x))
+        for x in [0.1f64, 1.0, 2.5, 10.0] {
+            assert!((gamma_cdf(1.0, x) - (1.0 - (-x).exp())).abs() < 1e-12);
+            assert!((gamma_cdf(0.5, x) - erf(x.sqrt())).abs() < 1e-6);
+        }
+        assert!((ln_gamma(5.0) - 24f64.ln()).abs() < 1e-12);
+        assert!((ln_gamma(0.5) - std::f64::consts::PI.sqrt().ln()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_error_display() {
+        assert_eq!("invalid parameter: std_dev", super::Error::InvalidParameter("std_dev").to_string());
+        assert_eq!("invalid weight at index 3", super::Error::InvalidWeight(3).to_string());
+    }
+
+    #[test]
+    fn test_chi_squared_critical() {
+        // tabulated values: 10.83 (1), 29.59 (10), 59.70 (30)
+        assert!((chi_squared_critical(1) - 10.83).abs() < 0.5);
+        assert!((chi_squared_critical(10) - 29.59).abs() < 0.2);
+        assert!((chi_squared_critical(30) - 59.70).abs() < 0.2);
+    }
+}
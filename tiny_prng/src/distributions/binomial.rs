@@ -0,0 +1,283 @@
+//! The binomial distribution.
+//!
+//! A large `n * min(p, 1 - p)` is sampled with BTPE of Kachitvichyanukul and Schmeiser (1988),
+//! "Binomial Random Variate Generation", and a small one with the inversion.
+
+use crate::Generator;
+use super::{Distribution, Error};
+
+/// the smallest `n * min(p, 1 - p)` sampled with BTPE
+const BTPE_THRESHOLD: f64 = 10.0;
+
+/// The binomial distribution of the number of successes in `n` trials with the probability `p`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Binomial {
+    n: u64,
+    p: f64,
+}
+
+impl Binomial {
+    /// construct the distribution; `p` must be in `[0, 1]`
+    pub fn new(n: u64, p: f64) -> Result<Self, Error> {
+        if !(0.0..=1.0).contains(&p) {
+            return Err(Error::InvalidParameter("p"));
+        }
+        Ok(Self { n, p })
+    }
+
+    pub fn n(&self) -> u64 {
+        self.n
+    }
+
+    pub fn p(&self) -> f64 {
+        self.p
+    }
+}
+
+/// inversion for `p <= 0.5` and a small `n * p`
+fn inversion<G: Generator + ?Sized>(g: &mut G, n: u64, p: f64) -> u64 {
+    let q = 1.0 - p;
+    // `ln(1 - p)` by `ln_1p`, as `q.ln()` loses the digits of the tiny `p` this method is chosen for
+    let qn = (n as f64 * (-p).ln_1p()).exp();
+    let np = n as f64 * p;
+    let bound = (n as f64).min(np + 10.0 * (np * q + 1.0).sqrt()) as u64;
+    loop {
+        let mut x = 0;
+        let mut px = qn;
+        let mut u = g.generate_real();
+        while u > px {
+            x += 1;
+            if x > bound {
+                break;
+            }
+            u -= px;
+            px *= (n - x + 1) as f64 * p / (x as f64 * q);
+        }
+        if x <= bound {
+            return x;
+        }
+    }
+}
+
+/// the correction term of Stirling's formula for the final acceptance test
+#[inline]
+fn stirling(a: f64) -> f64 {
+    let a2 = a * a;
+    (13860.0 - (462.0 - (132.0 - (99.0 - 140.0 / a2) / a2) / a2) / a2) / a / 166320.0
+}
+
+/// BTPE for `p <= 0.5` and `n * p >= BTPE_THRESHOLD`
+fn btpe<G: Generator + ?Sized>(g: &mut G, n: u64, p: f64) -> u64 {
+    let nf = n as f64;
+    let q = 1.0 - p;
+    let npq = nf * p * q;
+    let fm = nf * p + p;
+    let m = fm.floor();
+    // the triangle, the parallelograms and the exponential tails of the majorizing function
+    let p1 = (2.195 * npq.sqrt() - 4.6 * q).floor() + 0.5;
+    let xm = m + 0.5;
+    let xl = xm - p1;
+    let xr = xm + p1;
+    let c = 0.134 + 20.5 / (15.3 + m);
+    let a = (fm - xl) / (fm - xl * p);
+    let lambda_l = a * (1.0 + 0.5 * a);
+    let a = (xr - fm) / (xr * q);
+    let lambda_r = a * (1.0 + 0.5 * a);
+    let p2 = p1 * (1.0 + 2.0 * c);
+    let p3 = p2 + c / lambda_l;
+    let p4 = p3 + c / lambda_r;
+
+    loop {
+        let u = g.generate_real() * p4;
+        let mut v = g.generate_real_open();
+        let y;
+        if u <= p1 {
+            // the triangular region is accepted immediately
+            return (xm - p1 * v + u).floor() as u64;
+        } else if u <= p2 {
+            let x = xl + (u - p1) / c;
+            v = v * c + 1.0 - (m - x + 0.5).abs() / p1;
+            if v > 1.0 {
+                continue;
+            }
+            y = x.floor();
+        } else if u <= p3 {
+            let x = (xl + v.ln() / lambda_l).floor();
+            if x < 0.0 {
+                continue;
+            }
+            y = x;
+            v *= (u - p2) * lambda_l;
+        } else {
+            let x = (xr - v.ln() / lambda_r).floor();
+            if x > nf {
+                continue;
+            }
+            y = x;
+            v *= (u - p3) * lambda_r;
+        }
+
+        let k = (y - m).abs();
+        if k <= 20.0 || k >= npq / 2.0 - 1.0 {
+            // evaluate f(y) / f(m) by the recursion
+            let s = p / q;
+            let a = s * (nf + 1.0);
+            let mut f = 1.0;
+            if m < y {
+                let mut i = m + 1.0;
+                while i <= y {
+                    f *= a / i - s;
+                    i += 1.0;
+                }
+            } else if m > y {
+                let mut i = y + 1.0;
+                while i <= m {
+                    f /= a / i - s;
+                    i += 1.0;
+                }
+            }
+            if v <= f {
+                return y as u64;
+            }
+            continue;
+        }
+
+        // squeeze with the bounds of ln(f(y) / f(m))
+        let rho = (k / npq) * ((k * (k / 3.0 + 0.625) + 1.0 / 6.0) / npq + 0.5);
+        let t = -k * k / (2.0 * npq);
+        let ln_v = v.ln();
+        if ln_v < t - rho {
+            return y as u64;
+        }
+        if ln_v > t + rho {
+            continue;
+        }
+        let x1 = y + 1.0;
+        let f1 = m + 1.0;
+        let z = nf + 1.0 - m;
+        let w = nf - y + 1.0;
+        let bound = xm * (f1 / x1).ln()
+            + (nf - m + 0.5) * (z / w).ln()
+            + (y - m) * (w * p / (x1 * q)).ln()
+            + stirling(f1)
+            + stirling(z)
+            + stirling(x1)
+            + stirling(w);
+        if ln_v <= bound {
+            return y as u64;
+        }
+    }
+}
+
+impl Distribution<u64> for Binomial {
+    fn sample<G: Generator + ?Sized>(&self, g: &mut G) -> u64 {
+        if self.p == 0.0 || self.n == 0 {
+            return 0;
+        }
+        if self.p == 1.0 {
+            return self.n;
+        }
+        // sample the rarer outcome and flip it back
+        let p = self.p.min(1.0 - self.p);
+        let k = match self.n as f64 * p < BTPE_THRESHOLD {
+            true => inversion(g, self.n, p),
+            false => btpe(g, self.n, p),
+        };
+        match self.p > 0.5 {
+            true => self.n - k,
+            false => k,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::tests::{discrete_fit, ln_choose, mean_and_variance};
+    use crate::mt64;
+    use crate::xorshift::Xorshift64;
+
+    const COUNT: usize = 100 * 1000;
+
+    fn pmf(n: u64, p: f64) -> impl Fn(u64) -> f64 {
+        move |k| match k <= n {
+            true => (ln_choose(n, k) + k as f64 * p.ln() + (n - k) as f64 * (1.0 - p).ln()).exp(),
+            false => 0.0,
+        }
+    }
+
+    #[test]
+    fn test_binomial_inversion_fit() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        for (n, p) in [(10, 0.3), (100, 0.05), (15, 0.6), (1000, 0.995)] {
+            let d = Binomial::new(n, p).unwrap();
+            let v: Vec<u64> = (0..COUNT).map(|_| d.sample(&mut g)).collect();
+            assert!(v.iter().all(|&k| k <= n));
+            let (chi2, critical) = discrete_fit(&v, pmf(n, p));
+            assert!(chi2 < critical, "Binomial({}, {}): {} >= {}", n, p, chi2, critical);
+        }
+    }
+
+    /// a generator of a fixed double in `[0, 1)`
+    struct Fixed(f64);
+
+    impl Generator for Fixed {
+        fn generate_u32(&mut self) -> u32 {
+            (self.generate_u64() >> 32) as u32
+        }
+
+        fn generate_u64(&mut self) -> u64 {
+            ((self.0 * (1u64 << 53) as f64) as u64) << 11
+        }
+    }
+
+    #[test]
+    fn test_binomial_inversion_tiny_p() {
+        // P(X = 0) = (1 - p)^n, which `(1 - p).ln()` underestimates by about 7.5e-9 here
+        let (n, p) = (1_000_000_000, 1e-10f64);
+        let p0 = (n as f64 * (-p).ln_1p()).exp();
+        assert!((n as f64 * (1.0 - p).ln()).exp() < p0 - 1e-9);
+        // a uniform just below P(X = 0) yields 0, and one just above it does not
+        assert_eq!(0, inversion(&mut Fixed(p0 - 1e-12), n, p));
+        assert_eq!(1, inversion(&mut Fixed(p0 + 1e-12), n, p));
+    }
+
+    #[test]
+    fn test_binomial_btpe_fit() {
+        let mut g = mt64::Mt19937::with_array(vec![0x123, 0x234, 0x345, 0x456]);
+        for (n, p) in [(40, 0.5), (100, 0.3), (1000, 0.02), (5000, 0.75), (100000, 0.4)] {
+            let d = Binomial::new(n, p).unwrap();
+            let v: Vec<u64> = (0..COUNT).map(|_| d.sample(&mut g)).collect();
+            assert!(v.iter().all(|&k| k <= n));
+            let (chi2, critical) = discrete_fit(&v, pmf(n, p));
+            assert!(chi2 < critical, "Binomial({}, {}): {} >= {}", n, p, chi2, critical);
+        }
+    }
+
+    #[test]
+    fn test_binomial_btpe_moments() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        let (n, p) = (1u64 << 40, 0.25);
+        let d = Binomial::new(n, p).unwrap();
+        let v: Vec<f64> = (0..COUNT).map(|_| d.sample(&mut g) as f64).collect();
+        let (mean, var) = mean_and_variance(&v);
+        let (expected_mean, expected_var) = (n as f64 * p, n as f64 * p * (1.0 - p));
+        assert!((mean - expected_mean).abs() < 5.0 * (expected_var / COUNT as f64).sqrt());
+        assert!((var / expected_var - 1.0).abs() < 0.03);
+    }
+
+    #[test]
+    fn test_binomial_degenerate() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        assert_eq!(0, Binomial::new(0, 0.5).unwrap().sample(&mut g));
+        assert_eq!(0, Binomial::new(100, 0.0).unwrap().sample(&mut g));
+        assert_eq!(100, Binomial::new(100, 1.0).unwrap().sample(&mut g));
+    }
+
+    #[test]
+    fn test_binomial_parameters() {
+        assert_eq!(Err(Error::InvalidParameter("p")), Binomial::new(10, -0.1));
+        assert_eq!(Err(Error::InvalidParameter("p")), Binomial::new(10, 1.1));
+        assert_eq!(Err(Error::InvalidParameter("p")), Binomial::new(10, f64::NAN));
+    }
+}
@@ -0,0 +1,261 @@
+//! The Bernoulli, geometric and hypergeometric distributions.
+
+use crate::Generator;
+use super::{ln_gamma, Distribution, Error};
+
+/// the largest population of `Hypergeometric`, as in NumPy; the inversion walks O(sqrt(total)) steps
+const HYPERGEOMETRIC_MAX_TOTAL: u64 = 1_000_000_000;
+
+/// The Bernoulli distribution which yields `true` with the probability `p`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bernoulli {
+    p: f64,
+    /// `p` scaled to 2^64, compared with a 64bit word
+    threshold: u64,
+}
+
+impl Bernoulli {
+    /// construct the distribution; `p` must be in `[0, 1]`
+    pub fn new(p: f64) -> Result<Self, Error> {
+        if !(0.0..=1.0).contains(&p) {
+            return Err(Error::InvalidParameter("p"));
+        }
+        // p == 1 saturates to u64::MAX and is handled on the sampling
+        let threshold = (p * (1u128 << 64) as f64) as u64;
+        Ok(Self { p, threshold })
+    }
+
+    pub fn p(&self) -> f64 {
+        self.p
+    }
+}
+
+impl Distribution<bool> for Bernoulli {
+    fn sample<G: Generator + ?Sized>(&self, g: &mut G) -> bool {
+        match self.threshold {
+            u64::MAX => true,
+            t => g.generate_u64() < t,
+        }
+    }
+}
+
+/// The geometric distribution of the number of failures before the first success with the probability `p`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Geometric {
+    p: f64,
+    /// ln(1 - p)
+    ln_q: f64,
+}
+
+impl Geometric {
+    /// construct the distribution; `p` must be in `(0, 1]`
+    pub fn new(p: f64) -> Result<Self, Error> {
+        if !(p > 0.0 && p <= 1.0) {
+            return Err(Error::InvalidParameter("p"));
+        }
+        Ok(Self { p, ln_q: (-p).ln_1p() })
+    }
+
+    pub fn p(&self) -> f64 {
+        self.p
+    }
+}
+
+impl Distribution<u64> for Geometric {
+    fn sample<G: Generator + ?Sized>(&self, g: &mut G) -> u64 {
+        if self.p == 1.0 {
+            return 0;
+        }
+        // inversion; the cast saturates for the astronomically rare overflow
+        (g.generate_real_open().ln() / self.ln_q).floor() as u64
+    }
+}
+
+/// The hypergeometric distribution of the number of successes in `draws` draws without replacement
+/// from a population of `total` items with `successes` successes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hypergeometric {
+    total: u64,
+    successes: u64,
+    draws: u64,
+    // the reduced problem: `good` items of the sampled kind among `good + bad`, `m` draws
+    good: u64,
+    bad: u64,
+    m: u64,
+    lower: u64,
+    upper: u64,
+    mode: u64,
+    p_mode: f64,
+}
+
+impl Hypergeometric {
+    /// construct the distribution; `total` must not exceed 10^9, and `successes` and `draws` must not exceed `total`
+    pub fn new(total: u64, successes: u64, draws: u64) -> Result<Self, Error> {
+        if total > HYPERGEOMETRIC_MAX_TOTAL {
+            return Err(Error::InvalidParameter("total"));
+        }
+        if successes > total {
+            return Err(Error::InvalidParameter("successes"));
+        }
+        if draws > total {
+            return Err(Error::InvalidParameter("draws"));
+        }
+        // draw the smaller of the sample and its complement, and count the rarer kind
+        let m = draws.min(total - draws);
+        let good = successes.min(total - successes);
+        let bad = total - good;
+        let lower = m.saturating_sub(bad);
+        let upper = m.min(good);
+        let mode = (((m + 1) as f64 * (good + 1) as f64 / (total + 2) as f64) as u64).clamp(lower, upper);
+        let ln_choose = |n: u64, k: u64| ln_gamma(n as f64 + 1.0) - ln_gamma(k as f64 + 1.0) - ln_gamma((n - k) as f64 + 1.0);
+        let p_mode = (ln_choose(good, mode) + ln_choose(bad, m - mode) - ln_choose(total, m)).exp();
+        Ok(Self { total, successes, draws, good, bad, m, lower, upper, mode, p_mode })
+    }
+
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    pub fn successes(&self) -> u64 {
+        self.successes
+    }
+
+    pub fn draws(&self) -> u64 {
+        self.draws
+    }
+
+    /// inversion searching alternately down and up from the mode
+    fn sample_reduced<G: Generator + ?Sized>(&self, g: &mut G) -> u64 {
+        let (good, bad, m) = (self.good as f64, self.bad as f64, self.m as f64);
+        let mut u = g.generate_real();
+        let (mut kl, mut ku) = (self.mode, self.mode);
+        let (mut pl, mut pu) = (self.p_mode, self.p_mode);
+        if u < self.p_mode {
+            return self.mode;
+        }
+        u -= self.p_mode;
+        loop {
+            if kl > self.lower {
+                let k = kl as f64;
+                pl *= k * (bad - m + k) / ((good - k + 1.0) * (m - k + 1.0));
+                kl -= 1;
+                if u < pl {
+                    return kl;
+                }
+                u -= pl;
+            }
+            if ku < self.upper {
+                let k = ku as f64;
+                pu *= (good - k) * (m - k) / ((k + 1.0) * (bad - m + k + 1.0));
+                ku += 1;
+                if u < pu {
+                    return ku;
+                }
+                u -= pu;
+            }
+            if kl == self.lower && ku == self.upper {
+                // the rounding error left a tiny residue
+                return self.mode;
+            }
+        }
+    }
+}
+
+impl Distribution<u64> for Hypergeometric {
+    fn sample<G: Generator + ?Sized>(&self, g: &mut G) -> u64 {
+        let mut k = self.sample_reduced(g);
+        if self.good != self.successes {
+            k = self.m - k;
+        }
+        if self.m != self.draws {
+            k = self.successes - k;
+        }
+        k
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::tests::{discrete_fit, ln_choose, mean_and_variance};
+    use crate::pcg::PcgXslRr;
+    use crate::xorshift::Xorshift64;
+
+    const COUNT: usize = 100 * 1000;
+
+    #[test]
+    fn test_bernoulli() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        let d = Bernoulli::new(0.3).unwrap();
+        let hits = (0..COUNT).filter(|_| d.sample(&mut g)).count();
+        // 4.5 sigma
+        assert!((hits as f64 - 0.3 * COUNT as f64).abs() < 4.5 * (COUNT as f64 * 0.21).sqrt());
+        let d = Bernoulli::new(0.0).unwrap();
+        assert!((0..COUNT).all(|_| !d.sample(&mut g)));
+        let d = Bernoulli::new(1.0).unwrap();
+        assert!((0..COUNT).all(|_| d.sample(&mut g)));
+    }
+
+    #[test]
+    fn test_geometric_fit() {
+        let mut g = PcgXslRr::with_seed(0x1818729182367349);
+        for p in [0.05, 0.5, 0.9] {
+            let d = Geometric::new(p).unwrap();
+            let v: Vec<u64> = (0..COUNT).map(|_| d.sample(&mut g)).collect();
+            let (chi2, critical) = discrete_fit(&v, |k| p * (1.0 - p).powi(k as i32));
+            assert!(chi2 < critical, "Geometric({}): {} >= {}", p, chi2, critical);
+        }
+        let d = Geometric::new(1.0).unwrap();
+        assert_eq!(0, d.sample(&mut g));
+    }
+
+    #[test]
+    fn test_hypergeometric_fit() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        // covers every combination of the reductions
+        for (total, successes, draws) in [(50, 20, 10), (50, 40, 10), (50, 20, 45), (50, 40, 45), (1000, 300, 200)] {
+            let d = Hypergeometric::new(total, successes, draws).unwrap();
+            let v: Vec<u64> = (0..COUNT).map(|_| d.sample(&mut g)).collect();
+            let pmf = |k: u64| match k <= successes && k <= draws && draws - k <= total - successes {
+                true => (ln_choose(successes, k) + ln_choose(total - successes, draws - k) - ln_choose(total, draws)).exp(),
+                false => 0.0,
+            };
+            let (chi2, critical) = discrete_fit(&v, pmf);
+            assert!(chi2 < critical, "Hypergeometric({}, {}, {}): {} >= {}", total, successes, draws, chi2, critical);
+        }
+    }
+
+    #[test]
+    fn test_hypergeometric_large() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        let (total, successes, draws) = (1_000_000u64, 500_000u64, 500_000u64);
+        let d = Hypergeometric::new(total, successes, draws).unwrap();
+        let v: Vec<f64> = (0..COUNT / 10).map(|_| d.sample(&mut g) as f64).collect();
+        let (mean, var) = mean_and_variance(&v);
+        // mean 250000 and variance 62500.06
+        assert!((mean - 250000.0).abs() < 12.0);
+        assert!((var / 62500.0 - 1.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_hypergeometric_degenerate() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        assert_eq!(3, Hypergeometric::new(10, 3, 10).unwrap().sample(&mut g));
+        assert_eq!(0, Hypergeometric::new(10, 0, 5).unwrap().sample(&mut g));
+        assert_eq!(5, Hypergeometric::new(10, 10, 5).unwrap().sample(&mut g));
+        assert_eq!(0, Hypergeometric::new(0, 0, 0).unwrap().sample(&mut g));
+    }
+
+    #[test]
+    fn test_parameters() {
+        assert_eq!(Err(Error::InvalidParameter("p")), Bernoulli::new(1.5));
+        assert_eq!(Err(Error::InvalidParameter("p")), Bernoulli::new(f64::NAN));
+        assert_eq!(Err(Error::InvalidParameter("p")), Geometric::new(0.0));
+        assert_eq!(Err(Error::InvalidParameter("p")), Geometric::new(f64::NAN));
+        assert_eq!(Err(Error::InvalidParameter("successes")), Hypergeometric::new(10, 11, 5));
+        assert_eq!(Err(Error::InvalidParameter("draws")), Hypergeometric::new(10, 5, 11));
+        assert_eq!(Err(Error::InvalidParameter("total")), Hypergeometric::new(HYPERGEOMETRIC_MAX_TOTAL + 1, 5, 5));
+        assert_eq!(Err(Error::InvalidParameter("total")), Hypergeometric::new(u64::MAX, 0, 0));
+        assert!(Hypergeometric::new(HYPERGEOMETRIC_MAX_TOTAL, HYPERGEOMETRIC_MAX_TOTAL / 2, HYPERGEOMETRIC_MAX_TOTAL / 2).is_ok());
+    }
+}
@@ -0,0 +1,150 @@
+//! The exponential distribution sampled with the Ziggurat algorithm.
+//!
+//! The tables follow Marsaglia and Tsang (2000), "The Ziggurat Method for Generating Random Variables",
+//! with 256 layers. The tail beyond `R` is sampled by the memorylessness of the distribution.
+
+use std::sync::OnceLock;
+use crate::{real, Generator};
+use super::{Distribution, Error};
+
+const LAYERS: usize = 256;
+/// the start of the tail
+const R: f64 = 7.69711747013105;
+/// the area of each layer
+const V: f64 = 3.949659822581572e-3;
+
+struct Tables {
+    /// the right edges of the layers
+    x: [f64; LAYERS + 1],
+    /// x[i + 1] / x[i], the probability of the fast path
+    ratio: [f64; LAYERS],
+}
+
+#[inline]
+fn pdf(x: f64) -> f64 {
+    (-x).exp()
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let mut x = [0.0; LAYERS + 1];
+        let mut ratio = [0.0; LAYERS];
+        x[0] = V / pdf(R);
+        x[1] = R;
+        for i in 2..LAYERS {
+            x[i] = -(V / x[i - 1] + pdf(x[i - 1])).ln();
+        }
+        x[LAYERS] = 0.0;
+        for i in 0..LAYERS {
+            ratio[i] = x[i + 1] / x[i];
+        }
+        Tables { x, ratio }
+    })
+}
+
+/// The exponential distribution with the rate 1, sampled with the Ziggurat algorithm.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StandardExponential;
+
+impl Distribution<f64> for StandardExponential {
+    fn sample<G: Generator + ?Sized>(&self, g: &mut G) -> f64 {
+        let t = tables();
+        loop {
+            // the upper 53 bits give the abscissa and the lowest 8 bits the layer
+            let w = g.generate_u64();
+            let u = real::f64_half_open(w);
+            let i = (w & 0xff) as usize;
+            if u < t.ratio[i] {
+                return u * t.x[i];
+            }
+            if i == 0 {
+                return R - g.generate_real_open().ln();
+            }
+            let x = u * t.x[i];
+            if pdf(t.x[i]) + g.generate_real() * (pdf(t.x[i + 1]) - pdf(t.x[i])) < pdf(x) {
+                return x;
+            }
+        }
+    }
+}
+
+/// The exponential distribution with the rate `lambda`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Exponential {
+    lambda: f64,
+}
+
+impl Exponential {
+    /// construct the distribution; `lambda` must be finite and positive
+    pub fn new(lambda: f64) -> Result<Self, Error> {
+        if !lambda.is_finite() || lambda <= 0.0 {
+            return Err(Error::InvalidParameter("lambda"));
+        }
+        Ok(Self { lambda })
+    }
+
+    pub fn lambda(&self) -> f64 {
+        self.lambda
+    }
+}
+
+impl Distribution<f64> for Exponential {
+    fn sample<G: Generator + ?Sized>(&self, g: &mut G) -> f64 {
+        StandardExponential.sample(g) / self.lambda
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::tests::{ks_critical, ks_statistic};
+    use crate::pcg::PcgXslRr;
+    use crate::xorshift::Xorshift64;
+
+    const COUNT: usize = 100 * 1000;
+
+    #[test]
+    fn test_tables() {
+        let t = tables();
+        for i in 1..LAYERS {
+            assert!(t.x[i + 1] < t.x[i]);
+        }
+        for i in 1..LAYERS - 1 {
+            let area = t.x[i] * (pdf(t.x[i + 1]) - pdf(t.x[i]));
+            assert!((area - V).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_standard_exponential_ks() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        let mut v: Vec<f64> = (0..COUNT).map(|_| StandardExponential.sample(&mut g)).collect();
+        assert!(ks_statistic(&mut v, |x| 1.0 - (-x).exp()) < ks_critical(COUNT));
+    }
+
+    #[test]
+    fn test_exponential_ks() {
+        let mut g = PcgXslRr::with_seed(0x1818729182367349);
+        let e = Exponential::new(0.25).unwrap();
+        let mut v: Vec<f64> = (0..COUNT).map(|_| e.sample(&mut g)).collect();
+        assert!(v.iter().all(|&x| x >= 0.0));
+        assert!(ks_statistic(&mut v, |x| 1.0 - (-0.25 * x).exp()) < ks_critical(COUNT));
+    }
+
+    #[test]
+    fn test_standard_exponential_tail() {
+        // P(x > R) = 4.5e-4
+        let mut g = Xorshift64::with_seed(0x1818729);
+        let tail = (0..10 * COUNT).filter(|_| StandardExponential.sample(&mut g) > R).count();
+        assert!(tail > 380 && tail < 530);
+    }
+
+    #[test]
+    fn test_exponential_parameters() {
+        assert_eq!(Err(Error::InvalidParameter("lambda")), Exponential::new(0.0));
+        assert_eq!(Err(Error::InvalidParameter("lambda")), Exponential::new(-1.0));
+        assert_eq!(Err(Error::InvalidParameter("lambda")), Exponential::new(f64::NAN));
+        assert_eq!(Err(Error::InvalidParameter("lambda")), Exponential::new(f64::INFINITY));
+    }
+}
@@ -0,0 +1,275 @@
+//! The gamma distribution and the distributions derived from it.
+//!
+//! Gamma is sampled with the method of Marsaglia and Tsang (2000), "A Simple Method for Generating Gamma Variables",
+//! and the shape below 1 is boosted by `U^(1/shape)`.
+//! Chi-squared, Beta and Student's t are composed from gamma and normal variates.
+
+use crate::Generator;
+use super::{Distribution, Error, StandardNormal};
+
+/// sample Gamma(shape, 1) for a positive shape
+pub(super) fn standard_gamma<G: Generator + ?Sized>(g: &mut G, shape: f64) -> f64 {
+    if shape < 1.0 {
+        let u = g.generate_real_open();
+        return standard_gamma(g, shape + 1.0) * u.powf(1.0 / shape);
+    }
+    let d = shape - 1.0 / 3.0;
+    let c = 1.0 / (9.0 * d).sqrt();
+    loop {
+        let x: f64 = StandardNormal.sample(g);
+        let v = 1.0 + c * x;
+        if v <= 0.0 {
+            continue;
+        }
+        let v = v * v * v;
+        let u = g.generate_real_open();
+        let x2 = x * x;
+        // the squeeze avoids the logarithms in most cases
+        if u < 1.0 - 0.0331 * x2 * x2 {
+            return d * v;
+        }
+        if u.ln() < 0.5 * x2 + d * (1.0 - v + v.ln()) {
+            return d * v;
+        }
+    }
+}
+
+/// ln of a Gamma(shape, 1) variate, which does not underflow for a tiny shape
+pub(super) fn ln_standard_gamma<G: Generator + ?Sized>(g: &mut G, shape: f64) -> f64 {
+    match shape < 1.0 {
+        true => standard_gamma(g, shape + 1.0).ln() + g.generate_real_open().ln() / shape,
+        false => standard_gamma(g, shape).ln(),
+    }
+}
+
+#[inline]
+fn positive(v: f64, name: &'static str) -> Result<f64, Error> {
+    match v.is_finite() && v > 0.0 {
+        true => Ok(v),
+        false => Err(Error::InvalidParameter(name)),
+    }
+}
+
+/// The gamma distribution with the shape `k` and the scale `theta`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gamma {
+    shape: f64,
+    scale: f64,
+}
+
+impl Gamma {
+    /// construct the distribution; `shape` and `scale` must be finite and positive
+    pub fn new(shape: f64, scale: f64) -> Result<Self, Error> {
+        Ok(Self {
+            shape: positive(shape, "shape")?,
+            scale: positive(scale, "scale")?,
+        })
+    }
+
+    pub fn shape(&self) -> f64 {
+        self.shape
+    }
+
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+}
+
+impl Distribution<f64> for Gamma {
+    fn sample<G: Generator + ?Sized>(&self, g: &mut G) -> f64 {
+        standard_gamma(g, self.shape) * self.scale
+    }
+}
+
+/// The chi-squared distribution with `k` degrees of freedom, i.e. Gamma(k/2, 2).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChiSquared {
+    k: f64,
+}
+
+impl ChiSquared {
+    /// construct the distribution; `k` must be finite and positive (not necessarily an integer)
+    pub fn new(k: f64) -> Result<Self, Error> {
+        Ok(Self { k: positive(k, "k")? })
+    }
+
+    pub fn k(&self) -> f64 {
+        self.k
+    }
+}
+
+impl Distribution<f64> for ChiSquared {
+    fn sample<G: Generator + ?Sized>(&self, g: &mut G) -> f64 {
+        2.0 * standard_gamma(g, 0.5 * self.k)
+    }
+}
+
+/// The beta distribution with the shapes `alpha` and `beta`, as `X / (X + Y)` of two gamma variates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Beta {
+    alpha: f64,
+    beta: f64,
+}
+
+impl Beta {
+    /// construct the distribution; `alpha` and `beta` must be finite and positive
+    pub fn new(alpha: f64, beta: f64) -> Result<Self, Error> {
+        Ok(Self {
+            alpha: positive(alpha, "alpha")?,
+            beta: positive(beta, "beta")?,
+        })
+    }
+
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    pub fn beta(&self) -> f64 {
+        self.beta
+    }
+}
+
+impl Distribution<f64> for Beta {
+    fn sample<G: Generator + ?Sized>(&self, g: &mut G) -> f64 {
+        // X / (X + Y) in the log space, as the variates underflow for tiny shapes
+        let ln_x = ln_standard_gamma(g, self.alpha);
+        let ln_y = ln_standard_gamma(g, self.beta);
+        match ln_x.max(ln_y) == f64::NEG_INFINITY {
+            // even the logarithms overflow for shapes near the smallest normal float, where the mass lies
+            // at 0 and 1, and X exceeds Y with the probability alpha / (alpha + beta)
+            true => match g.generate_real() * (self.alpha + self.beta) < self.alpha {
+                true => 1.0,
+                false => 0.0,
+            },
+            false => 1.0 / (1.0 + (ln_y - ln_x).exp()),
+        }
+    }
+}
+
+/// Student's t distribution with `nu` degrees of freedom, as `Z / sqrt(V / nu)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StudentT {
+    nu: f64,
+}
+
+impl StudentT {
+    /// construct the distribution; `nu` must be finite and positive
+    pub fn new(nu: f64) -> Result<Self, Error> {
+        Ok(Self { nu: positive(nu, "nu")? })
+    }
+
+    pub fn nu(&self) -> f64 {
+        self.nu
+    }
+}
+
+impl Distribution<f64> for StudentT {
+    fn sample<G: Generator + ?Sized>(&self, g: &mut G) -> f64 {
+        let z: f64 = StandardNormal.sample(g);
+        let v = 2.0 * standard_gamma(g, 0.5 * self.nu);
+        z / (v / self.nu).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::tests::{gamma_cdf, ks_critical, ks_statistic, mean_and_variance};
+    use crate::mt64;
+    use crate::pcg::PcgXslRr;
+    use crate::xorshift::Xorshift64;
+
+    const COUNT: usize = 100 * 1000;
+
+    #[test]
+    fn test_gamma_ks() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        for (shape, scale) in [(0.3, 1.0), (1.0, 2.0), (2.5, 0.5), (30.0, 1.0)] {
+            let d = Gamma::new(shape, scale).unwrap();
+            let mut v: Vec<f64> = (0..COUNT).map(|_| d.sample(&mut g)).collect();
+            assert!(v.iter().all(|&x| x >= 0.0));
+            let ks = ks_statistic(&mut v, |x| gamma_cdf(shape, x / scale));
+            assert!(ks < ks_critical(COUNT), "Gamma({}, {}): {}", shape, scale, ks);
+        }
+    }
+
+    #[test]
+    fn test_chi_squared_ks() {
+        let mut g = PcgXslRr::with_seed(0x1818729182367349);
+        for k in [1.0, 3.0, 10.0] {
+            let d = ChiSquared::new(k).unwrap();
+            let mut v: Vec<f64> = (0..COUNT).map(|_| d.sample(&mut g)).collect();
+            let ks = ks_statistic(&mut v, |x| gamma_cdf(0.5 * k, 0.5 * x));
+            assert!(ks < ks_critical(COUNT), "ChiSquared({}): {}", k, ks);
+        }
+    }
+
+    #[test]
+    fn test_beta_ks() {
+        // Beta(a, 1) has the cdf x^a, and Beta(1, b) has 1 - (1 - x)^b
+        let mut g = mt64::Mt19937::with_array(vec![0x123, 0x234, 0x345, 0x456]);
+        let d = Beta::new(2.5, 1.0).unwrap();
+        let mut v: Vec<f64> = (0..COUNT).map(|_| d.sample(&mut g)).collect();
+        assert!(ks_statistic(&mut v, |x| x.powf(2.5)) < ks_critical(COUNT));
+        let d = Beta::new(1.0, 0.5).unwrap();
+        let mut v: Vec<f64> = (0..COUNT).map(|_| d.sample(&mut g)).collect();
+        assert!(ks_statistic(&mut v, |x| 1.0 - (1.0 - x).powf(0.5)) < ks_critical(COUNT));
+    }
+
+    #[test]
+    fn test_beta_moments() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        let (a, b) = (2.0, 5.0);
+        let d = Beta::new(a, b).unwrap();
+        let v: Vec<f64> = (0..COUNT).map(|_| d.sample(&mut g)).collect();
+        assert!(v.iter().all(|&x| (0.0..=1.0).contains(&x)));
+        let (mean, var) = mean_and_variance(&v);
+        assert!((mean - a / (a + b)).abs() < 0.003);
+        assert!((var - a * b / ((a + b).powi(2) * (a + b + 1.0))).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_beta_tiny_shapes() {
+        // the mass splits between 0 and 1 by the ratio of the shapes
+        let mut g = Xorshift64::with_seed(0x1818729);
+        for (a, b) in [(1e-300, 1e-300), (1e-300, 3e-300), (1e-310, 1e-310), (1e-3, 1e-3)] {
+            let d = Beta::new(a, b).unwrap();
+            let v: Vec<f64> = (0..COUNT).map(|_| d.sample(&mut g)).collect();
+            assert!(v.iter().all(|&x| (0.0..=1.0).contains(&x)));
+            let ones = v.iter().filter(|&&x| x > 0.5).count() as f64 / COUNT as f64;
+            assert!((ones - a / (a + b)).abs() < 0.01, "Beta({}, {}): {}", a, b, ones);
+        }
+    }
+
+    #[test]
+    fn test_student_t_ks() {
+        // t with 1 degree of freedom is the Cauchy distribution
+        let mut g = Xorshift64::with_seed(0x1818729);
+        let d = StudentT::new(1.0).unwrap();
+        let mut v: Vec<f64> = (0..COUNT).map(|_| d.sample(&mut g)).collect();
+        let cdf = |x: f64| 0.5 + x.atan() / std::f64::consts::PI;
+        assert!(ks_statistic(&mut v, cdf) < ks_critical(COUNT));
+    }
+
+    #[test]
+    fn test_student_t_moments() {
+        let mut g = PcgXslRr::with_seed(0x1818729182367349);
+        let nu = 10.0;
+        let d = StudentT::new(nu).unwrap();
+        let v: Vec<f64> = (0..COUNT).map(|_| d.sample(&mut g)).collect();
+        let (mean, var) = mean_and_variance(&v);
+        assert!(mean.abs() < 0.02);
+        assert!((var - nu / (nu - 2.0)).abs() < 0.04);
+    }
+
+    #[test]
+    fn test_parameters() {
+        assert_eq!(Err(Error::InvalidParameter("shape")), Gamma::new(0.0, 1.0));
+        assert_eq!(Err(Error::InvalidParameter("scale")), Gamma::new(1.0, -1.0));
+        assert_eq!(Err(Error::InvalidParameter("shape")), Gamma::new(f64::NAN, 1.0));
+        assert_eq!(Err(Error::InvalidParameter("k")), ChiSquared::new(0.0));
+        assert_eq!(Err(Error::InvalidParameter("alpha")), Beta::new(-1.0, 1.0));
+        assert_eq!(Err(Error::InvalidParameter("beta")), Beta::new(1.0, f64::INFINITY));
+        assert_eq!(Err(Error::InvalidParameter("nu")), StudentT::new(0.0));
+    }
+}
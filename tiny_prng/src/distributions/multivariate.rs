@@ -0,0 +1,345 @@
+//! Vector-valued distributions.
+//!
+//! The multivariate normal transforms standard normal variates with a Cholesky factor, Dirichlet normalizes
+//! gamma variates, and the uniform distributions on the sphere, the ball and the simplex normalize
+//! normal and exponential variates.
+
+use crate::Generator;
+use super::gamma::ln_standard_gamma;
+use super::{Distribution, Error, StandardExponential, StandardNormal};
+
+/// The multivariate normal distribution N(mean, L L^T) for a lower triangular factor L.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultivariateNormal {
+    mean: Vec<f64>,
+    /// the lower triangular factor in row-major order; the upper triangle is zero
+    factor: Vec<f64>,
+}
+
+impl MultivariateNormal {
+    /// construct the distribution from the Cholesky factor of the covariance, an `n * n` row-major matrix
+    ///
+    /// The upper triangle of `cholesky` is ignored. The mean must be finite and non-empty, and the factor finite
+    /// with a positive diagonal.
+    pub fn new(mean: &[f64], cholesky: &[f64]) -> Result<Self, Error> {
+        let n = mean.len();
+        if n == 0 || mean.iter().any(|m| !m.is_finite()) {
+            return Err(Error::InvalidParameter("mean"));
+        }
+        if cholesky.len() != n * n || cholesky.iter().any(|v| !v.is_finite()) || (0..n).any(|i| cholesky[i * n + i] <= 0.0) {
+            return Err(Error::InvalidParameter("cholesky"));
+        }
+        let mut factor = cholesky.to_vec();
+        for i in 0..n {
+            for j in i + 1..n {
+                factor[i * n + j] = 0.0;
+            }
+        }
+        Ok(Self { mean: mean.to_vec(), factor })
+    }
+
+    /// construct the distribution from the covariance, an `n * n` row-major matrix
+    ///
+    /// Only the lower triangle of `covariance` is read; it must be positive definite.
+    pub fn from_covariance(mean: &[f64], covariance: &[f64]) -> Result<Self, Error> {
+        let n = mean.len();
+        if covariance.len() != n * n || covariance.iter().any(|v| !v.is_finite()) {
+            return Err(Error::InvalidParameter("covariance"));
+        }
+        // the Cholesky-Banachiewicz algorithm
+        let mut l = vec![0.0; n * n];
+        for i in 0..n {
+            for j in 0..=i {
+                let s: f64 = (0..j).map(|k| l[i * n + k] * l[j * n + k]).sum();
+                let v = covariance[i * n + j] - s;
+                match i == j {
+                    true => {
+                        if v.is_nan() || v <= 0.0 {
+                            return Err(Error::InvalidParameter("covariance"));
+                        }
+                        l[i * n + i] = v.sqrt();
+                    }
+                    false => l[i * n + j] = v / l[j * n + j],
+                }
+            }
+        }
+        Self::new(mean, &l)
+    }
+
+    pub fn mean(&self) -> &[f64] {
+        &self.mean
+    }
+
+    /// the lower triangular factor in row-major order
+    pub fn cholesky(&self) -> &[f64] {
+        &self.factor
+    }
+
+    /// the number of the dimensions
+    pub fn dim(&self) -> usize {
+        self.mean.len()
+    }
+}
+
+impl Distribution<Vec<f64>> for MultivariateNormal {
+    fn sample<G: Generator + ?Sized>(&self, g: &mut G) -> Vec<f64> {
+        let n = self.mean.len();
+        let z: Vec<f64> = (0..n).map(|_| StandardNormal.sample(g)).collect();
+        (0..n)
+            .map(|i| self.mean[i] + (0..=i).map(|j| self.factor[i * n + j] * z[j]).sum::<f64>())
+            .collect()
+    }
+}
+
+/// The Dirichlet distribution with the concentrations `alpha`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dirichlet {
+    alpha: Vec<f64>,
+}
+
+impl Dirichlet {
+    /// construct the distribution; `alpha` must be non-empty with every element finite and positive
+    pub fn new(alpha: &[f64]) -> Result<Self, Error> {
+        if alpha.is_empty() || alpha.iter().any(|a| !a.is_finite() || *a <= 0.0) {
+            return Err(Error::InvalidParameter("alpha"));
+        }
+        Ok(Self { alpha: alpha.to_vec() })
+    }
+
+    pub fn alpha(&self) -> &[f64] {
+        &self.alpha
+    }
+}
+
+impl Distribution<Vec<f64>> for Dirichlet {
+    fn sample<G: Generator + ?Sized>(&self, g: &mut G) -> Vec<f64> {
+        // normalize in the log space
+        let ln: Vec<f64> = self.alpha.iter().map(|&a| ln_standard_gamma(g, a)).collect();
+        let max = ln.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let x: Vec<f64> = ln.iter().map(|l| (l - max).exp()).collect();
+        let sum: f64 = x.iter().sum();
+        x.iter().map(|v| v / sum).collect()
+    }
+}
+
+/// validate the number of the dimensions
+fn dimension(dim: usize) -> Result<usize, Error> {
+    match dim {
+        0 => Err(Error::InvalidParameter("dim")),
+        d => Ok(d),
+    }
+}
+
+/// The uniform distribution on the unit sphere in `dim` dimensions, i.e. a random direction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnitSphere {
+    dim: usize,
+}
+
+impl UnitSphere {
+    /// construct the distribution; `dim` must be positive
+    pub fn new(dim: usize) -> Result<Self, Error> {
+        Ok(Self { dim: dimension(dim)? })
+    }
+
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+}
+
+impl Distribution<Vec<f64>> for UnitSphere {
+    fn sample<G: Generator + ?Sized>(&self, g: &mut G) -> Vec<f64> {
+        loop {
+            let v: Vec<f64> = (0..self.dim).map(|_| StandardNormal.sample(g)).collect();
+            let norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+            if norm > 0.0 {
+                return v.iter().map(|x| x / norm).collect();
+            }
+        }
+    }
+}
+
+/// The uniform distribution in the closed unit ball in `dim` dimensions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnitBall {
+    sphere: UnitSphere,
+}
+
+impl UnitBall {
+    /// construct the distribution; `dim` must be positive
+    pub fn new(dim: usize) -> Result<Self, Error> {
+        Ok(Self { sphere: UnitSphere::new(dim)? })
+    }
+
+    pub fn dim(&self) -> usize {
+        self.sphere.dim
+    }
+}
+
+impl Distribution<Vec<f64>> for UnitBall {
+    fn sample<G: Generator + ?Sized>(&self, g: &mut G) -> Vec<f64> {
+        let v = self.sphere.sample(g);
+        // the volume within the radius r grows as r^dim
+        let r = g.generate_real().powf(1.0 / self.sphere.dim as f64);
+        v.iter().map(|x| x * r).collect()
+    }
+}
+
+/// The uniform distribution on the probability simplex with `dim` coordinates, i.e. Dirichlet(1, ..., 1).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnitSimplex {
+    dim: usize,
+}
+
+impl UnitSimplex {
+    /// construct the distribution; `dim` must be positive
+    pub fn new(dim: usize) -> Result<Self, Error> {
+        Ok(Self { dim: dimension(dim)? })
+    }
+
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+}
+
+impl Distribution<Vec<f64>> for UnitSimplex {
+    fn sample<G: Generator + ?Sized>(&self, g: &mut G) -> Vec<f64> {
+        loop {
+            let v: Vec<f64> = (0..self.dim).map(|_| StandardExponential.sample(g)).collect();
+            let sum: f64 = v.iter().sum();
+            if sum > 0.0 {
+                return v.iter().map(|x| x / sum).collect();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::tests::{ks_critical, ks_statistic, mean_and_variance};
+    use crate::mt64;
+    use crate::pcg::PcgXslRr;
+    use crate::xorshift::Xorshift64;
+
+    const COUNT: usize = 100 * 1000;
+
+    /// the i-th coordinates of the samples
+    fn column(samples: &[Vec<f64>], i: usize) -> Vec<f64> {
+        samples.iter().map(|v| v[i]).collect()
+    }
+
+    #[test]
+    fn test_multivariate_normal_moments() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        let mean = [1.0, -2.0, 0.5];
+        let cov = [4.0, 1.2, 0.0, 1.2, 2.0, -0.6, 0.0, -0.6, 1.0];
+        let d = MultivariateNormal::from_covariance(&mean, &cov).unwrap();
+        let samples: Vec<Vec<f64>> = (0..COUNT).map(|_| d.sample(&mut g)).collect();
+        let means: Vec<f64> = (0..3).map(|i| mean_and_variance(&column(&samples, i)).0).collect();
+        for i in 0..3 {
+            assert!((means[i] - mean[i]).abs() < 5.0 * (cov[i * 3 + i] / COUNT as f64).sqrt());
+            for j in 0..3 {
+                let c = samples.iter().map(|v| (v[i] - means[i]) * (v[j] - means[j])).sum::<f64>() / COUNT as f64;
+                let scale = (cov[i * 3 + i] * cov[j * 3 + j]).sqrt();
+                assert!((c - cov[i * 3 + j]).abs() < 0.03 * scale, "cov[{}][{}] = {}", i, j, c);
+            }
+        }
+    }
+
+    #[test]
+    fn test_multivariate_normal_factor() {
+        let d = MultivariateNormal::from_covariance(&[0.0, 0.0], &[4.0, 2.0, 2.0, 2.0]).unwrap();
+        assert_eq!(&[2.0, 0.0, 1.0, 1.0], d.cholesky());
+        // the upper triangle of the given factor is ignored
+        let e = MultivariateNormal::new(&[0.0, 0.0], &[2.0, 9.0, 1.0, 1.0]).unwrap();
+        assert_eq!(d, e);
+        let mut g = PcgXslRr::with_seed(0x1818729182367349);
+        let mut h = PcgXslRr::with_seed(0x1818729182367349);
+        assert_eq!(d.sample(&mut g), e.sample(&mut h));
+    }
+
+    #[test]
+    fn test_dirichlet_moments() {
+        let mut g = mt64::Mt19937::with_array(vec![0x123, 0x234, 0x345, 0x456]);
+        let alpha = [0.5, 2.0, 3.5];
+        let a0: f64 = alpha.iter().sum();
+        let d = Dirichlet::new(&alpha).unwrap();
+        let samples: Vec<Vec<f64>> = (0..COUNT).map(|_| d.sample(&mut g)).collect();
+        assert!(samples.iter().all(|v| (v.iter().sum::<f64>() - 1.0).abs() < 1e-12));
+        for (i, &a) in alpha.iter().enumerate() {
+            let (mean, var) = mean_and_variance(&column(&samples, i));
+            let expected_var = a * (a0 - a) / (a0 * a0 * (a0 + 1.0));
+            assert!((mean - a / a0).abs() < 5.0 * (expected_var / COUNT as f64).sqrt());
+            assert!((var / expected_var - 1.0).abs() < 0.03);
+        }
+    }
+
+    #[test]
+    fn test_dirichlet_tiny_alpha() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        let d = Dirichlet::new(&[1e-3, 1e-3, 1e-3]).unwrap();
+        for _ in 0..1000 {
+            let v = d.sample(&mut g);
+            assert!(v.iter().all(|x| x.is_finite() && *x >= 0.0));
+            assert!((v.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_unit_sphere() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        let d = UnitSphere::new(3).unwrap();
+        let samples: Vec<Vec<f64>> = (0..COUNT).map(|_| d.sample(&mut g)).collect();
+        assert!(samples.iter().all(|v| (v.iter().map(|x| x * x).sum::<f64>() - 1.0).abs() < 1e-12));
+        for i in 0..3 {
+            let (mean, var) = mean_and_variance(&column(&samples, i));
+            assert!(mean.abs() < 5.0 * (1.0 / 3.0 / COUNT as f64).sqrt());
+            assert!((var * 3.0 - 1.0).abs() < 0.03);
+        }
+        // Archimedes: a coordinate on the 2-sphere is uniform in [-1, 1]
+        let mut x = column(&samples, 0);
+        assert!(ks_statistic(&mut x, |t| (t + 1.0) / 2.0) < ks_critical(COUNT));
+        let one = UnitSphere::new(1).unwrap().sample(&mut g);
+        assert_eq!(1.0, one[0].abs());
+    }
+
+    #[test]
+    fn test_unit_ball() {
+        let mut g = PcgXslRr::with_seed(0x1818729182367349);
+        let d = UnitBall::new(4).unwrap();
+        // the radius to the 4th is uniform in [0, 1)
+        let mut r: Vec<f64> = (0..COUNT)
+            .map(|_| d.sample(&mut g).iter().map(|x| x * x).sum::<f64>().powi(2))
+            .collect();
+        assert!(r.iter().all(|&v| v <= 1.0 + 1e-12));
+        assert!(ks_statistic(&mut r, |t| t) < ks_critical(COUNT));
+    }
+
+    #[test]
+    fn test_unit_simplex() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        let d = UnitSimplex::new(4).unwrap();
+        let samples: Vec<Vec<f64>> = (0..COUNT).map(|_| d.sample(&mut g)).collect();
+        assert!(samples.iter().all(|v| v.iter().all(|&x| x >= 0.0) && (v.iter().sum::<f64>() - 1.0).abs() < 1e-12));
+        // a coordinate is Beta(1, 3)
+        let mut x = column(&samples, 2);
+        assert!(ks_statistic(&mut x, |t| 1.0 - (1.0 - t).powi(3)) < ks_critical(COUNT));
+        assert_eq!(vec![1.0], UnitSimplex::new(1).unwrap().sample(&mut g));
+    }
+
+    #[test]
+    fn test_parameters() {
+        assert_eq!(Err(Error::InvalidParameter("mean")), MultivariateNormal::new(&[], &[]));
+        assert_eq!(Err(Error::InvalidParameter("mean")), MultivariateNormal::new(&[f64::NAN], &[1.0]));
+        assert_eq!(Err(Error::InvalidParameter("cholesky")), MultivariateNormal::new(&[0.0, 0.0], &[1.0, 0.0, 1.0]));
+        assert_eq!(Err(Error::InvalidParameter("cholesky")), MultivariateNormal::new(&[0.0, 0.0], &[1.0, 0.0, 0.0, 0.0]));
+        assert_eq!(Err(Error::InvalidParameter("covariance")), MultivariateNormal::from_covariance(&[0.0, 0.0], &[1.0, 2.0, 2.0, 1.0]));
+        assert_eq!(Err(Error::InvalidParameter("covariance")), MultivariateNormal::from_covariance(&[0.0], &[f64::NAN]));
+        assert_eq!(Err(Error::InvalidParameter("alpha")), Dirichlet::new(&[]));
+        assert_eq!(Err(Error::InvalidParameter("alpha")), Dirichlet::new(&[1.0, 0.0]));
+        assert_eq!(Err(Error::InvalidParameter("dim")), UnitSphere::new(0));
+        assert_eq!(Err(Error::InvalidParameter("dim")), UnitBall::new(0));
+        assert_eq!(Err(Error::InvalidParameter("dim")), UnitSimplex::new(0));
+    }
+}
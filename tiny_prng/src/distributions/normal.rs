@@ -0,0 +1,314 @@
+//! The normal distribution sampled with the Ziggurat algorithm.
+//!
+//! The tables follow ZIGNOR of Doornik (2005), "An Improved Ziggurat Method to Generate Normal Random Samples",
+//! with 128 layers. The tail beyond `R` is sampled with Marsaglia's method.
+
+use std::sync::OnceLock;
+use crate::{real, Generator};
+use super::{Distribution, Error};
+
+const LAYERS: usize = 128;
+/// the start of the tail
+const R: f64 = 3.442619855899;
+/// the area of each layer
+const V: f64 = 9.91256303526217e-3;
+
+struct Tables {
+    /// the right edges of the layers
+    x: [f64; LAYERS + 1],
+    /// x[i + 1] / x[i], the probability of the fast path
+    ratio: [f64; LAYERS],
+}
+
+#[inline]
+fn pdf(x: f64) -> f64 {
+    (-0.5 * x * x).exp()
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let mut x = [0.0; LAYERS + 1];
+        let mut ratio = [0.0; LAYERS];
+        x[0] = V / pdf(R);
+        x[1] = R;
+        for i in 2..LAYERS {
+            x[i] = (-2.0 * (V / x[i - 1] + pdf(x[i - 1])).ln()).sqrt();
+        }
+        x[LAYERS] = 0.0;
+        for i in 0..LAYERS {
+            ratio[i] = x[i + 1] / x[i];
+        }
+        Tables { x, ratio }
+    })
+}
+
+/// sample the tail beyond `R`
+#[inline]
+fn tail<G: Generator + ?Sized>(g: &mut G, negative: bool) -> f64 {
+    loop {
+        let x = g.generate_real_open().ln() / R;
+        let y = g.generate_real_open().ln();
+        if -2.0 * y >= x * x {
+            return match negative {
+                true => x - R,
+                false => R - x,
+            };
+        }
+    }
+}
+
+/// The standard normal distribution N(0, 1) sampled with the Ziggurat algorithm.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StandardNormal;
+
+impl Distribution<f64> for StandardNormal {
+    fn sample<G: Generator + ?Sized>(&self, g: &mut G) -> f64 {
+        let t = tables();
+        loop {
+            // the upper 53 bits give the abscissa and the lowest 7 bits the layer
+            let w = g.generate_u64();
+            let u = 2.0 * real::f64_half_open(w) - 1.0;
+            let i = (w & 0x7f) as usize;
+            if u.abs() < t.ratio[i] {
+                return u * t.x[i];
+            }
+            if i == 0 {
+                return tail(g, u < 0.0);
+            }
+            let x = u * t.x[i];
+            let f0 = (-0.5 * (t.x[i] * t.x[i] - x * x)).exp();
+            let f1 = (-0.5 * (t.x[i + 1] * t.x[i + 1] - x * x)).exp();
+            if f1 + g.generate_real() * (f0 - f1) < 1.0 {
+                return x;
+            }
+        }
+    }
+}
+
+/// The standard normal distribution N(0, 1) sampled with Marsaglia's polar method.
+///
+/// It is slower than `StandardNormal` but needs no table, and serves as the reference implementation.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Polar;
+
+impl Distribution<f64> for Polar {
+    fn sample<G: Generator + ?Sized>(&self, g: &mut G) -> f64 {
+        loop {
+            let u = 2.0 * g.generate_real() - 1.0;
+            let v = 2.0 * g.generate_real() - 1.0;
+            let s = u * u + v * v;
+            if s < 1.0 && s > 0.0 {
+                return u * (-2.0 * s.ln() / s).sqrt();
+            }
+        }
+    }
+}
+
+/// The normal distribution N(mean, std_dev^2).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Normal {
+    mean: f64,
+    std_dev: f64,
+}
+
+impl Normal {
+    /// construct the distribution; `mean` must be finite and `std_dev` finite and non-negative
+    pub fn new(mean: f64, std_dev: f64) -> Result<Self, Error> {
+        if !mean.is_finite() {
+            return Err(Error::InvalidParameter("mean"));
+        }
+        if !std_dev.is_finite() || std_dev < 0.0 {
+            return Err(Error::InvalidParameter("std_dev"));
+        }
+        Ok(Self { mean, std_dev })
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    pub fn std_dev(&self) -> f64 {
+        self.std_dev
+    }
+}
+
+impl Distribution<f64> for Normal {
+    fn sample<G: Generator + ?Sized>(&self, g: &mut G) -> f64 {
+        self.mean + self.std_dev * StandardNormal.sample(g)
+    }
+}
+
+/// The log-normal distribution, i.e. `exp(X)` for X ~ N(mu, sigma^2).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LogNormal {
+    normal: Normal,
+}
+
+impl LogNormal {
+    /// construct the distribution; `mu` must be finite and `sigma` finite and non-negative
+    pub fn new(mu: f64, sigma: f64) -> Result<Self, Error> {
+        match Normal::new(mu, sigma) {
+            Ok(normal) => Ok(Self { normal }),
+            Err(Error::InvalidParameter("mean")) => Err(Error::InvalidParameter("mu")),
+            Err(_) => Err(Error::InvalidParameter("sigma")),
+        }
+    }
+
+    pub fn mu(&self) -> f64 {
+        self.normal.mean
+    }
+
+    pub fn sigma(&self) -> f64 {
+        self.normal.std_dev
+    }
+}
+
+impl Distribution<f64> for LogNormal {
+    fn sample<G: Generator + ?Sized>(&self, g: &mut G) -> f64 {
+        self.normal.sample(g).exp()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::tests::{chi_squared, chi_squared_critical, ks_critical, ks_statistic, normal_cdf};
+    use crate::mt64;
+    use crate::pcg::PcgXshRr6432;
+    use crate::xorshift::Xorshift64;
+
+    const COUNT: usize = 100 * 1000;
+
+    #[test]
+    fn test_tables() {
+        let t = tables();
+        // the layers shrink towards the top and the top layer ends at 0
+        for i in 1..LAYERS {
+            assert!(t.x[i + 1] < t.x[i]);
+        }
+        assert!(t.x[LAYERS - 1] > 0.0 && t.x[LAYERS - 1] < 0.3);
+        // every layer has the area V
+        for i in 1..LAYERS - 1 {
+            let area = t.x[i] * (pdf(t.x[i + 1]) - pdf(t.x[i]));
+            assert!((area - V).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_standard_normal_ks() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        let mut v: Vec<f64> = (0..COUNT).map(|_| StandardNormal.sample(&mut g)).collect();
+        assert!(ks_statistic(&mut v, normal_cdf) < ks_critical(COUNT));
+    }
+
+    #[test]
+    fn test_polar_ks() {
+        let mut g = PcgXshRr6432::with_seed(0x1818729182367349);
+        let mut v: Vec<f64> = (0..COUNT).map(|_| Polar.sample(&mut g)).collect();
+        assert!(ks_statistic(&mut v, normal_cdf) < ks_critical(COUNT));
+    }
+
+    #[test]
+    fn test_standard_normal_chi_squared() {
+        // 40 bins over [-4, 4) and two bins for the tails
+        let mut g = mt64::Mt19937::with_array(vec![0x123, 0x234, 0x345, 0x456]);
+        let mut observed = [0usize; 42];
+        for _ in 0..COUNT {
+            let x = StandardNormal.sample(&mut g);
+            let bin = match x {
+                x if x < -4.0 => 0,
+                x if x >= 4.0 => 41,
+                x => 1 + ((x + 4.0) * 5.0) as usize,
+            };
+            observed[bin] += 1;
+        }
+        let mut expected = [0.0; 42];
+        expected[0] = normal_cdf(-4.0) * COUNT as f64;
+        expected[41] = (1.0 - normal_cdf(4.0)) * COUNT as f64;
+        for (i, e) in expected.iter_mut().enumerate().take(41).skip(1) {
+            let lower = -4.0 + (i - 1) as f64 * 0.2;
+            *e = (normal_cdf(lower + 0.2) - normal_cdf(lower)) * COUNT as f64;
+        }
+        // merge the sparse tails into their neighbours
+        let observed = [&[observed[0] + observed[1]], &observed[2..40], &[observed[40] + observed[41]]].concat();
+        let expected = [&[expected[0] + expected[1]], &expected[2..40], &[expected[40] + expected[41]]].concat();
+        assert!(chi_squared(&observed, &expected) < chi_squared_critical(observed.len() - 1));
+    }
+
+    #[test]
+    fn test_standard_normal_tail() {
+        // P(|x| > R) = 5.7e-4, so the tail must be sampled on both sides
+        let mut g = Xorshift64::with_seed(0x1818729);
+        let (mut lower, mut upper) = (0, 0);
+        for _ in 0..10 * COUNT {
+            let x = StandardNormal.sample(&mut g);
+            if x < -R {
+                lower += 1;
+            }
+            if x > R {
+                upper += 1;
+            }
+        }
+        assert!(lower > 200 && lower < 400);
+        assert!(upper > 200 && upper < 400);
+    }
+
+    #[test]
+    fn test_normal_moments() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        let n = Normal::new(-3.0, 2.5).unwrap();
+        let v: Vec<f64> = (0..COUNT).map(|_| n.sample(&mut g)).collect();
+        let mean = v.iter().sum::<f64>() / COUNT as f64;
+        let var = v.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (COUNT - 1) as f64;
+        assert!((mean + 3.0).abs() < 0.03);
+        assert!((var.sqrt() - 2.5).abs() < 0.03);
+    }
+
+    #[test]
+    fn test_log_normal_ks() {
+        let mut g = PcgXshRr6432::with_seed(0x1818729182367349);
+        let d = LogNormal::new(0.5, 0.75).unwrap();
+        let mut v: Vec<f64> = (0..COUNT).map(|_| d.sample(&mut g)).collect();
+        assert!(v.iter().all(|&x| x > 0.0));
+        assert!(ks_statistic(&mut v, |x| normal_cdf((x.ln() - 0.5) / 0.75)) < ks_critical(COUNT));
+    }
+
+    #[test]
+    fn test_log_normal_parameters() {
+        assert_eq!(Err(Error::InvalidParameter("mu")), LogNormal::new(f64::NAN, 1.0));
+        assert_eq!(Err(Error::InvalidParameter("sigma")), LogNormal::new(0.0, -1.0));
+    }
+
+    #[test]
+    fn test_normal_parameters() {
+        assert!(Normal::new(0.0, 0.0).is_ok());
+        assert_eq!(Err(Error::InvalidParameter("std_dev")), Normal::new(0.0, -1.0));
+        assert_eq!(Err(Error::InvalidParameter("std_dev")), Normal::new(0.0, f64::NAN));
+        assert_eq!(Err(Error::InvalidParameter("mean")), Normal::new(f64::INFINITY, 1.0));
+    }
+
+    #[bench]
+    fn bench_standard_normal_1mil(b: &mut test::Bencher) {
+        b.iter(|| {
+            let mut g = Xorshift64::with_seed(13378593);
+            let mut v = 0.0;
+            for _ in 0..COUNT * 10 {
+                v = StandardNormal.sample(&mut g);
+            }
+            println!("{}", v);
+        })
+    }
+
+    #[bench]
+    fn bench_polar_1mil(b: &mut test::Bencher) {
+        b.iter(|| {
+            let mut g = Xorshift64::with_seed(13378593);
+            let mut v = 0.0;
+            for _ in 0..COUNT * 10 {
+                v = Polar.sample(&mut g);
+            }
+            println!("{}", v);
+        })
+    }
+}
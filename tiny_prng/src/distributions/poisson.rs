@@ -0,0 +1,134 @@
+//! The Poisson distribution.
+//!
+//! A large mean is sampled with PTRS of Hörmann (1993), "The transformed rejection method for generating
+//! Poisson random variables", and a small one with the multiplication of uniforms.
+
+use crate::Generator;
+use super::{ln_gamma, Distribution, Error};
+
+/// the smallest mean sampled with PTRS
+const PTRS_THRESHOLD: f64 = 10.0;
+
+/// The Poisson distribution with the mean `lambda`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Poisson {
+    lambda: f64,
+    /// exp(-lambda) for the multiplication
+    exp_lambda: f64,
+    /// the constants of PTRS for a large mean
+    ptrs: Option<Ptrs>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Ptrs {
+    ln_lambda: f64,
+    a: f64,
+    b: f64,
+    ln_inv_alpha: f64,
+    vr: f64,
+}
+
+impl Poisson {
+    /// construct the distribution; `lambda` must be positive and below 1e18
+    pub fn new(lambda: f64) -> Result<Self, Error> {
+        // keeps the samples far from the saturation of u64
+        if !lambda.is_finite() || lambda <= 0.0 || lambda >= 1e18 {
+            return Err(Error::InvalidParameter("lambda"));
+        }
+        let ptrs = match lambda < PTRS_THRESHOLD {
+            true => None,
+            false => {
+                let b = 0.931 + 2.53 * lambda.sqrt();
+                Some(Ptrs {
+                    ln_lambda: lambda.ln(),
+                    a: -0.059 + 0.02483 * b,
+                    b,
+                    ln_inv_alpha: (1.1239 + 1.1328 / (b - 3.4)).ln(),
+                    vr: 0.9277 - 3.6224 / (b - 2.0),
+                })
+            }
+        };
+        Ok(Self { lambda, exp_lambda: (-lambda).exp(), ptrs })
+    }
+
+    pub fn lambda(&self) -> f64 {
+        self.lambda
+    }
+}
+
+impl Distribution<u64> for Poisson {
+    fn sample<G: Generator + ?Sized>(&self, g: &mut G) -> u64 {
+        let c = match self.ptrs {
+            Some(c) => c,
+            None => {
+                let mut x = 0;
+                let mut prod = g.generate_real();
+                while prod > self.exp_lambda {
+                    x += 1;
+                    prod *= g.generate_real();
+                }
+                return x;
+            }
+        };
+        loop {
+            let u = g.generate_real() - 0.5;
+            let v = g.generate_real_open();
+            let us = 0.5 - u.abs();
+            let k = ((2.0 * c.a / us + c.b) * u + self.lambda + 0.43).floor();
+            if us >= 0.07 && v <= c.vr {
+                return k as u64;
+            }
+            if k < 0.0 || (us < 0.013 && v > us) {
+                continue;
+            }
+            let lhs = v.ln() + c.ln_inv_alpha - (c.a / (us * us) + c.b).ln();
+            if lhs <= -self.lambda + k * c.ln_lambda - ln_gamma(k + 1.0) {
+                return k as u64;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::tests::{discrete_fit, mean_and_variance};
+    use crate::pcg::PcgXslRr;
+    use crate::xorshift::Xorshift64;
+
+    const COUNT: usize = 100 * 1000;
+
+    fn pmf(lambda: f64) -> impl Fn(u64) -> f64 {
+        move |k| (k as f64 * lambda.ln() - lambda - ln_gamma(k as f64 + 1.0)).exp()
+    }
+
+    #[test]
+    fn test_poisson_fit() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        for lambda in [0.1, 1.0, 4.5, 9.99, 10.0, 33.3, 500.0, 12345.6] {
+            let d = Poisson::new(lambda).unwrap();
+            let v: Vec<u64> = (0..COUNT).map(|_| d.sample(&mut g)).collect();
+            let (chi2, critical) = discrete_fit(&v, pmf(lambda));
+            assert!(chi2 < critical, "Poisson({}): {} >= {}", lambda, chi2, critical);
+        }
+    }
+
+    #[test]
+    fn test_poisson_moments() {
+        let mut g = PcgXslRr::with_seed(0x1818729182367349);
+        let lambda = 1e9;
+        let d = Poisson::new(lambda).unwrap();
+        let v: Vec<f64> = (0..COUNT).map(|_| d.sample(&mut g) as f64).collect();
+        let (mean, var) = mean_and_variance(&v);
+        assert!((mean - lambda).abs() < 5.0 * (lambda / COUNT as f64).sqrt());
+        assert!((var / lambda - 1.0).abs() < 0.03);
+    }
+
+    #[test]
+    fn test_poisson_parameters() {
+        assert_eq!(Err(Error::InvalidParameter("lambda")), Poisson::new(0.0));
+        assert_eq!(Err(Error::InvalidParameter("lambda")), Poisson::new(-1.0));
+        assert_eq!(Err(Error::InvalidParameter("lambda")), Poisson::new(f64::NAN));
+        assert_eq!(Err(Error::InvalidParameter("lambda")), Poisson::new(f64::INFINITY));
+    }
+}
@@ -0,0 +1,261 @@
+//! Weighted choice of indices.
+//!
+//! `WeightedAlias` builds the alias table of Vose (1991), "A linear algorithm for generating random numbers
+//! with a given distribution", once and draws in O(1). `WeightedIndex` keeps the cumulative weights and draws
+//! by binary search in O(log n), which suits weights updated between the draws.
+
+use crate::generator::{Generator, UniformInt};
+use super::{Distribution, Error};
+
+/// Types accepted as weights.
+pub trait Weight: Copy {
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! impl_weight {
+    ($($t:ty),*) => {
+        $(
+            impl Weight for $t {
+                #[inline]
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
+}
+
+impl_weight!(f32, f64, u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// validate the weights and convert them to doubles, returning them with their sum
+fn validate<W: Weight>(weights: &[W]) -> Result<(Vec<f64>, f64), Error> {
+    if weights.is_empty() {
+        return Err(Error::EmptyWeights);
+    }
+    let mut total = 0.0;
+    let mut converted = Vec::with_capacity(weights.len());
+    for (i, w) in weights.iter().enumerate() {
+        let w = w.to_f64();
+        if !w.is_finite() || w < 0.0 {
+            return Err(Error::InvalidWeight(i));
+        }
+        total += w;
+        // the weight whose addition overflows the sum is the invalid one
+        if !total.is_finite() {
+            return Err(Error::InvalidWeight(i));
+        }
+        converted.push(w);
+    }
+    if total == 0.0 {
+        return Err(Error::AllWeightsZero);
+    }
+    Ok((converted, total))
+}
+
+/// The weighted choice of an index with the alias table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeightedAlias {
+    /// the probability to keep the drawn column
+    prob: Vec<f64>,
+    /// the index which fills up the rest of the column
+    alias: Vec<usize>,
+}
+
+impl WeightedAlias {
+    /// build the table from the weights; they must be finite, non-negative and not all zero
+    pub fn new<W: Weight>(weights: &[W]) -> Result<Self, Error> {
+        let (weights, total) = validate(weights)?;
+        let n = weights.len();
+        let mut scaled: Vec<f64> = weights.iter().map(|w| w * n as f64 / total).collect();
+        let mut prob = vec![0.0; n];
+        let mut alias = vec![0; n];
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) = (0..n).partition(|&i| scaled[i] < 1.0);
+        while let (Some(&s), Some(&l)) = (small.last(), large.last()) {
+            small.pop();
+            prob[s] = scaled[s];
+            alias[s] = l;
+            scaled[l] = (scaled[l] + scaled[s]) - 1.0;
+            if scaled[l] < 1.0 {
+                large.pop();
+                small.push(l);
+            }
+        }
+        // the remaining columns are full up to the rounding error
+        for i in large.into_iter().chain(small) {
+            prob[i] = 1.0;
+            alias[i] = i;
+        }
+        Ok(Self { prob, alias })
+    }
+
+    /// the number of the indices
+    pub fn len(&self) -> usize {
+        self.prob.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prob.is_empty()
+    }
+}
+
+impl Distribution<usize> for WeightedAlias {
+    fn sample<G: Generator + ?Sized>(&self, g: &mut G) -> usize {
+        let i = usize::sample_below(g, self.prob.len());
+        match g.generate_real() < self.prob[i] {
+            true => i,
+            false => self.alias[i],
+        }
+    }
+}
+
+/// The weighted choice of an index with the binary search on the cumulative weights.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeightedIndex {
+    weights: Vec<f64>,
+    cumulative: Vec<f64>,
+}
+
+impl WeightedIndex {
+    /// build the cumulative weights; they must be finite, non-negative and not all zero
+    pub fn new<W: Weight>(weights: &[W]) -> Result<Self, Error> {
+        let (weights, _) = validate(weights)?;
+        let mut s = Self { weights, cumulative: vec![] };
+        s.accumulate();
+        Ok(s)
+    }
+
+    fn accumulate(&mut self) {
+        let mut sum = 0.0;
+        self.cumulative = self.weights.iter().map(|w| {
+            sum += w;
+            sum
+        }).collect();
+    }
+
+    /// replace the weights at the indices; the table is left unchanged on an error
+    ///
+    /// Panics if an index is out of bounds.
+    pub fn update_weights<W: Weight>(&mut self, updates: &[(usize, W)]) -> Result<(), Error> {
+        let mut weights = self.weights.clone();
+        for &(i, w) in updates {
+            weights[i] = w.to_f64();
+        }
+        let (weights, _) = validate(&weights)?;
+        self.weights = weights;
+        self.accumulate();
+        Ok(())
+    }
+
+    /// the sum of the weights
+    pub fn total(&self) -> f64 {
+        self.cumulative[self.cumulative.len() - 1]
+    }
+
+    /// the number of the indices
+    pub fn len(&self) -> usize {
+        self.weights.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+}
+
+impl Distribution<usize> for WeightedIndex {
+    fn sample<G: Generator + ?Sized>(&self, g: &mut G) -> usize {
+        let x = g.generate_real() * self.total();
+        // the first index whose cumulative weight exceeds x, which skips the zero weights
+        self.cumulative.partition_point(|&c| c <= x).min(self.cumulative.len() - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::tests::{chi_squared, chi_squared_critical};
+    use crate::pcg::PcgXslRr;
+    use crate::xorshift::Xorshift64;
+
+    const COUNT: usize = 100 * 1000;
+
+    fn fit<D: Distribution<usize>>(d: &D, weights: &[f64], g: &mut impl Generator) -> bool {
+        let total: f64 = weights.iter().sum();
+        let mut observed = vec![0; weights.len()];
+        for _ in 0..COUNT {
+            observed[d.sample(g)] += 1;
+        }
+        // the zero weights must never be drawn and are left out of the statistic
+        let (observed, expected): (Vec<usize>, Vec<f64>) = observed
+            .iter()
+            .zip(weights)
+            .filter_map(|(&o, &w)| match w == 0.0 {
+                true => {
+                    assert_eq!(0, o);
+                    None
+                }
+                false => Some((o, w / total * COUNT as f64)),
+            })
+            .unzip();
+        chi_squared(&observed, &expected) < chi_squared_critical(observed.len() - 1)
+    }
+
+    #[test]
+    fn test_weighted_alias_fit() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        let weights = [1.0, 0.0, 2.0, 3.5, 0.25, 10.0, 0.0, 4.0];
+        assert!(fit(&WeightedAlias::new(&weights).unwrap(), &weights, &mut g));
+        let weights: Vec<f64> = (0..1000).map(|i| ((i * 7919) % 113) as f64).collect();
+        assert!(fit(&WeightedAlias::new(&weights).unwrap(), &weights, &mut g));
+    }
+
+    #[test]
+    fn test_weighted_index_fit() {
+        let mut g = PcgXslRr::with_seed(0x1818729182367349);
+        let weights = [0.0, 1.0, 0.0, 2.0, 3.5, 0.25, 10.0, 4.0, 0.0];
+        assert!(fit(&WeightedIndex::new(&weights).unwrap(), &weights, &mut g));
+    }
+
+    #[test]
+    fn test_weighted_index_update() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        let mut d = WeightedIndex::new(&[1u32, 2, 3, 4]).unwrap();
+        d.update_weights(&[(0, 0), (3, 10)]).unwrap();
+        assert_eq!(15.0, d.total());
+        assert!(fit(&d, &[0.0, 2.0, 3.0, 10.0], &mut g));
+        // an invalid update leaves the weights unchanged
+        assert_eq!(Err(Error::AllWeightsZero), d.update_weights(&[(1, 0), (2, 0), (3, 0)]));
+        assert_eq!(Err(Error::InvalidWeight(2)), d.update_weights(&[(2, -1)]));
+        assert_eq!(15.0, d.total());
+    }
+
+    #[test]
+    fn test_integer_weights() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        let d = WeightedAlias::new(&[1u64, 0, 3]).unwrap();
+        assert!(fit(&d, &[1.0, 0.0, 3.0], &mut g));
+        let d = WeightedAlias::new(&[0i32, 5]).unwrap();
+        assert!((0..100).all(|_| d.sample(&mut g) == 1));
+    }
+
+    #[test]
+    fn test_single_weight() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        assert_eq!(0, WeightedAlias::new(&[0.5]).unwrap().sample(&mut g));
+        assert_eq!(0, WeightedIndex::new(&[0.5]).unwrap().sample(&mut g));
+    }
+
+    #[test]
+    fn test_invalid_weights() {
+        let empty: [f64; 0] = [];
+        assert_eq!(Err(Error::EmptyWeights), WeightedAlias::new(&empty));
+        assert_eq!(Err(Error::EmptyWeights), WeightedIndex::new(&empty));
+        assert_eq!(Err(Error::InvalidWeight(1)), WeightedAlias::new(&[1.0, -1.0]));
+        assert_eq!(Err(Error::InvalidWeight(0)), WeightedAlias::new(&[f64::NAN, 1.0]));
+        assert_eq!(Err(Error::InvalidWeight(2)), WeightedIndex::new(&[1.0, 1.0, f64::INFINITY]));
+        assert_eq!(Err(Error::InvalidWeight(1)), WeightedIndex::new(&[1i64, -3]));
+        assert_eq!(Err(Error::InvalidWeight(1)), WeightedIndex::new(&[f64::MAX, f64::MAX]));
+        assert_eq!(Err(Error::InvalidWeight(2)), WeightedAlias::new(&[1.0, f64::MAX, f64::MAX, 1.0, 0.0]));
+        assert_eq!(Err(Error::AllWeightsZero), WeightedAlias::new(&[0.0, 0.0]));
+        assert_eq!(Err(Error::AllWeightsZero), WeightedIndex::new(&[0u8; 4]));
+    }
+}
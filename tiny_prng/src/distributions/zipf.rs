@@ -0,0 +1,140 @@
+//! The Zipf distribution sampled with the rejection-inversion of Hörmann and Derflinger (1996),
+//! "Rejection-inversion to generate variates from monotone discrete distributions".
+
+use crate::Generator;
+use super::{Distribution, Error};
+
+/// The Zipf distribution on `1..=n` with the exponent `s`, i.e. `P(k)` proportional to `k^-s`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Zipf {
+    n: u64,
+    s: f64,
+    h_integral_x1: f64,
+    h_integral_n: f64,
+    /// the width of the region accepted without evaluating the integral
+    squeeze: f64,
+}
+
+/// ln(1 + x) / x, continuous at 0
+#[inline]
+fn helper1(x: f64) -> f64 {
+    match x.abs() > 1e-8 {
+        true => x.ln_1p() / x,
+        false => 1.0 - x * (0.5 - x * (1.0 / 3.0 - 0.25 * x)),
+    }
+}
+
+/// (exp(x) - 1) / x, continuous at 0
+#[inline]
+fn helper2(x: f64) -> f64 {
+    match x.abs() > 1e-8 {
+        true => x.exp_m1() / x,
+        false => 1.0 + x * 0.5 * (1.0 + x / 3.0 * (1.0 + 0.25 * x)),
+    }
+}
+
+impl Zipf {
+    /// construct the distribution; `n` must be positive and `s` finite and non-negative
+    pub fn new(n: u64, s: f64) -> Result<Self, Error> {
+        if n == 0 {
+            return Err(Error::InvalidParameter("n"));
+        }
+        if !s.is_finite() || s < 0.0 {
+            return Err(Error::InvalidParameter("s"));
+        }
+        let mut z = Self { n, s, h_integral_x1: 0.0, h_integral_n: 0.0, squeeze: 0.0 };
+        z.h_integral_x1 = z.h_integral(1.5) - 1.0;
+        z.h_integral_n = z.h_integral(n as f64 + 0.5);
+        z.squeeze = 2.0 - z.h_integral_inverse(z.h_integral(2.5) - z.h(2.0));
+        Ok(z)
+    }
+
+    pub fn n(&self) -> u64 {
+        self.n
+    }
+
+    pub fn s(&self) -> f64 {
+        self.s
+    }
+
+    /// the hat function x^-s
+    #[inline]
+    fn h(&self, x: f64) -> f64 {
+        (-self.s * x.ln()).exp()
+    }
+
+    /// the integral of the hat function, (x^(1-s) - 1) / (1 - s)
+    #[inline]
+    fn h_integral(&self, x: f64) -> f64 {
+        let ln_x = x.ln();
+        helper2((1.0 - self.s) * ln_x) * ln_x
+    }
+
+    #[inline]
+    fn h_integral_inverse(&self, x: f64) -> f64 {
+        // the rounding may push the argument of the logarithm below -1
+        let t = (x * (1.0 - self.s)).max(-1.0);
+        (helper1(t) * x).exp()
+    }
+}
+
+impl Distribution<u64> for Zipf {
+    fn sample<G: Generator + ?Sized>(&self, g: &mut G) -> u64 {
+        loop {
+            let u = self.h_integral_n + g.generate_real() * (self.h_integral_x1 - self.h_integral_n);
+            let x = self.h_integral_inverse(u);
+            let k = ((x + 0.5) as u64).clamp(1, self.n);
+            let kf = k as f64;
+            if kf - x <= self.squeeze || u >= self.h_integral(kf + 0.5) - self.h(kf) {
+                return k;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::tests::discrete_fit;
+    use crate::mt::Mt19937;
+    use crate::xorshift::Xorshift64;
+
+    const COUNT: usize = 100 * 1000;
+
+    fn pmf(n: u64, s: f64) -> impl Fn(u64) -> f64 {
+        let norm: f64 = (1..=n).map(|k| (k as f64).powf(-s)).sum();
+        move |k| match k >= 1 && k <= n {
+            true => (k as f64).powf(-s) / norm,
+            false => 0.0,
+        }
+    }
+
+    #[test]
+    fn test_zipf_fit() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        for (n, s) in [(1, 1.0), (10, 0.0), (10, 1.0), (100, 0.5), (1000, 1.0), (1000, 1.5), (100000, 2.5)] {
+            let d = Zipf::new(n, s).unwrap();
+            let v: Vec<u64> = (0..COUNT).map(|_| d.sample(&mut g)).collect();
+            assert!(v.iter().all(|&k| k >= 1 && k <= n));
+            let (chi2, critical) = discrete_fit(&v, pmf(n, s));
+            assert!(chi2 < critical, "Zipf({}, {}): {} >= {}", n, s, chi2, critical);
+        }
+    }
+
+    #[test]
+    fn test_zipf_huge_support() {
+        // the sampling does not depend on n
+        let mut g = Mt19937::with_array(vec![0x123, 0x234, 0x345, 0x456]);
+        let d = Zipf::new(u64::MAX, 1.2).unwrap();
+        let ones = (0..COUNT).filter(|_| d.sample(&mut g) == 1).count() as f64 / COUNT as f64;
+        // 1 / zeta(1.2) = 0.1788
+        assert!((ones - 0.1788).abs() < 0.006);
+    }
+
+    #[test]
+    fn test_zipf_parameters() {
+        assert_eq!(Err(Error::InvalidParameter("n")), Zipf::new(0, 1.0));
+        assert_eq!(Err(Error::InvalidParameter("s")), Zipf::new(10, -1.0));
+        assert_eq!(Err(Error::InvalidParameter("s")), Zipf::new(10, f64::NAN));
+    }
+}
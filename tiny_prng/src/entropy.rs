@@ -0,0 +1,231 @@
+//! The `entropy` module seeds the generators from the entropy source of the operating system.
+//!
+//! It is compiled with the `entropy` feature. The entropy comes from the `getrandom` system call on Linux,
+//! from `/dev/urandom` on the other Unix platforms and on kernels without the call, and from `BCryptGenRandom`
+//! on Windows; the other platforms report `Error::Unsupported`.
+//! When the entropy is unavailable, the fallback mixes the time, the process ID, addresses randomized by ASLR and
+//! the keys of the standard library's hash maps, which differs between processes started together
+//! but is no substitute for a real entropy source.
+//!
+//! ```rust
+//! use tiny_prng::seed::FromSeedSequence;
+//! use tiny_prng::xorshift::Xorshift64;
+//! use tiny_prng::pcg::PcgXslRr;
+//!
+//! fn main(){
+//!     let mut x = Xorshift64::from_entropy().expect("no entropy source");
+//!     let mut p = PcgXslRr::from_entropy_or_fallback();
+//!     println!("{} {}", x.generate(), p.generate());
+//! }
+//! ```
+
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::BuildHasher;
+use std::io;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::seed::SeedSequence;
+
+/// the number of the 32bit words of entropy drawn for a seed sequence
+const ENTROPY_WORDS: usize = 8;
+
+/// The error on reading the entropy of the operating system.
+#[derive(Debug)]
+pub enum Error {
+    /// the platform has no supported entropy source
+    Unsupported,
+    /// the entropy source failed
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Unsupported => write!(f, "no entropy source on this platform"),
+            Error::Io(e) => write!(f, "failed to read entropy: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Unsupported => None,
+            Error::Io(e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+/// fill the slice with bytes from the entropy source of the operating system
+#[cfg(target_os = "linux")]
+pub fn fill_bytes(dest: &mut [u8]) -> Result<(), Error> {
+    /// the `errno` of a kernel without the system call
+    const ENOSYS: i32 = 38;
+    unsafe extern "C" {
+        fn getrandom(buf: *mut u8, buflen: usize, flags: u32) -> isize;
+    }
+    let mut filled = 0;
+    while filled < dest.len() {
+        let rest = &mut dest[filled..];
+        // SAFETY: the buffer is valid for writes of its length
+        let n = unsafe { getrandom(rest.as_mut_ptr(), rest.len(), 0) };
+        if n < 0 {
+            let e = io::Error::last_os_error();
+            match (e.kind(), e.raw_os_error()) {
+                (io::ErrorKind::Interrupted, _) => continue,
+                (_, Some(ENOSYS)) => return read_urandom(rest),
+                _ => return Err(e.into()),
+            }
+        }
+        filled += n as usize;
+    }
+    Ok(())
+}
+
+/// fill the slice with bytes from the entropy source of the operating system
+#[cfg(all(unix, not(target_os = "linux")))]
+pub fn fill_bytes(dest: &mut [u8]) -> Result<(), Error> {
+    read_urandom(dest)
+}
+
+/// read the entropy from the device of the kernel
+#[cfg(unix)]
+fn read_urandom(dest: &mut [u8]) -> Result<(), Error> {
+    use std::io::Read;
+    std::fs::File::open("/dev/urandom")?.read_exact(dest)?;
+    Ok(())
+}
+
+/// fill the slice with bytes from the entropy source of the operating system
+#[cfg(windows)]
+pub fn fill_bytes(dest: &mut [u8]) -> Result<(), Error> {
+    /// draw from the system preferred generator without an algorithm handle
+    const BCRYPT_USE_SYSTEM_PREFERRED_RNG: u32 = 2;
+    #[link(name = "bcrypt")]
+    unsafe extern "system" {
+        fn BCryptGenRandom(algorithm: *mut std::ffi::c_void, buffer: *mut u8, len: u32, flags: u32) -> i32;
+    }
+    for chunk in dest.chunks_mut(u32::MAX as usize) {
+        // SAFETY: the buffer is valid for writes of its length, and a null handle is allowed with the flag
+        let status = unsafe {
+            BCryptGenRandom(std::ptr::null_mut(), chunk.as_mut_ptr(), chunk.len() as u32, BCRYPT_USE_SYSTEM_PREFERRED_RNG)
+        };
+        if status < 0 {
+            return Err(io::Error::other(format!("BCryptGenRandom failed with the status {:#x}", status)).into());
+        }
+    }
+    Ok(())
+}
+
+/// fill the slice with bytes from the entropy source of the operating system
+#[cfg(not(any(unix, windows)))]
+pub fn fill_bytes(_dest: &mut [u8]) -> Result<(), Error> {
+    Err(Error::Unsupported)
+}
+
+/// a seed sequence from the entropy of the operating system
+pub fn seed_sequence() -> Result<SeedSequence, Error> {
+    let mut bytes = [0u8; 4 * ENTROPY_WORDS];
+    fill_bytes(&mut bytes)?;
+    let words: Vec<u32> = bytes
+        .chunks_exact(4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect();
+    Ok(SeedSequence::new(&words))
+}
+
+/// a seed sequence mixing the time, the process ID and the randomized addresses and hash keys
+///
+/// Successive calls in a process differ by a counter.
+pub fn fallback_seed_sequence() -> SeedSequence {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+    let stack = 0u8;
+    let heap = Box::new(0u8);
+    let words64 = [
+        now as u64,
+        (now >> 64) as u64,
+        std::process::id() as u64,
+        &stack as *const u8 as u64,
+        &*heap as *const u8 as u64,
+        fallback_seed_sequence as *const () as u64,
+        // the standard library keys its hash maps with the entropy of the process
+        RandomState::new().hash_one(COUNTER.fetch_add(1, Ordering::Relaxed)),
+    ];
+    let words: Vec<u32> = words64.iter().flat_map(|&w| [w as u32, (w >> 32) as u32]).collect();
+    SeedSequence::new(&words)
+}
+
+/// a seed sequence from the entropy of the operating system, or from the fallback if it is unavailable
+pub fn seed_sequence_or_fallback() -> SeedSequence {
+    seed_sequence().unwrap_or_else(|_| fallback_seed_sequence())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mt::Mt19937;
+    use crate::pcg::{PcgXslRr, PcgXshRr6432};
+    use crate::seed::FromSeedSequence;
+    use crate::xorshift::{Xorshift1024star, Xorshift32};
+
+    #[test]
+    #[cfg(any(unix, windows))]
+    fn test_fill_bytes() {
+        let (mut a, mut b) = ([0u8; 32], [0u8; 32]);
+        fill_bytes(&mut a).unwrap();
+        fill_bytes(&mut b).unwrap();
+        assert_ne!(a, b);
+        fill_bytes(&mut []).unwrap();
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_read_urandom() {
+        let (mut a, mut b) = ([0u8; 32], [0u8; 32]);
+        read_urandom(&mut a).unwrap();
+        read_urandom(&mut b).unwrap();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    #[cfg(any(unix, windows))]
+    fn test_seed_sequences_differ() {
+        assert_ne!(seed_sequence().unwrap().seed_u128(), seed_sequence().unwrap().seed_u128());
+        assert_ne!(seed_sequence_or_fallback().seed_u128(), seed_sequence_or_fallback().seed_u128());
+    }
+
+    #[test]
+    fn test_fallback_differs() {
+        assert_ne!(fallback_seed_sequence().seed_u128(), fallback_seed_sequence().seed_u128());
+    }
+
+    #[test]
+    #[cfg(any(unix, windows))]
+    fn test_from_entropy() {
+        let mut a = Xorshift32::from_entropy().unwrap();
+        let mut b = Xorshift32::from_entropy().unwrap();
+        assert_ne!(a.generate(), b.generate());
+        let mut p = PcgXslRr::from_entropy().unwrap();
+        let mut q = PcgXshRr6432::from_entropy_or_fallback();
+        let mut m = Mt19937::from_entropy().unwrap();
+        let mut x = Xorshift1024star::from_entropy_or_fallback();
+        assert_ne!(p.generate(), 0);
+        assert_ne!((q.generate(), m.generate(), x.generate()), (0, 0, 0));
+    }
+
+    #[test]
+    fn test_error_display() {
+        assert_eq!("no entropy source on this platform", Error::Unsupported.to_string());
+        let e = Error::from(io::Error::new(io::ErrorKind::NotFound, "gone"));
+        assert_eq!("failed to read entropy: gone", e.to_string());
+        assert!(std::error::Error::source(&e).is_some());
+    }
+}
@@ -0,0 +1,829 @@
+//! The `generator` module provides the `Generator` trait which is implemented by every PRNG in the crate.
+//!
+//! The trait exposes the raw output of a generator as 32, 64 and 128 bit words and builds the
+//! real number generation (see the `real` module for the intervals) and the unbiased bounded
+//! integer generation on top of them.
+//! Integers in a range are generated with Lemire's nearly-divisionless method, which never
+//! suffers from the bias of `generate() % n`.
+//!
+//! ```rust
+//! use tiny_prng::Generator;
+//! use tiny_prng::pcg::PcgXshRr6432;
+//!
+//! fn main(){
+//!     let mut x = PcgXshRr6432::with_seed(0x1818729182367349);
+//!     // a dice roll
+//!     let d: u32 = x.generate_range(1..=6);
+//!     assert!((1..=6).contains(&d));
+//!     // a signed integer in a half-open range
+//!     let t: i64 = x.generate_range(-40..40);
+//!     assert!((-40..40).contains(&t));
+//!     // an index for a slice
+//!     let i = x.generate_below(10usize);
+//!     assert!(i < 10);
+//! }
+//! ```
+
+use std::iter;
+use std::ops::{Bound, RangeBounds};
+use crate::real;
+
+/// The common interface of the generators.
+///
+/// Implementors only provide the raw words; the real number and bounded integer generation is shared.
+/// Generators whose native output is narrower than the requested word concatenate
+/// successive outputs, and the wider ones return the most significant bits of an output.
+pub trait Generator {
+    /// generate a 32bit word with the current state of the generator
+    fn generate_u32(&mut self) -> u32;
+
+    /// generate a 64bit word with the current state of the generator
+    fn generate_u64(&mut self) -> u64;
+
+    /// generate a 128bit word with the current state of the generator
+    fn generate_u128(&mut self) -> u128 {
+        let upper = self.generate_u64() as u128;
+        let lower = self.generate_u64() as u128;
+        (upper << 64) | lower
+    }
+
+    /// generate a double in `[0, 1)` with 53bit precision
+    fn generate_real(&mut self) -> f64 {
+        real::f64_half_open(self.generate_u64())
+    }
+
+    /// generate a double in `[0, 1]` with 53bit precision
+    fn generate_real_closed(&mut self) -> f64 {
+        real::f64_closed(self.generate_u64())
+    }
+
+    /// generate a double in `(0, 1)` with 52bit precision
+    fn generate_real_open(&mut self) -> f64 {
+        real::f64_open(self.generate_u64())
+    }
+
+    /// generate a double in `(0, 1]` with 53bit precision
+    fn generate_real_open_closed(&mut self) -> f64 {
+        real::f64_open_closed(self.generate_u64())
+    }
+
+    /// generate a double in `[lower, upper)`
+    fn generate_real_in_range(&mut self, lower: f64, upper: f64) -> f64 {
+        scale_f64(self.generate_real(), lower, upper)
+    }
+
+    /// generate a float in `[0, 1)` with 24bit precision
+    fn generate_real_f32(&mut self) -> f32 {
+        real::f32_half_open(self.generate_u32())
+    }
+
+    /// generate a float in `[0, 1]` with 24bit precision
+    fn generate_real_closed_f32(&mut self) -> f32 {
+        real::f32_closed(self.generate_u32())
+    }
+
+    /// generate a float in `(0, 1)` with 23bit precision
+    fn generate_real_open_f32(&mut self) -> f32 {
+        real::f32_open(self.generate_u32())
+    }
+
+    /// generate a float in `(0, 1]` with 24bit precision
+    fn generate_real_open_closed_f32(&mut self) -> f32 {
+        real::f32_open_closed(self.generate_u32())
+    }
+
+    /// generate a float in `[lower, upper)`
+    fn generate_real_in_range_f32(&mut self, lower: f32, upper: f32) -> f32 {
+        scale_f32(self.generate_real_f32(), lower, upper)
+    }
+
+    /// fill the slice with 32bit words
+    fn fill_u32(&mut self, dest: &mut [u32]) {
+        for d in dest {
+            *d = self.generate_u32();
+        }
+    }
+
+    /// fill the slice with 64bit words
+    fn fill_u64(&mut self, dest: &mut [u64]) {
+        for d in dest {
+            *d = self.generate_u64();
+        }
+    }
+
+    /// fill the slice with bytes
+    ///
+    /// Every 8 bytes are a 64bit word in little-endian order, and the trailing bytes are the lowest bytes
+    /// of one more word, so the output does not depend on the endianness of the platform.
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        let mut chunks = dest.chunks_exact_mut(8);
+        for chunk in &mut chunks {
+            chunk.copy_from_slice(&self.generate_u64().to_le_bytes());
+        }
+        let rest = chunks.into_remainder();
+        if !rest.is_empty() {
+            let n = rest.len();
+            rest.copy_from_slice(&self.generate_u64().to_le_bytes()[..n]);
+        }
+    }
+
+    /// fill the slice with doubles in `[0, 1)`, equal to the successive `generate_real`
+    fn fill_f64(&mut self, dest: &mut [f64]) {
+        let mut words = [0u64; FILL_BUFFER];
+        for chunk in dest.chunks_mut(FILL_BUFFER) {
+            let words = &mut words[..chunk.len()];
+            self.fill_u64(words);
+            for (d, &w) in chunk.iter_mut().zip(words.iter()) {
+                *d = real::f64_half_open(w);
+            }
+        }
+    }
+
+    /// fill the slice with doubles in `[lower, upper)`
+    fn fill_f64_in_range(&mut self, dest: &mut [f64], lower: f64, upper: f64) {
+        self.fill_f64(dest);
+        for d in dest {
+            *d = scale_f64(*d, lower, upper);
+        }
+    }
+
+    /// fill the slice with floats in `[0, 1)`, equal to the successive `generate_real_f32`
+    fn fill_f32(&mut self, dest: &mut [f32]) {
+        let mut words = [0u32; FILL_BUFFER];
+        for chunk in dest.chunks_mut(FILL_BUFFER) {
+            let words = &mut words[..chunk.len()];
+            self.fill_u32(words);
+            for (d, &w) in chunk.iter_mut().zip(words.iter()) {
+                *d = real::f32_half_open(w);
+            }
+        }
+    }
+
+    /// fill the slice with floats in `[lower, upper)`
+    fn fill_f32_in_range(&mut self, dest: &mut [f32], lower: f32, upper: f32) {
+        self.fill_f32(dest);
+        for d in dest {
+            *d = scale_f32(*d, lower, upper);
+        }
+    }
+
+    /// generate an integer uniformly distributed in `[0, bound)`.
+    ///
+    /// Panics if `bound` is zero.
+    fn generate_below<T: UniformInt>(&mut self, bound: T) -> T
+    where
+        Self: Sized,
+    {
+        T::sample_below(self, bound)
+    }
+
+    /// generate an integer uniformly distributed in `range`.
+    ///
+    /// Both `lower..upper` and `lower..=upper` are accepted. Panics if the range is empty.
+    fn generate_range<T: UniformInt, R: RangeBounds<T>>(&mut self, range: R) -> T
+    where
+        Self: Sized,
+    {
+        let (lower, upper) = inclusive_bounds(&range, "generate_range");
+        T::sample_inclusive(self, lower, upper)
+    }
+
+    /// iterate over 32bit words
+    fn iter_u32(&mut self) -> impl Iterator<Item = u32>
+    where
+        Self: Sized,
+    {
+        iter::repeat_with(move || self.generate_u32())
+    }
+
+    /// iterate over 64bit words
+    fn iter_u64(&mut self) -> impl Iterator<Item = u64>
+    where
+        Self: Sized,
+    {
+        iter::repeat_with(move || self.generate_u64())
+    }
+
+    /// iterate over doubles in `[0, 1)`
+    fn reals(&mut self) -> impl Iterator<Item = f64>
+    where
+        Self: Sized,
+    {
+        iter::repeat_with(move || self.generate_real())
+    }
+
+    /// iterate over doubles in `[lower, upper)`
+    fn reals_in_range(&mut self, lower: f64, upper: f64) -> impl Iterator<Item = f64>
+    where
+        Self: Sized,
+    {
+        iter::repeat_with(move || self.generate_real_in_range(lower, upper))
+    }
+
+    /// iterate over integers uniformly distributed in `range`
+    ///
+    /// Panics if the range is empty.
+    fn integers_in_range<T: UniformInt, R: RangeBounds<T>>(&mut self, range: R) -> impl Iterator<Item = T>
+    where
+        Self: Sized,
+    {
+        let (lower, upper) = inclusive_bounds(&range, "integers_in_range");
+        iter::repeat_with(move || T::sample_inclusive(self, lower, upper))
+    }
+}
+
+/// the number of the words generated at once for the real number fills
+const FILL_BUFFER: usize = 64;
+
+/// map a double in `[0, 1)` to `[lower, upper)`
+#[inline]
+fn scale_f64(u: f64, lower: f64, upper: f64) -> f64 {
+    let v = lower + (upper - lower) * u;
+    // the product may be rounded up to the excluded bound
+    match v >= upper && lower < upper {
+        true => upper.next_down(),
+        false => v,
+    }
+}
+
+/// map a float in `[0, 1)` to `[lower, upper)`
+#[inline]
+fn scale_f32(u: f32, lower: f32, upper: f32) -> f32 {
+    let v = lower + (upper - lower) * u;
+    match v >= upper && lower < upper {
+        true => upper.next_down(),
+        false => v,
+    }
+}
+
+/// the inclusive bounds of a non-empty range; panics with the name of the caller otherwise
+fn inclusive_bounds<T: UniformInt, R: RangeBounds<T>>(range: &R, caller: &str) -> (T, T) {
+    let lower = match range.start_bound() {
+        Bound::Included(&v) => Some(v),
+        Bound::Excluded(&v) => v.checked_next(),
+        Bound::Unbounded => Some(T::MIN),
+    };
+    let upper = match range.end_bound() {
+        Bound::Included(&v) => Some(v),
+        Bound::Excluded(&v) => v.checked_prev(),
+        Bound::Unbounded => Some(T::MAX),
+    };
+    match (lower, upper) {
+        (Some(lower), Some(upper)) if lower <= upper => (lower, upper),
+        _ => panic!("{}: empty range", caller),
+    }
+}
+
+/// Integer types which can be generated uniformly in a range.
+pub trait UniformInt: Copy + PartialOrd {
+    const MIN: Self;
+    const MAX: Self;
+
+    /// sample a value in `[lower, upper]` (`lower <= upper` is assumed)
+    fn sample_inclusive<G: Generator + ?Sized>(g: &mut G, lower: Self, upper: Self) -> Self;
+
+    /// sample a value in `[0, bound)`
+    fn sample_below<G: Generator + ?Sized>(g: &mut G, bound: Self) -> Self;
+
+    #[doc(hidden)]
+    fn checked_next(self) -> Option<Self>;
+
+    #[doc(hidden)]
+    fn checked_prev(self) -> Option<Self>;
+}
+
+/// Lemire's nearly-divisionless method for a 32bit range `[0, s)`.
+/// `s == 0` stands for the whole 2^32 range.
+#[inline]
+pub(crate) fn lemire32<G: Generator + ?Sized>(g: &mut G, s: u32) -> u32 {
+    if s == 0 {
+        return g.generate_u32();
+    }
+    let mut m = (g.generate_u32() as u64) * (s as u64);
+    let mut l = m as u32;
+    if l < s {
+        let t = s.wrapping_neg() % s;
+        while l < t {
+            m = (g.generate_u32() as u64) * (s as u64);
+            l = m as u32;
+        }
+    }
+    (m >> 32) as u32
+}
+
+/// Lemire's nearly-divisionless method for a 64bit range `[0, s)`.
+/// `s == 0` stands for the whole 2^64 range.
+#[inline]
+pub(crate) fn lemire64<G: Generator + ?Sized>(g: &mut G, s: u64) -> u64 {
+    if s == 0 {
+        return g.generate_u64();
+    }
+    let mut m = (g.generate_u64() as u128) * (s as u128);
+    let mut l = m as u64;
+    if l < s {
+        let t = s.wrapping_neg() % s;
+        while l < t {
+            m = (g.generate_u64() as u128) * (s as u128);
+            l = m as u64;
+        }
+    }
+    (m >> 64) as u64
+}
+
+/// full 128x128 bit multiplication returning the (high, low) halves of the product
+#[inline]
+fn widening_mul128(x: u128, y: u128) -> (u128, u128) {
+    const MASK: u128 = 0xffffffffffffffff;
+    let (x1, x0) = (x >> 64, x & MASK);
+    let (y1, y0) = (y >> 64, y & MASK);
+    let p00 = x0 * y0;
+    let p01 = x0 * y1;
+    let p10 = x1 * y0;
+    let p11 = x1 * y1;
+    let middle = (p00 >> 64) + (p01 & MASK) + (p10 & MASK);
+    let high = p11 + (p01 >> 64) + (p10 >> 64) + (middle >> 64);
+    let low = (middle << 64) | (p00 & MASK);
+    (high, low)
+}
+
+/// Lemire's nearly-divisionless method for a 128bit range `[0, s)`.
+/// `s == 0` stands for the whole 2^128 range.
+#[inline]
+pub(crate) fn lemire128<G: Generator + ?Sized>(g: &mut G, s: u128) -> u128 {
+    if s == 0 {
+        return g.generate_u128();
+    }
+    let (mut high, mut low) = widening_mul128(g.generate_u128(), s);
+    if low < s {
+        let t = s.wrapping_neg() % s;
+        while low < t {
+            (high, low) = widening_mul128(g.generate_u128(), s);
+        }
+    }
+    high
+}
+
+macro_rules! impl_uniform_int {
+    ($ty:ty, $unsigned:ty, $word:ty, $lemire:ident) => {
+        impl UniformInt for $ty {
+            const MIN: Self = <$ty>::MIN;
+            const MAX: Self = <$ty>::MAX;
+
+            #[inline]
+            fn sample_inclusive<G: Generator + ?Sized>(g: &mut G, lower: Self, upper: Self) -> Self {
+                // the range size wraps to zero only when the range covers the whole word
+                let span = ((upper as $unsigned).wrapping_sub(lower as $unsigned) as $word).wrapping_add(1);
+                let v = $lemire(g, span) as $unsigned;
+                lower.wrapping_add(v as $ty)
+            }
+
+            #[inline]
+            fn sample_below<G: Generator + ?Sized>(g: &mut G, bound: Self) -> Self {
+                assert!(bound > 0 as $ty, "generate_below: bound must be positive");
+                $lemire(g, bound as $word) as $ty
+            }
+
+            #[inline]
+            fn checked_next(self) -> Option<Self> {
+                self.checked_add(1)
+            }
+
+            #[inline]
+            fn checked_prev(self) -> Option<Self> {
+                self.checked_sub(1)
+            }
+        }
+    };
+}
+
+impl_uniform_int!(u8, u8, u32, lemire32);
+impl_uniform_int!(u16, u16, u32, lemire32);
+impl_uniform_int!(u32, u32, u32, lemire32);
+impl_uniform_int!(u64, u64, u64, lemire64);
+impl_uniform_int!(u128, u128, u128, lemire128);
+impl_uniform_int!(usize, usize, u64, lemire64);
+impl_uniform_int!(i8, u8, u32, lemire32);
+impl_uniform_int!(i16, u16, u32, lemire32);
+impl_uniform_int!(i32, u32, u32, lemire32);
+impl_uniform_int!(i64, u64, u64, lemire64);
+impl_uniform_int!(i128, u128, u128, lemire128);
+impl_uniform_int!(isize, usize, u64, lemire64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mt::Mt19937;
+    use crate::mt64;
+    use crate::pcg::{PcgXshRr6432, PcgXslRr};
+    use crate::xorshift::{Xorshift128, Xorshift32, Xorshift64};
+    use crate::seed::{FromSeedSequence, SeedSequence};
+    use proptest::prelude::*;
+
+    const COUNT: usize = 100 * 1000;
+
+    // chi-squared statistic of the bucket counts against the uniform expectation
+    fn chi_squared(buckets: &[usize], total: usize) -> f64 {
+        let expected = total as f64 / buckets.len() as f64;
+        buckets.iter().map(|&b| (b as f64 - expected).powi(2) / expected).sum()
+    }
+
+    // 99.9% quantile of chi-squared with 15 degrees of freedom
+    const CHI2_15DF: f64 = 37.70;
+
+    // a generator which repeats a fixed word, to examine the boundaries
+    struct Constant(u64);
+
+    impl Generator for Constant {
+        fn generate_u32(&mut self) -> u32 {
+            (self.0 >> 32) as u32
+        }
+
+        fn generate_u64(&mut self) -> u64 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_generate_real_boundaries() {
+        let mut zero = Constant(0);
+        assert_eq!(0.0, zero.generate_real());
+        assert_eq!(0.0, zero.generate_real_closed());
+        assert!(zero.generate_real_open() > 0.0);
+        assert!(zero.generate_real_open_closed() > 0.0);
+        assert_eq!(-1.0, zero.generate_real_in_range(-1.0, 1.0));
+
+        let mut max = Constant(u64::MAX);
+        assert!(max.generate_real() < 1.0);
+        assert_eq!(1.0, max.generate_real_closed());
+        assert!(max.generate_real_open() < 1.0);
+        assert_eq!(1.0, max.generate_real_open_closed());
+        assert!(max.generate_real_in_range(-1.0, 1.0) < 1.0);
+        // (1 - 2^-53) * 3 is rounded up to 3.0 without the correction
+        assert!(max.generate_real_in_range(0.0, 3.0) < 3.0);
+        assert!(max.generate_real_in_range(1e10, 1e10 + 1.0) < 1e10 + 1.0);
+    }
+
+    #[test]
+    fn test_generate_real_f32_boundaries() {
+        let mut zero = Constant(0);
+        assert_eq!(0.0, zero.generate_real_f32());
+        assert_eq!(0.0, zero.generate_real_closed_f32());
+        assert!(zero.generate_real_open_f32() > 0.0);
+        assert!(zero.generate_real_open_closed_f32() > 0.0);
+        assert_eq!(-1.0, zero.generate_real_in_range_f32(-1.0, 1.0));
+
+        let mut max = Constant(u64::MAX);
+        assert!(max.generate_real_f32() < 1.0);
+        assert_eq!(1.0, max.generate_real_closed_f32());
+        assert!(max.generate_real_open_f32() < 1.0);
+        assert_eq!(1.0, max.generate_real_open_closed_f32());
+        assert!(max.generate_real_in_range_f32(0.0, 3.0) < 3.0);
+        assert!(max.generate_real_in_range_f32(1e5, 1e5 + 1.0) < 1e5 + 1.0);
+    }
+
+    #[test]
+    fn test_generate_real_f32_uses_upper_bits() {
+        // the lower half of a 64bit word must not contribute to a float
+        let mut g = Constant(0x8000000000ffffff);
+        assert_eq!(0.5, g.generate_real_f32());
+        let mut g = Constant(0x00000000ffffffff);
+        assert_eq!(0.0, g.generate_real_f32());
+    }
+
+    #[test]
+    fn test_generate_real_uses_53bits_of_32bit_generators() {
+        // the lower half of the double comes from the second output
+        let mut g = Xorshift32::with_seed(0x1818729);
+        let mut distinct_low_bits = false;
+        for _ in 0..100 {
+            let v = g.generate_real() * (1u64 << 53) as f64;
+            if v as u64 & 0x1fffff != 0 {
+                distinct_low_bits = true;
+            }
+        }
+        assert!(distinct_low_bits);
+    }
+
+    #[test]
+    fn test_widening_mul128() {
+        assert_eq!((0, 6), widening_mul128(2, 3));
+        assert_eq!((u128::MAX - 1, 1), widening_mul128(u128::MAX, u128::MAX));
+        assert_eq!((1, 0), widening_mul128(1 << 64, 1 << 64));
+    }
+
+    #[test]
+    fn test_generate_range_bounds_small() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        for _ in 0..COUNT {
+            let v: u32 = g.generate_range(1..=6);
+            assert!((1..=6).contains(&v));
+            let v: i8 = g.generate_range(-3..3);
+            assert!((-3..3).contains(&v));
+            let v: i128 = g.generate_range(-5..=-1);
+            assert!((-5..=-1).contains(&v));
+        }
+    }
+
+    #[test]
+    fn test_generate_range_single_value() {
+        let mut g = PcgXshRr6432::with_seed(0x1818729182367349);
+        assert_eq!(7u64, g.generate_range(7..8));
+        assert_eq!(-7i32, g.generate_range(-7..=-7));
+        assert_eq!(u128::MAX, g.generate_range(u128::MAX..=u128::MAX));
+    }
+
+    #[test]
+    fn test_generate_range_full_width() {
+        let mut g = PcgXslRr::with_seed(0x1818729182367349);
+        let mut negative = 0;
+        for _ in 0..COUNT {
+            let v: i8 = g.generate_range(..);
+            if v < 0 {
+                negative += 1;
+            }
+            let _: u32 = g.generate_range(0..=u32::MAX);
+            let _: i64 = g.generate_range(i64::MIN..=i64::MAX);
+            let _: u128 = g.generate_range(..);
+        }
+        assert!(negative > COUNT * 49 / 100 && negative < COUNT * 51 / 100);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_generate_range_empty() {
+        let mut g = Xorshift32::with_seed(0x1818729);
+        let _: u32 = g.generate_range(5..5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_generate_below_zero() {
+        let mut g = Xorshift32::with_seed(0x1818729);
+        let _ = g.generate_below(0u64);
+    }
+
+    #[test]
+    #[should_panic(expected = "integers_in_range: empty range")]
+    fn test_integers_in_range_empty() {
+        let mut g = Xorshift32::with_seed(0x1818729);
+        // panics on the construction, before any item is drawn
+        let _ = g.integers_in_range(7u8..7);
+    }
+
+    #[test]
+    fn test_native_iterators() {
+        let (mut a, mut b) = (Xorshift32::with_seed(0x1818729), Xorshift32::with_seed(0x1818729));
+        let v: Vec<u32> = a.iter().take(10).collect();
+        assert!(v.iter().all(|&x| x == b.generate()));
+        // the generator is an iterator by itself
+        let (a, mut b) = (mt64::Mt19937::with_array(vec![0x123, 0x234, 0x345, 0x456]), mt64::Mt19937::with_array(vec![0x123, 0x234, 0x345, 0x456]));
+        for x in a.take(10) {
+            assert_eq!(b.generate(), x);
+        }
+        let (mut a, mut b) = (Xorshift128::with_seed(0x1818729), Xorshift128::with_seed(0x1818729));
+        let v: Vec<u32> = (0..3).zip(a.by_ref()).map(|(_, x)| x).collect();
+        assert!(v.iter().all(|&x| x == b.generate()));
+        assert_eq!(a.next(), Some(b.generate()));
+    }
+
+    #[test]
+    fn test_word_and_real_iterators() {
+        let (mut a, mut b) = (PcgXslRr::with_seed(0x1818729182367349), PcgXslRr::with_seed(0x1818729182367349));
+        assert!(a.iter_u32().take(10).all(|x| x == b.generate_u32()));
+        assert!(a.iter_u64().take(10).all(|x| x == b.generate_u64()));
+        assert!(a.reals().take(10).all(|x| x == b.generate_real()));
+        assert!(a.reals_in_range(-2.0, 3.0).take(10).all(|x| x == b.generate_real_in_range(-2.0, 3.0)));
+        let mut g = Mt19937::with_array(vec![0x123, 0x234, 0x345, 0x456]);
+        assert!(g.reals().take(COUNT).all(|x| (0.0..1.0).contains(&x)));
+        assert!(g.reals_in_range(-1.0, 1.0).take(COUNT).all(|x| (-1.0..1.0).contains(&x)));
+        let before = g.reals().take_while(|&x| x < 0.99).count();
+        assert!(before < COUNT);
+    }
+
+    #[test]
+    fn test_integers_in_range() {
+        let (mut a, mut b) = (PcgXshRr6432::with_seed(0x1818729), PcgXshRr6432::with_seed(0x1818729));
+        assert!(a.integers_in_range(-5i32..5).take(100).all(|x| x == b.generate_range(-5i32..5)));
+        let mut buckets = [0; 16];
+        for x in a.integers_in_range(..16u8).take(COUNT) {
+            buckets[x as usize] += 1;
+        }
+        assert!(chi_squared(&buckets, COUNT) < CHI2_15DF);
+    }
+
+    #[test]
+    fn test_fill_words() {
+        let (mut a, mut b) = (Xorshift32::with_seed(0x1818729), Xorshift32::with_seed(0x1818729));
+        let mut v = [0u32; 100];
+        a.fill_u32(&mut v);
+        assert!(v.iter().all(|&x| x == b.generate_u32()));
+        let mut w = [0u64; 100];
+        a.fill_u64(&mut w);
+        assert!(w.iter().all(|&x| x == b.generate_u64()));
+    }
+
+    #[test]
+    fn test_fill_bytes() {
+        let mut c = Constant(0x0102030405060708);
+        let mut v = [0u8; 11];
+        c.fill_bytes(&mut v);
+        // little-endian regardless of the platform, the tail from the lowest bytes
+        assert_eq!([8, 7, 6, 5, 4, 3, 2, 1, 8, 7, 6], v);
+        let (mut a, mut b) = (PcgXslRr::with_seed(0x1818729182367349), PcgXslRr::with_seed(0x1818729182367349));
+        let mut v = [0u8; 16];
+        a.fill_bytes(&mut v);
+        assert_eq!(b.generate_u64().to_le_bytes(), v[..8]);
+        assert_eq!(b.generate_u64().to_le_bytes(), v[8..]);
+        a.fill_bytes(&mut []);
+        assert_eq!(a.generate_u64(), b.generate_u64());
+    }
+
+    #[test]
+    fn test_fill_reals() {
+        let (mut a, mut b) = (Mt19937::with_array(vec![0x123, 0x234, 0x345, 0x456]), Mt19937::with_array(vec![0x123, 0x234, 0x345, 0x456]));
+        // longer than the internal buffer
+        let mut v = [0.0f64; 150];
+        a.fill_f64(&mut v);
+        assert!(v.iter().all(|&x| x == b.generate_real()));
+        a.fill_f64_in_range(&mut v, -3.0, 7.0);
+        assert!(v.iter().all(|&x| x == b.generate_real_in_range(-3.0, 7.0)));
+        let mut v = [0.0f32; 150];
+        a.fill_f32(&mut v);
+        assert!(v.iter().all(|&x| x == b.generate_real_f32()));
+        a.fill_f32_in_range(&mut v, -3.0, 7.0);
+        assert!(v.iter().all(|&x| x == b.generate_real_in_range_f32(-3.0, 7.0)));
+        let mut zero = Constant(u64::MAX);
+        let mut v = [0.0f64; 3];
+        zero.fill_f64_in_range(&mut v, 1.0, 2.0);
+        assert!(v.iter().all(|&x| x < 2.0));
+    }
+
+    // 2^31+1 is the bound where the 32bit rejection threshold is the largest
+    #[test]
+    fn test_uniformity_u32_awkward_bound() {
+        let mut g = Mt19937::with_array(vec![0x123, 0x234, 0x345, 0x456]);
+        let bound = (1u32 << 31) + 1;
+        let mut buckets = [0; 16];
+        for _ in 0..COUNT {
+            let v = g.generate_below(bound);
+            assert!(v < bound);
+            buckets[(v as u64 * 16 / bound as u64) as usize] += 1;
+        }
+        assert!(chi_squared(&buckets, COUNT) < CHI2_15DF);
+    }
+
+    // `generate() % 3*2^30` would hit the lower third twice as often as the others
+    #[test]
+    fn test_uniformity_u32_modulo_biased_bound() {
+        let mut g = PcgXshRr6432::with_seed(0x1818729182367349);
+        let bound = 3u32 << 30;
+        let mut buckets = [0; 3];
+        for _ in 0..COUNT {
+            buckets[(g.generate_below(bound) / (1 << 30)) as usize] += 1;
+        }
+        // 99.9% quantile of chi-squared with 2 degrees of freedom
+        assert!(chi_squared(&buckets, COUNT) < 13.82);
+    }
+
+    #[test]
+    fn test_uniformity_u64_awkward_bound() {
+        let mut g = mt64::Mt19937::with_array(vec![0x123, 0x234, 0x345, 0x456]);
+        let bound = (1u64 << 63) + 1;
+        let mut buckets = [0; 16];
+        for _ in 0..COUNT {
+            let v = g.generate_below(bound);
+            assert!(v < bound);
+            buckets[(v as u128 * 16 / bound as u128) as usize] += 1;
+        }
+        assert!(chi_squared(&buckets, COUNT) < CHI2_15DF);
+    }
+
+    #[test]
+    fn test_uniformity_u128_awkward_bound() {
+        let mut g = Xorshift128::with_seed(0x1818729);
+        let bound = (1u128 << 127) + 1;
+        let mut buckets = [0; 16];
+        for _ in 0..COUNT {
+            let v = g.generate_below(bound);
+            assert!(v < bound);
+            buckets[(v / (bound / 16 + 1)) as usize] += 1;
+        }
+        assert!(chi_squared(&buckets, COUNT) < CHI2_15DF);
+    }
+
+    #[test]
+    fn test_uniformity_signed_range() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        let mut buckets = [0; 16];
+        for _ in 0..COUNT {
+            let v: i32 = g.generate_range(-8..=7);
+            buckets[(v + 8) as usize] += 1;
+        }
+        assert!(chi_squared(&buckets, COUNT) < CHI2_15DF);
+    }
+
+    // the properties of the bounded integers and the ranged reals over random seeds and bounds
+
+    macro_rules! range_properties {
+        ($($name:ident: $t:ty),* $(,)?) => {
+            proptest! {
+                $(
+                    #[test]
+                    fn $name(
+                        seed: u128, a: i64, b: i64, bound in 1u64.., c: u8, d: u8,
+                        x in -1e300f64..1e300, y in -1e300f64..1e300, ulps in 1usize..4,
+                    ) {
+                        let mut g = <$t>::from_seed_sequence(&SeedSequence::from_u128(seed));
+                        let (lower, upper) = (a.min(b), a.max(b));
+                        let v: i64 = g.generate_range(lower..=upper);
+                        prop_assert!((lower..=upper).contains(&v));
+                        if lower < upper {
+                            let v: i64 = g.generate_range(lower..upper);
+                            prop_assert!((lower..upper).contains(&v));
+                        }
+                        prop_assert!(g.generate_below(bound) < bound);
+                        let v: u8 = g.generate_range(c.min(d)..=c.max(d));
+                        prop_assert!((c.min(d)..=c.max(d)).contains(&v));
+                        // reals over a wide range and over the few doubles above a bound
+                        let (lower, upper) = (x.min(y), x.max(y));
+                        if lower < upper {
+                            let v = g.generate_real_in_range(lower, upper);
+                            prop_assert!(lower <= v && v < upper, "{} not in [{}, {})", v, lower, upper);
+                        }
+                        let upper = (0..ulps).fold(x, |u, _| u.next_up());
+                        let v = g.generate_real_in_range(x, upper);
+                        prop_assert!(x <= v && v < upper, "{} not in [{}, {})", v, x, upper);
+                        let (x, y) = ((x * 1e-270) as f32, (y * 1e-270) as f32);
+                        let (lower, upper) = (x.min(y), x.max(y));
+                        if lower < upper {
+                            let v = g.generate_real_in_range_f32(lower, upper);
+                            prop_assert!(lower <= v && v < upper, "{} not in [{}, {})", v, lower, upper);
+                        }
+                        let upper = (0..ulps).fold(x, |u, _| u.next_up());
+                        let v = g.generate_real_in_range_f32(x, upper);
+                        prop_assert!(x <= v && v < upper, "{} not in [{}, {})", v, x, upper);
+                    }
+                )*
+            }
+        };
+    }
+
+    range_properties!(
+        prop_ranges_mt: Mt19937,
+        prop_ranges_mt64: mt64::Mt19937,
+        prop_ranges_xorshift32: Xorshift32,
+        prop_ranges_xorshift64: Xorshift64,
+        prop_ranges_xorshift128: Xorshift128,
+        prop_ranges_xorshift64star: crate::xorshift::Xorshift64star,
+        prop_ranges_xorshift1024star: crate::xorshift::Xorshift1024star,
+        prop_ranges_xoshiro256plusplus: crate::xorshift::Xoshiro256plusplus,
+        prop_ranges_pcg_xsh_rr6432: PcgXshRr6432,
+        prop_ranges_pcg_xsh_rs6432: crate::pcg::PcgXshRs6432,
+        prop_ranges_pcg_xsl_rr6432_mcg: crate::pcg::PcgXslRr6432Mcg,
+        prop_ranges_pcg_xsl_rr: PcgXslRr,
+        prop_ranges_pcg_xsl_rr_mcg: crate::pcg::PcgXslRrMcg,
+        prop_ranges_pcg32: crate::pcg::Pcg32,
+        prop_ranges_pcg64: crate::pcg::Pcg64,
+    );
+
+    macro_rules! extreme_seeds {
+        ($($name:ident: $seed:ty => |$s:ident| $make:expr),* $(,)?) => {
+            $(
+                #[test]
+                fn $name() {
+                    // zero, one, all ones and the alternating bits, past the first block of Mersenne Twister;
+                    // a debug build panics on any overflow of the seeding or the generation
+                    for $s in [0, 1, <$seed>::MAX, <$seed>::MAX / 3, !(<$seed>::MAX / 3)] {
+                        let mut g = $make;
+                        for _ in 0..700 {
+                            g.generate();
+                        }
+                    }
+                }
+            )*
+        };
+    }
+
+    extreme_seeds!(
+        test_extreme_seeds_mt_key: u32 => |s| Mt19937::with_key([s, s, s]),
+        test_extreme_seeds_mt_array: u64 => |s| Mt19937::with_array(vec![s; 700]),
+        test_extreme_seeds_mt64: u64 => |s| mt64::Mt19937::with_array([s, s, s]),
+        test_extreme_seeds_xorshift32: u32 => |s| Xorshift32::with_seed(s),
+        test_extreme_seeds_xorshift64: u64 => |s| Xorshift64::with_seed(s),
+        test_extreme_seeds_xorshift128: u128 => |s| Xorshift128::with_seed(s),
+        test_extreme_seeds_xorshift64star: u64 => |s| crate::xorshift::Xorshift64star::with_seed(s),
+        test_extreme_seeds_xorshift1024star: u64 => |s| crate::xorshift::Xorshift1024star::with_seed([s; 16]),
+        test_extreme_seeds_xoshiro256plusplus: u64 => |s| crate::xorshift::Xoshiro256plusplus::with_seed([s; 4]),
+        test_extreme_seeds_pcg_xsh_rr6432: u64 => |s| PcgXshRr6432::with_seed(s),
+        test_extreme_seeds_pcg_xsh_rs6432: u64 => |s| crate::pcg::PcgXshRs6432::with_seed(s),
+        test_extreme_seeds_pcg_xsl_rr6432_mcg: u64 => |s| crate::pcg::PcgXslRr6432Mcg::with_seed(s),
+        test_extreme_seeds_pcg_xsl_rr: u128 => |s| PcgXslRr::with_seed(s),
+        test_extreme_seeds_pcg_xsl_rr_mcg: u128 => |s| crate::pcg::PcgXslRrMcg::with_seed(s),
+        test_extreme_seeds_pcg32: u64 => |s| crate::pcg::Pcg32::with_stream(s, s),
+        test_extreme_seeds_pcg64: u128 => |s| crate::pcg::Pcg64::with_stream(s, s),
+        test_extreme_seeds_xorshift64_lanes: u64 => |s| crate::lanes::Xorshift64x4::with_seeds([s; 4]),
+        test_extreme_seeds_xorshift64star_lanes: u64 => |s| crate::lanes::Xorshift64starLanes::<8>::with_seeds([s; 8]),
+        test_extreme_seeds_xoshiro256plusplus_lanes: u64 => |s| crate::lanes::Xoshiro256plusplusx4::with_seeds([[s; 4]; 4]),
+        test_extreme_seeds_pcg_xsh_rr6432_lanes: u64 => |s| crate::lanes::PcgXshRr6432Lanes::<4>::with_seeds([s; 4]),
+    );
+}
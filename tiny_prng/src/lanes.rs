@@ -0,0 +1,283 @@
+//! The `lanes` module implements lane-parallel variants of the generators for batch throughput.
+//!
+//! A lane-parallel generator keeps `L` scalar generators side by side and advances all of them with one step,
+//! which the compiler maps onto vector registers. Lane `i` is the scalar generator seeded with the `i`-th seed,
+//! so a batch can be reproduced lane by lane.
+//! `fill` is compiled twice from the same loop, portably and with AVX2 enabled so that the loop auto-vectorizes
+//! onto the wider registers, and picks the latter at runtime on x86_64 CPUs supporting it.
+//! `fill_u64` (`fill_u32` for the 32bit outputs) writes the same outputs into a flat slice as the batch APIs
+//! of the `Generator` trait do.
+//!
+//! The variants are `Xorshift64Lanes` (xorshift64), `Xorshift64starLanes` (the scrambled xorshift64*),
+//! `Xoshiro256plusplusLanes` (xoshiro256++) and `PcgXshRr6432Lanes` (PCG-XSH-RR-64/32), with aliases for 4 and 8 lanes.
+//!
+//! ```rust
+//! use tiny_prng::lanes::Xorshift64x4;
+//! use tiny_prng::xorshift::Xorshift64;
+//!
+//! fn main(){
+//!     let mut x = Xorshift64x4::with_seeds([1, 2, 3, 4]);
+//!     let mut batch = vec![[0u64; 4]; 1000];
+//!     x.fill(&mut batch);
+//!     // the third lane is the scalar generator seeded with 3
+//!     let mut s = Xorshift64::with_seed(3);
+//!     assert!(batch.iter().all(|b| b[2] == s.generate()));
+//! }
+//! ```
+
+use crate::pcg::PcgXshRr6432;
+use crate::xorshift::{Xorshift64, Xorshift64star, Xoshiro256plusplus};
+
+macro_rules! impl_lanes {
+    ($(#[$doc:meta])* $name:ident, $scalar:ty, $seed:ty, $word:ty, $fill_flat:ident) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone)]
+        pub struct $name<const L: usize> {
+            lanes: [$scalar; L],
+        }
+
+        impl<const L: usize> $name<L> {
+            /// construct the lanes; lane `i` is seeded with `seeds[i]` as the scalar generator
+            #[inline]
+            pub fn with_seeds(seeds: [$seed; L]) -> Self {
+                Self { lanes: seeds.map(<$scalar>::with_seed) }
+            }
+
+            /// advance every lane once and return their outputs
+            #[inline(always)]
+            pub fn generate(&mut self) -> [$word; L] {
+                let mut out = [0; L];
+                // the scalar steps are inlined, so the loop over the lanes vectorizes
+                for (o, g) in out.iter_mut().zip(self.lanes.iter_mut()) {
+                    *o = g.generate();
+                }
+                out
+            }
+
+            /// fill the slice with the outputs of successive steps
+            pub fn fill(&mut self, dest: &mut [[$word; L]]) {
+                #[cfg(target_arch = "x86_64")]
+                {
+                    if std::is_x86_feature_detected!("avx2") {
+                        // SAFETY: the CPU supports AVX2
+                        unsafe { self.fill_avx2(dest) };
+                        return;
+                    }
+                }
+                self.fill_portable(dest);
+            }
+
+            /// fill the flat slice with the outputs of successive steps, lane by lane within a step;
+            /// a tail shorter than `L` takes the first lanes of one more step
+            pub fn $fill_flat(&mut self, dest: &mut [$word]) {
+                let (steps, tail) = dest.as_chunks_mut::<L>();
+                self.fill(steps);
+                if !tail.is_empty() {
+                    let out = self.generate();
+                    tail.copy_from_slice(&out[..tail.len()]);
+                }
+            }
+
+            fn fill_portable(&mut self, dest: &mut [[$word; L]]) {
+                for d in dest {
+                    *d = self.generate();
+                }
+            }
+
+            // the same body as `fill_portable`, compiled with AVX2 enabled so that the loop auto-vectorizes
+            #[cfg(target_arch = "x86_64")]
+            #[target_feature(enable = "avx2")]
+            unsafe fn fill_avx2(&mut self, dest: &mut [[$word; L]]) {
+                for d in dest {
+                    *d = self.generate();
+                }
+            }
+        }
+    };
+}
+
+impl_lanes!(
+    /// Lane-parallel xorshift64, matching `xorshift::Xorshift64` per lane.
+    Xorshift64Lanes, Xorshift64, u64, u64, fill_u64
+);
+
+impl_lanes!(
+    /// Lane-parallel xorshift64*, matching `xorshift::Xorshift64star` per lane.
+    Xorshift64starLanes, Xorshift64star, u64, u64, fill_u64
+);
+
+impl_lanes!(
+    /// Lane-parallel xoshiro256++, matching `xorshift::Xoshiro256plusplus` per lane.
+    Xoshiro256plusplusLanes, Xoshiro256plusplus, [u64; 4], u64, fill_u64
+);
+
+impl_lanes!(
+    /// Lane-parallel PCG-XSH-RR-64/32, matching `pcg::PcgXshRr6432` per lane.
+    PcgXshRr6432Lanes, PcgXshRr6432, u64, u32, fill_u32
+);
+
+pub type Xorshift64x4 = Xorshift64Lanes<4>;
+pub type Xorshift64x8 = Xorshift64Lanes<8>;
+pub type Xorshift64starx4 = Xorshift64starLanes<4>;
+pub type Xorshift64starx8 = Xorshift64starLanes<8>;
+pub type Xoshiro256plusplusx4 = Xoshiro256plusplusLanes<4>;
+pub type Xoshiro256plusplusx8 = Xoshiro256plusplusLanes<8>;
+pub type PcgXshRr6432x4 = PcgXshRr6432Lanes<4>;
+pub type PcgXshRr6432x8 = PcgXshRr6432Lanes<8>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEEDS: [u64; 8] = [
+        0x1818729, 0x2, 0x123456789abcdef, 0xffffffffffffffff,
+        0x8000000000000000, 0x5555555555555555, 0xdeadbeef, 0x3,
+    ];
+    const STEPS: usize = 1000;
+
+    macro_rules! check_lanes {
+        ($lanes:ty, $scalar:ty, $l:expr) => {
+            check_lanes!($lanes, $scalar, $l, |s| s)
+        };
+        // the seeds of the lanes made from `SEEDS`
+        ($lanes:ty, $scalar:ty, $l:expr, $seed:expr) => {{
+            let seeds: [u64; $l] = SEEDS[..$l].try_into().unwrap();
+            let seeds = seeds.map($seed);
+            let mut scalars: Vec<$scalar> = seeds.iter().map(|&s| <$scalar>::with_seed(s)).collect();
+            let mut lanes = <$lanes>::with_seeds(seeds);
+            for _ in 0..STEPS {
+                let out = lanes.generate();
+                for (o, s) in out.iter().zip(scalars.iter_mut()) {
+                    assert_eq!(s.generate(), *o);
+                }
+            }
+            // both fill paths continue the same streams
+            let mut batch = vec![[0; $l]; STEPS];
+            lanes.clone().fill_portable(&mut batch);
+            let mut dispatched = vec![[0; $l]; STEPS];
+            lanes.fill(&mut dispatched);
+            assert_eq!(batch, dispatched);
+            for b in &batch {
+                for (o, s) in b.iter().zip(scalars.iter_mut()) {
+                    assert_eq!(s.generate(), *o);
+                }
+            }
+        }};
+    }
+
+    #[test]
+    fn test_xorshift64_lanes_match_scalar() {
+        check_lanes!(Xorshift64x4, Xorshift64, 4);
+        check_lanes!(Xorshift64x8, Xorshift64, 8);
+    }
+
+    #[test]
+    fn test_xorshift64star_lanes_match_scalar() {
+        check_lanes!(Xorshift64starx4, Xorshift64star, 4);
+        check_lanes!(Xorshift64starx8, Xorshift64star, 8);
+    }
+
+    #[test]
+    fn test_xoshiro256plusplus_lanes_match_scalar() {
+        let seed = |s: u64| [s, s.rotate_left(17), !s, 1];
+        check_lanes!(Xoshiro256plusplusx4, Xoshiro256plusplus, 4, seed);
+        check_lanes!(Xoshiro256plusplusx8, Xoshiro256plusplus, 8, seed);
+    }
+
+    #[test]
+    fn test_pcg_lanes_match_scalar() {
+        check_lanes!(PcgXshRr6432x4, PcgXshRr6432, 4);
+        check_lanes!(PcgXshRr6432x8, PcgXshRr6432, 8);
+    }
+
+    #[test]
+    fn test_fill_empty() {
+        let mut x = Xorshift64x4::with_seeds([1, 2, 3, 4]);
+        x.fill(&mut []);
+        x.fill_u64(&mut []);
+        assert_eq!(Xorshift64x4::with_seeds([1, 2, 3, 4]).generate(), x.generate());
+    }
+
+    #[test]
+    fn test_fill_flat() {
+        // the flat slice holds the steps one after another, and the tail the first lanes of one more step
+        let (mut a, mut b) = (Xorshift64x4::with_seeds([1, 2, 3, 4]), Xorshift64x4::with_seeds([1, 2, 3, 4]));
+        let mut flat = vec![0u64; 4 * 100 + 3];
+        a.fill_u64(&mut flat);
+        let mut batch = vec![[0u64; 4]; 101];
+        b.fill(&mut batch);
+        assert_eq!(&batch.concat()[..flat.len()], &flat[..]);
+        assert_eq!(a.generate(), b.generate());
+        let (mut a, mut b) = (PcgXshRr6432x8::with_seeds(SEEDS), PcgXshRr6432x8::with_seeds(SEEDS));
+        let mut flat = vec![0u32; 8 * 10];
+        a.fill_u32(&mut flat);
+        assert!(flat.chunks(8).all(|c| c == b.generate()));
+        assert_eq!(a.generate(), b.generate());
+    }
+
+    // 10 million outputs in total for every benchmark
+
+    #[bench]
+    fn bench_xorshift64_scalar_4streams_10mil(b: &mut test::Bencher) {
+        b.iter(|| {
+            let mut s: Vec<Xorshift64> = SEEDS[..4].iter().map(|&s| Xorshift64::with_seed(s)).collect();
+            let mut v = 0u64;
+            for _ in 0..2500000usize {
+                for x in s.iter_mut() {
+                    v ^= x.generate();
+                }
+            }
+            println!("{:x}", v);
+        })
+    }
+
+    #[bench]
+    fn bench_xorshift64x4_10mil(b: &mut test::Bencher) {
+        let mut batch = vec![[0u64; 4]; 2500];
+        b.iter(|| {
+            let mut x = Xorshift64x4::with_seeds([1, 2, 3, 4]);
+            for _ in 0..1000usize {
+                x.fill(&mut batch);
+            }
+            println!("{:x}", batch[0][0]);
+        })
+    }
+
+    #[bench]
+    fn bench_xorshift64x8_10mil(b: &mut test::Bencher) {
+        let mut batch = vec![[0u64; 8]; 1250];
+        b.iter(|| {
+            let mut x = Xorshift64x8::with_seeds(SEEDS);
+            for _ in 0..1000usize {
+                x.fill(&mut batch);
+            }
+            println!("{:x}", batch[0][0]);
+        })
+    }
+
+    #[bench]
+    fn bench_pcgxshrr6432_scalar_8streams_10mil(b: &mut test::Bencher) {
+        b.iter(|| {
+            let mut s: Vec<PcgXshRr6432> = SEEDS.iter().map(|&s| PcgXshRr6432::with_seed(s)).collect();
+            let mut v = 0u32;
+            for _ in 0..1250000usize {
+                for x in s.iter_mut() {
+                    v ^= x.generate();
+                }
+            }
+            println!("{:x}", v);
+        })
+    }
+
+    #[bench]
+    fn bench_pcgxshrr6432x8_10mil(b: &mut test::Bencher) {
+        let mut batch = vec![[0u32; 8]; 1250];
+        b.iter(|| {
+            let mut x = PcgXshRr6432x8::with_seeds(SEEDS);
+            for _ in 0..1000usize {
+                x.fill(&mut batch);
+            }
+            println!("{:x}", batch[0][0]);
+        })
+    }
+}
@@ -1,5 +1,5 @@
 //! [!NOTE]
-//! Beta release. The quality of generated pseudo random numbers is not tested at now.
+//! Beta release. The quality of generated pseudo random numbers can be checked with the statistical tests in the `quality` module.
 //! 
 //! This crate provides common psuedo random number generators written in pure Rust, which include:
 //! 
@@ -7,15 +7,34 @@
 //! | name                                 | supported mode                                                                              | cycle period                                                                                                | reference                                                                                                                                                                                                                    |
 //! |--------------------------------------|---------------------------------------------------------------------------------------------|-------------------------------------------------------------------------------------------------------------|------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------|
 //! | Mersenne Twister                     | `MT19937` `MT19937_64`                                                                      | 2<sup>19937</sup>-1                                                                                         | [Saitoh and Matsumoto (1997)](https://www.math.sci.hiroshima-u.ac.jp/m-mat/MT/MT2002/emt19937ar.html)                                                                                                                        |
-//! | Xorshift                             | `xorshift32` <br/>`xorshift64`<br/>`xorshift128`<br/>`xorshift64*`<br/>`xorshift1024*`<br/> | 2<sup>64</sup>-1 <br/>2<sup>64</sup>-1 <br/>2<sup>128</sup>-1 <br/>2<sup>64</sup>-1 <br/>2<sup>1024</sup>-1 | [Marsaglia (2003), J. Stat. Softw. 8 (14)](https://www.jstatsoft.org/index.php/jss/article/view/v008i14/916)<br/> [Vigna (2016), ACM Trans. Math. Softw. Vol. 42 (4), 30](https://vigna.di.unimi.it/ftp/papers/xorshift.pdf) |
-//! | PCG (with LCG)                       | `PCG-XSL-RR-128/64` <br/>`PCG-XSH-RS-64/32` <br/>`PCG-XSH-RR-64/32`                         | 2<sup>128</sup> <br/> 2<sup>64</sup> <br/> 2<sup>64</sup>                                                   | [O'Neil (2014), HMC-CS-2014-0905](https://www.pcg-random.org/pdf/hmc-cs-2014-0905.pdf)<br/>[Reference implementation](https://github.com/imneme/pcg-c-basic)                                                                 |
+//! | Xorshift                             | `xorshift32` <br/>`xorshift64`<br/>`xorshift128`<br/>`xorshift64*`<br/>`xorshift1024*`<br/> | 2<sup>32</sup>-1 <br/>2<sup>64</sup>-1 <br/>2<sup>128</sup>-1 <br/>2<sup>64</sup>-1 <br/>2<sup>1024</sup>-1 | [Marsaglia (2003), J. Stat. Softw. 8 (14)](https://www.jstatsoft.org/index.php/jss/article/view/v008i14/916)<br/> [Vigna (2016), ACM Trans. Math. Softw. Vol. 42 (4), 30](https://vigna.di.unimi.it/ftp/papers/xorshift.pdf) |
+//! | PCG (with LCG)                       | `PCG-XSL-RR-128/64` <br/>`PCG-XSH-RS-64/32` <br/>`PCG-XSH-RR-64/32`                         | 2<sup>128</sup> <br/> 2<sup>61</sup> <br/> 2<sup>61</sup>                                                   | [O'Neil (2014), HMC-CS-2014-0905](https://www.pcg-random.org/pdf/hmc-cs-2014-0905.pdf)<br/>[Reference implementation](https://github.com/imneme/pcg-c-basic)                                                                 |
+//!
+//! The periods are verified by the tests: algebraically from the order of the transition over GF(2) for xorshift
+//! and from the jump-ahead of the LCG for PCG, and exhaustively for `xorshift32` in an ignored test.
+//! The 64bit multiplier of PCG-XSH-RS-64/32 and PCG-XSH-RR-64/32 is 3 modulo 4, short of the condition of Hull and Dobell,
+//! which limits their period to 2<sup>61</sup>. `Pcg32` and `Pcg64` with the constants of the reference implementation
+//! have the full periods of 2<sup>64</sup> and 2<sup>128</sup>.
 
 #![feature(test)]
 
 extern crate test;
 
+pub mod distributions;
+#[cfg(feature = "entropy")]
+pub mod entropy;
+pub mod generator;
+pub mod lanes;
 pub mod mt;
 pub mod mt64;
 pub mod xorshift;
 pub mod pcg;
+pub mod quality;
+pub mod real;
+pub mod seed;
+pub mod seq;
+pub mod spectral;
+pub mod vectors;
 mod prelude;
+
+pub use generator::Generator;
@@ -17,18 +17,14 @@
 //! }
 //! ```
 
-use crate::generate_real32;
+use crate::{generate_real, impl_generator32};
+use std::borrow::Borrow;
 
 const N: usize = 624;
-const N64: usize = 312;
 const M: usize = 397;
-const M64: usize = 156;
 const MATRIX_A: u64 = 0x9908b0df;
-const MATRIX_A64: u64 = 0xB5026F5AA96619E9;
 const UPPER_MASK: u64 = 0x80000000;
 const LOWER_MASK: u64 = 0x7fffffff;
-const UPPER_MASK64: u64 = 0xFFFFFFFF80000000;
-const LOWER_MASK64: u64 = 0x7FFFFFFF;
 
 pub struct Mt19937 {
     state: [u64; N],
@@ -41,38 +37,36 @@ impl Mt19937 {
         self.state[0] = s & 0xffffffffu64;
         self.index = 1;
         while self.index < N {
-            self.state[self.index] = 1812433253u64 * (self.state[self.index - 1] ^ (self.state[self.index - 1] >> 30)) + (self.index as u64);
+            self.state[self.index] = 1812433253u64.wrapping_mul(self.state[self.index - 1] ^ (self.state[self.index - 1] >> 30)).wrapping_add(self.index as u64);
             self.state[self.index] &= 0xffffffffu64;
             self.index += 1;
         }
     }
 
+    /// regenerate the whole state
     #[inline]
-    pub fn generate(&mut self) -> u32 {
+    fn twist(&mut self) {
         let mut y: u64;
         const MAG01: [u64; 2] = [0x0u64, MATRIX_A];
-        while self.index >= N {
-            if self.index == N + 1 {
-                self.init(5489u64);
-            }
-            for kk in 0..N - M {
-                y = (self.state[kk] & UPPER_MASK) | (self.state[kk + 1] & LOWER_MASK);
-                self.state[kk] = self.state[kk + M] ^ (y >> 1) ^ MAG01[(y as usize) & 0x1usize];
-            }
-            for kk in N - M..N - 1 {
-                y = (self.state[kk] & UPPER_MASK) | (self.state[kk + 1] & LOWER_MASK);
-                self.state[kk] = self.state[kk + M - N] ^ (y >> 1) ^ MAG01[(y as usize) & 0x1usize];
-            }
-            y = (self.state[N - 1] & UPPER_MASK) | (self.state[0] & LOWER_MASK);
-            self.state[N - 1] = self.state[M - 1] ^ (y >> 1) ^ MAG01[(y as usize) & 0x1usize];
-
-            self.index = 0;
+        if self.index == N + 1 {
+            self.init(5489u64);
         }
-        y = self.state[self.index];
-        self.index += 1;
+        for kk in 0..N - M {
+            y = (self.state[kk] & UPPER_MASK) | (self.state[kk + 1] & LOWER_MASK);
+            self.state[kk] = self.state[kk + M] ^ (y >> 1) ^ MAG01[(y as usize) & 0x1usize];
+        }
+        for kk in N - M..N - 1 {
+            y = (self.state[kk] & UPPER_MASK) | (self.state[kk + 1] & LOWER_MASK);
+            self.state[kk] = self.state[kk + M - N] ^ (y >> 1) ^ MAG01[(y as usize) & 0x1usize];
+        }
+        y = (self.state[N - 1] & UPPER_MASK) | (self.state[0] & LOWER_MASK);
+        self.state[N - 1] = self.state[M - 1] ^ (y >> 1) ^ MAG01[(y as usize) & 0x1usize];
 
+        self.index = 0;
+    }
 
-        // tempering
+    #[inline]
+    fn temper(mut y: u64) -> u32 {
         y ^= y >> 11;
         y ^= (y << 7) & 0x9d2c5680;
         y ^= (y << 15) & 0xefc60000;
@@ -80,32 +74,49 @@ impl Mt19937 {
         y as u32
     }
 
-
     #[inline]
-    pub fn with_array(init_key: Vec<u64>) -> Self {
-        let mut i: usize;
-        let mut j: usize;
-        let k: usize;
+    pub fn generate(&mut self) -> u32 {
+        if self.index >= N {
+            self.twist();
+        }
+        let y = self.state[self.index];
+        self.index += 1;
+        Self::temper(y)
+    }
+
+    /// fill the slice with the outputs, tempering the state block by block
+    pub fn fill(&mut self, dest: &mut [u32]) {
+        let mut filled = 0;
+        while filled < dest.len() {
+            if self.index >= N {
+                self.twist();
+            }
+            let n = (N - self.index).min(dest.len() - filled);
+            for (d, &y) in dest[filled..filled + n].iter_mut().zip(&self.state[self.index..self.index + n]) {
+                *d = Self::temper(y);
+            }
+            self.index += n;
+            filled += n;
+        }
+    }
+
+
+    /// the initialization by array of mt19937ar up to its final `mt[0] = 0x80000000`
+    fn init_by_array(&mut self, init_key: &[u32]) {
         let key_len = init_key.len();
-        let mut mt = Self {
-            state: [0; N],
-            index: 0,
-        };
-        mt.init(19650218);
-        i = 1;
-        j = 0;
-        k = match N > key_len {
-            true => N,
-            false => key_len.clone(),
-        };
-        for _ in 0..k {
-            mt.state[i] = (mt.state[i] ^ ((mt.state[i - 1] ^ (mt.state[i - 1] >> 30)) * 1664525))
-                + init_key[j] + (j as u64);
-            mt.state[i] &= 0xffffffff;
+        self.init(19650218);
+        let mut i = 1;
+        let mut j = 0;
+        for _ in 0..N.max(key_len) {
+            let prev = self.state[i - 1] ^ (self.state[i - 1] >> 30);
+            self.state[i] = (self.state[i] ^ prev.wrapping_mul(1664525))
+                .wrapping_add(init_key[j] as u64)
+                .wrapping_add(j as u64);
+            self.state[i] &= 0xffffffff;
             i += 1;
             j += 1;
             if i >= N {
-                mt.state[0] = mt.state[N - 1];
+                self.state[0] = self.state[N - 1];
                 i = 1;
             }
             if j >= key_len {
@@ -113,26 +124,140 @@ impl Mt19937 {
             }
         }
         for _ in 0..N - 1 {
-            mt.state[i] = (mt.state[i] ^ ((mt.state[i - 1] ^ (mt.state[i - 1] >> 30)) * 1566083941))
-                - (i as u64);
-            mt.state[i] &= 0xffffffff;
+            let prev = self.state[i - 1] ^ (self.state[i - 1] >> 30);
+            self.state[i] = (self.state[i] ^ prev.wrapping_mul(1566083941)).wrapping_sub(i as u64);
+            self.state[i] &= 0xffffffff;
             i += 1;
             if i >= N {
-                mt.state[0] = mt.state[N - 1];
+                self.state[0] = self.state[N - 1];
                 i = 1;
             }
         }
+    }
+
+    /// seed with the key, or with `init_genrand(5489)`, the default seed of the reference, for an empty key
+    fn keyed(init_key: &[u32]) -> Self {
+        let mut mt = Self {
+            state: [0; N],
+            index: 0,
+        };
+        match init_key.is_empty() {
+            true => mt.init(5489),
+            false => mt.init_by_array(init_key),
+        }
+        mt
+    }
+
+    /// seed with a key of 32bit words exactly as `init_by_array` of mt19937ar.
+    ///
+    /// The key is a slice, an array, a vector or any iterator of words. An empty key seeds as
+    /// `init_genrand(5489)`, the default seed of the reference.
+    pub fn with_key<K>(init_key: K) -> Self
+    where
+        K: IntoIterator,
+        K::Item: Borrow<u32>,
+    {
+        let init_key: Vec<u32> = init_key.into_iter().map(|k| *k.borrow()).collect();
+        let mut mt = Self::keyed(&init_key);
+        if !init_key.is_empty() {
+            // the most significant bit is set, assuring a non-zero initial state
+            mt.state[0] = UPPER_MASK;
+        }
         mt
     }
 
-    generate_real32!();
+    /// seed with a key of 64bit words by the initialization by array, leaving out its final `mt[0] = 0x80000000`.
+    ///
+    /// Only the lower 32 bits of each word take part, as the masked sums of the reference wrap around.
+    /// The omission keeps the sequences seeded so far, unlike `with_key`, which follows the reference.
+    /// The key is a slice, an array, a vector or any iterator of words. An empty key seeds as
+    /// `init_genrand(5489)`, the default seed of the reference.
+    pub fn with_array<K>(init_key: K) -> Self
+    where
+        K: IntoIterator,
+        K::Item: Borrow<u64>,
+    {
+        let init_key: Vec<u32> = init_key.into_iter().map(|k| *k.borrow() as u32).collect();
+        Self::keyed(&init_key)
+    }
+
+    generate_real!();
 }
 
+impl_generator32!(Mt19937, fill);
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
 
+    #[test]
+    fn test_fill_equals_generate() {
+        let mut a = Mt19937::with_array(vec![0x123, 0x234, 0x345, 0x456]);
+        let mut b = Mt19937::with_array(vec![0x123, 0x234, 0x345, 0x456]);
+        // start in the middle of a block and cross several block boundaries
+        for _ in 0..100 {
+            assert_eq!(a.generate(), b.generate());
+        }
+        let mut v = vec![0u32; 3 * N + 7];
+        a.fill(&mut v);
+        assert!(v.iter().all(|&x| x == b.generate()));
+        let mut w = [0u32; 10];
+        crate::Generator::fill_u32(&mut a, &mut w);
+        assert!(w.iter().all(|&x| x == b.generate()));
+        a.fill(&mut []);
+        assert_eq!(a.generate(), b.generate());
+    }
+
+    #[test]
+    fn test_with_key_reference() {
+        // the first outputs of mt19937ar.out
+        let mut mt = Mt19937::with_key([0x123, 0x234, 0x345, 0x456]);
+        let outputs: Vec<u32> = (0..5).map(|_| mt.generate()).collect();
+        assert_eq!(vec![1067595299, 955945823, 477289528, 4107218783, 4228976476], outputs);
+    }
+
+    #[test]
+    fn test_with_key_takes_slices_and_iterators() {
+        let key = vec![0x123u32, 0x234, 0x345, 0x456];
+        let expected = Mt19937::with_key(key.clone()).generate();
+        assert_eq!(expected, Mt19937::with_key(&key).generate());
+        assert_eq!(expected, Mt19937::with_key(&key[..]).generate());
+        assert_eq!(expected, Mt19937::with_key((1..5).map(|k| k * 0x111 + 0x12)).generate());
+        let expected = Mt19937::with_array(vec![0x123, 0x234, 0x345, 0x456]).generate();
+        assert_eq!(expected, Mt19937::with_array([0x123u64, 0x234, 0x345, 0x456].iter()).generate());
+        assert_eq!(expected, Mt19937::with_array(&[0x123u64, 0x234, 0x345, 0x456][..]).generate());
+    }
+
+    #[test]
+    fn test_with_array_leaves_out_msb() {
+        // `with_array` keeps its stream without `mt[0] = 0x80000000`, which changes the first output,
+        // and the twist carries the difference on from the output `N - M`
+        let mut a = Mt19937::with_array([0x123, 0x234, 0x345, 0x456]);
+        let mut b = Mt19937::with_key([0x123, 0x234, 0x345, 0x456]);
+        assert_ne!(a.generate(), b.generate());
+        for _ in 1..N - M {
+            assert_eq!(a.generate(), b.generate());
+        }
+        assert!((N - M..N).any(|_| a.generate() != b.generate()));
+    }
+
+    #[test]
+    fn test_with_key_empty() {
+        // the first output of init_genrand(5489)
+        assert_eq!(3499211612, Mt19937::with_key(Vec::<u32>::new()).generate());
+        assert_eq!(3499211612, Mt19937::with_array(Vec::<u64>::new()).generate());
+    }
+
+    #[test]
+    fn test_with_array_wide_words() {
+        let mut a = Mt19937::with_array([u64::MAX, 0xffff_ffff_0000_0001, 1 << 32]);
+        let mut b = Mt19937::with_array([u32::MAX as u64, 1, 0]);
+        for _ in 0..N + 1 {
+            assert_eq!(a.generate(), b.generate());
+        }
+    }
+
     #[test]
     fn test_mt_init_and_generate_with_long_array() {
         let mut mt = Mt19937::with_array([0x1; N + 32].to_vec());
@@ -151,7 +276,7 @@ mod tests {
             true => sum - u32::MAX / 2,
             false => u32::MAX / 2 - sum,
         };
-        assert_eq!(true, diff < acceptable_delta);
+        assert!(diff < acceptable_delta);
     }
 
 
@@ -168,7 +293,7 @@ mod tests {
             true => sum - 0.5,
             false => 0.5 - sum,
         };
-        assert_eq!(true, diff < acceptable_delta);
+        assert!(diff < acceptable_delta);
     }
 
     #[test]
@@ -184,7 +309,25 @@ mod tests {
             true => sum - 0.5,
             false => 0.5 - sum,
         };
-        assert_eq!(true, diff < acceptable_delta);
+        assert!(diff < acceptable_delta);
+    }
+
+    #[test]
+    fn test_quality_genrand_real_f32_average100000() {
+        let mut mt = Mt19937::with_array(vec![0x123, 0x234, 0x345, 0x456]);
+        let mut sum = 0.0;
+        let max_count = 100000;
+        let acceptable_delta = 1.0 / 100.0;
+        for _ in 0..max_count {
+            let v = mt.generate_real_f32();
+            assert!((0.0..1.0).contains(&v));
+            sum += v as f64 / max_count as f64;
+        }
+        let diff = match sum > 0.5 {
+            true => sum - 0.5,
+            false => 0.5 - sum,
+        };
+        assert!(diff < acceptable_delta);
     }
 
     #[bench]
@@ -198,4 +341,14 @@ mod tests {
             println!("{:x}", v);
         })
     }
+
+    #[bench]
+    fn bench_mt19937_32_fill_10mil(b: &mut test::Bencher) {
+        let mut v = vec![0u32; 10000000];
+        b.iter(|| {
+            let mut s = Mt19937::with_array(vec![0x123, 0x456, 0x789, 0xabc, 0xdef]);
+            s.fill(&mut v);
+            println!("{:x}", v[v.len() - 1]);
+        })
+    }
 }
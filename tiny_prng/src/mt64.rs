@@ -17,7 +17,8 @@
 //! }
 //! ```
 
-use crate::generate_real64;
+use crate::{generate_real, impl_generator64};
+use std::borrow::Borrow;
 
 const N: usize = 312;
 const M: usize = 156;
@@ -33,74 +34,90 @@ pub struct Mt19937 {
 impl Mt19937 {
     #[inline]
     fn init_genrand(&mut self, s: u64) {
-        self.state[0] = s & 0xffffffffffffffffu64;
+        self.state[0] = s;
         self.index = 1;
         while self.index < N {
-            self.state[self.index] = 6364136223846793005u64.wrapping_mul(self.state[self.index - 1] ^ (self.state[self.index - 1] >> 30)) + (self.index as u64);
+            self.state[self.index] = 6364136223846793005u64.wrapping_mul(self.state[self.index - 1] ^ (self.state[self.index - 1] >> 30)).wrapping_add(self.index as u64);
             self.state[self.index] &= 0x5555555555555555u64;
             self.index += 1;
         }
     }
 
+    /// regenerate the whole state
     #[inline]
-    pub fn generate(&mut self) -> u64 {
+    fn twist(&mut self) {
         let mut y: u64;
         const MAG01: [u64; 2] = [0x0u64, MATRIX_A];
-        while self.index >= N {
-            if self.index == N + 1 {
-                self.init_genrand(5489u64);
-            }
-            for kk in 0..N - M {
-                y = (self.state[kk] & UPPER_MASK) | (self.state[kk + 1] & LOWER_MASK);
-                self.state[kk] = self.state[kk + M] ^ (y >> 1) ^ MAG01[(y as usize) & 0x1usize];
-            }
-            for kk in N - M..N - 1 {
-                y = (self.state[kk] & UPPER_MASK) | (self.state[kk + 1] & LOWER_MASK);
-                self.state[kk] = self.state[kk + M - N] ^ (y >> 1) ^ MAG01[(y as usize) & 0x1usize];
-            }
-            y = (self.state[N - 1] & UPPER_MASK) | (self.state[0] & LOWER_MASK);
-            self.state[N - 1] = self.state[M - 1] ^ (y >> 1) ^ MAG01[(y as usize) & 0x1usize];
-
-            self.index = 0;
+        if self.index == N + 1 {
+            self.init_genrand(5489u64);
         }
-        y = self.state[self.index];
-        self.index += 1;
+        for kk in 0..N - M {
+            y = (self.state[kk] & UPPER_MASK) | (self.state[kk + 1] & LOWER_MASK);
+            self.state[kk] = self.state[kk + M] ^ (y >> 1) ^ MAG01[(y as usize) & 0x1usize];
+        }
+        for kk in N - M..N - 1 {
+            y = (self.state[kk] & UPPER_MASK) | (self.state[kk + 1] & LOWER_MASK);
+            self.state[kk] = self.state[kk + M - N] ^ (y >> 1) ^ MAG01[(y as usize) & 0x1usize];
+        }
+        y = (self.state[N - 1] & UPPER_MASK) | (self.state[0] & LOWER_MASK);
+        self.state[N - 1] = self.state[M - 1] ^ (y >> 1) ^ MAG01[(y as usize) & 0x1usize];
 
+        self.index = 0;
+    }
 
-        // tempering
+    #[inline]
+    fn temper(mut y: u64) -> u64 {
         y ^= y >> 11;
         y ^= (y << 7) & 0x9d2c5680;
         y ^= (y << 15) & 0xefc60000;
         y ^= y >> 18;
-        y as u64
+        y
     }
 
-
     #[inline]
-    pub fn with_array(init_key: Vec<u64>) -> Self {
-        let mut i: usize;
-        let mut j: usize;
-        let k: usize;
+    pub fn generate(&mut self) -> u64 {
+        if self.index >= N {
+            self.twist();
+        }
+        let y = self.state[self.index];
+        self.index += 1;
+        Self::temper(y)
+    }
+
+    /// fill the slice with the outputs, tempering the state block by block
+    pub fn fill(&mut self, dest: &mut [u64]) {
+        let mut filled = 0;
+        while filled < dest.len() {
+            if self.index >= N {
+                self.twist();
+            }
+            let n = (N - self.index).min(dest.len() - filled);
+            for (d, &y) in dest[filled..filled + n].iter_mut().zip(&self.state[self.index..self.index + n]) {
+                *d = Self::temper(y);
+            }
+            self.index += n;
+            filled += n;
+        }
+    }
+
+
+    /// the initialization by array, taking the lower 32 bits of each word of the key; as `mt::Mt19937::with_array`,
+    /// it leaves out the final `mt[0]` assignment of the reference
+    fn init_by_array(&mut self, init_key: &[u64]) {
         let key_len = init_key.len();
-        let mut mt = Self {
-            state: [0; N],
-            index: 0,
-        };
-        mt.init_genrand(19650218);
-        i = 1;
-        j = 0;
-        k = match N > key_len {
-            true => N,
-            false => key_len.clone(),
-        };
-        for _ in 0..k {
-            mt.state[i] = (mt.state[i] ^ ((mt.state[i - 1] ^ (mt.state[i - 1] >> 30)) * 1664525))
-                + init_key[j] + (j as u64);
-            mt.state[i] &= 0xffffffff;
+        self.init_genrand(19650218);
+        let mut i = 1;
+        let mut j = 0;
+        for _ in 0..N.max(key_len) {
+            let prev = self.state[i - 1] ^ (self.state[i - 1] >> 30);
+            self.state[i] = (self.state[i] ^ prev.wrapping_mul(1664525))
+                .wrapping_add(init_key[j])
+                .wrapping_add(j as u64);
+            self.state[i] &= 0xffffffff;
             i += 1;
             j += 1;
             if i >= N {
-                mt.state[0] = mt.state[N - 1];
+                self.state[0] = self.state[N - 1];
                 i = 1;
             }
             if j >= key_len {
@@ -108,25 +125,93 @@ impl Mt19937 {
             }
         }
         for _ in 0..N - 1 {
-            mt.state[i] = (mt.state[i] ^ ((mt.state[i - 1] ^ (mt.state[i - 1] >> 30)) * 1566083941))
-                - (i as u64);
-            mt.state[i] &= 0xffffffff;
+            let prev = self.state[i - 1] ^ (self.state[i - 1] >> 30);
+            self.state[i] = (self.state[i] ^ prev.wrapping_mul(1566083941)).wrapping_sub(i as u64);
+            self.state[i] &= 0xffffffff;
             i += 1;
             if i >= N {
-                mt.state[0] = mt.state[N - 1];
+                self.state[0] = self.state[N - 1];
                 i = 1;
             }
         }
+    }
+
+    /// seed with a key of 64bit words.
+    ///
+    /// The key is a slice, an array, a vector or any iterator of words. Only the lower 32 bits of each word
+    /// take part, as the masked sums wrap around. An empty key seeds as `init_genrand(5489)`, the default seed.
+    pub fn with_array<K>(init_key: K) -> Self
+    where
+        K: IntoIterator,
+        K::Item: Borrow<u64>,
+    {
+        let init_key: Vec<u64> = init_key.into_iter().map(|k| *k.borrow()).collect();
+        let mut mt = Self {
+            state: [0; N],
+            index: 0,
+        };
+        match init_key.is_empty() {
+            true => mt.init_genrand(5489),
+            false => mt.init_by_array(&init_key),
+        }
         mt
     }
 
-    generate_real64!();
+    generate_real!();
 }
 
+impl_generator64!(Mt19937, fill);
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_fill_equals_generate() {
+        let mut a = Mt19937::with_array(vec![0x123, 0x234, 0x345, 0x456]);
+        let mut b = Mt19937::with_array(vec![0x123, 0x234, 0x345, 0x456]);
+        // start in the middle of a block and cross several block boundaries
+        for _ in 0..100 {
+            assert_eq!(a.generate(), b.generate());
+        }
+        let mut v = vec![0u64; 3 * N + 7];
+        a.fill(&mut v);
+        assert!(v.iter().all(|&x| x == b.generate()));
+        let mut w = [0u64; 10];
+        crate::Generator::fill_u64(&mut a, &mut w);
+        assert!(w.iter().all(|&x| x == b.generate()));
+        a.fill(&mut []);
+        assert_eq!(a.generate(), b.generate());
+    }
+
+    #[test]
+    fn test_with_array_takes_slices_and_iterators() {
+        let key = vec![0x123u64, 0x234, 0x345, 0x456];
+        let expected = Mt19937::with_array(key.clone()).generate();
+        assert_eq!(expected, Mt19937::with_array(&key).generate());
+        assert_eq!(expected, Mt19937::with_array(&key[..]).generate());
+        assert_eq!(expected, Mt19937::with_array((1..5).map(|k| k * 0x111 + 0x12)).generate());
+    }
+
+    #[test]
+    fn test_with_array_empty() {
+        // an unseeded state is initialized with the default seed at the first twist
+        let mut a = Mt19937::with_array(Vec::<u64>::new());
+        let mut b = Mt19937 { state: [0; N], index: N + 1 };
+        for _ in 0..N + 1 {
+            assert_eq!(a.generate(), b.generate());
+        }
+    }
+
+    #[test]
+    fn test_with_array_wide_words() {
+        let mut a = Mt19937::with_array([u64::MAX, 0xffff_ffff_0000_0001, 1 << 32]);
+        let mut b = Mt19937::with_array([u32::MAX as u64, 1, 0]);
+        for _ in 0..N + 1 {
+            assert_eq!(a.generate(), b.generate());
+        }
+    }
+
     #[test]
     fn test_mt64_init_and_generate_with_long_array() {
         let mut mt = Mt19937::with_array([0x1; N + 32].to_vec());
@@ -145,7 +230,7 @@ mod tests {
             true => sum - u64::MAX / 2,
             false => u64::MAX / 2 - sum,
         };
-        assert_eq!(true, diff < acceptable_delta);
+        assert!(diff < acceptable_delta);
     }
 
 
@@ -162,7 +247,7 @@ mod tests {
             true => sum - 0.5,
             false => 0.5 - sum,
         };
-        assert_eq!(true, diff < acceptable_delta);
+        assert!(diff < acceptable_delta);
     }
 
     #[test]
@@ -178,7 +263,7 @@ mod tests {
             true => sum - 0.5,
             false => 0.5 - sum,
         };
-        assert_eq!(true, diff < acceptable_delta);
+        assert!(diff < acceptable_delta);
     }
 
     #[test]
@@ -194,7 +279,25 @@ mod tests {
             true => sum - 0.5,
             false => 0.5 - sum,
         };
-        assert_eq!(true, diff < acceptable_delta);
+        assert!(diff < acceptable_delta);
+    }
+
+    #[test]
+    fn test_mean_genrand_real_f32_average100000() {
+        let mut mt = Mt19937::with_array(vec![0x123, 0x234, 0x345, 0x456]);
+        let mut sum = 0.0;
+        let max_count = 100000;
+        let acceptable_delta = 1.0 / 100.0;
+        for _ in 0..max_count {
+            let v = mt.generate_real_f32();
+            assert!((0.0..1.0).contains(&v));
+            sum += v as f64 / max_count as f64;
+        }
+        let diff = match sum > 0.5 {
+            true => sum - 0.5,
+            false => 0.5 - sum,
+        };
+        assert!(diff < acceptable_delta);
     }
 
     #[bench]
@@ -208,4 +311,14 @@ mod tests {
             println!("{:x}", v);
         })
     }
+
+    #[bench]
+    fn bench_mt19937_fill_10mil(b: &mut test::Bencher) {
+        let mut v = vec![0u64; 10000000];
+        b.iter(|| {
+            let mut s = Mt19937::with_array(vec![0x123, 0x456, 0x789, 0xabc, 0xdef]);
+            s.fill(&mut v);
+            println!("{:x}", v[v.len() - 1]);
+        })
+    }
 }
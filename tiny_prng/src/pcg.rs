@@ -17,42 +17,87 @@
 //! }
 //!
 
-use std::ops::{BitAnd, BitOr, Shl, Shr};
-use crate::{generate_real64, generate_real32};
+use crate::{generate_array, generate_real, impl_generator32, impl_generator64};
 
-pub static MULTIPLIER: u64 = 1957840684519283055;
-pub static MULTIPLIER128: u128 = 0x1957840684519283055;
-pub static INCREMENT: u64 = 3571826365018266039;
-pub static INCREMENT128: u128 = 0x3571826365018266039;
+pub const MULTIPLIER: u64 = 1957840684519283055;
+pub const MULTIPLIER128: u128 = 0x1957840684519283055;
+pub const INCREMENT: u64 = 3571826365018266039;
+pub const INCREMENT128: u128 = 0x3571826365018266039;
+
+/// the multiplier of the 64bit LCG in the reference implementation, pcg-c
+pub const REFERENCE_MULTIPLIER: u64 = 6364136223846793005;
+/// the multiplier of the 128bit LCG in the reference implementation, pcg-c
+pub const REFERENCE_MULTIPLIER128: u128 = 0x2360ed051fc65da44385df649fccf645;
+/// the default increment of the 64bit LCG in the reference implementation, pcg-c
+pub const REFERENCE_INCREMENT: u64 = 1442695040888963407;
+/// the default increment of the 128bit LCG in the reference implementation, pcg-c
+pub const REFERENCE_INCREMENT128: u128 = 0x5851f42d4c957f2d14057b7ef767814f;
 
 
 macro_rules! rotr32 {
     ($x:expr, $r:expr) => {{
-        ($x >> $r) | ($x << ((32-$r) & 31))
+        ($x >> $r) | ($x << ($r.wrapping_neg() & 31))
     }};
 }
 
 macro_rules! rotr64 {
     ($x:expr, $r:expr) => {{
-        ($x >> $r) | ($x << ((64-$r) & 63))
+        ($x >> $r) | ($x << ($r.wrapping_neg() & 63))
     }}
 }
 
+macro_rules! lcg_advance {
+    ($name:ident, $t:ty) => {
+        /// the state of the LCG after `delta` steps by the jump-ahead of Brown (1994), in O(log delta) steps
+        #[inline]
+        fn $name(state: $t, multiplier: $t, increment: $t, mut delta: $t) -> $t {
+            let (mut acc_mult, mut acc_plus): ($t, $t) = (1, 0);
+            let (mut cur_mult, mut cur_plus) = (multiplier, increment);
+            while delta > 0 {
+                if delta & 1 == 1 {
+                    acc_mult = acc_mult.wrapping_mul(cur_mult);
+                    acc_plus = acc_plus.wrapping_mul(cur_mult).wrapping_add(cur_plus);
+                }
+                cur_plus = cur_mult.wrapping_add(1).wrapping_mul(cur_plus);
+                cur_mult = cur_mult.wrapping_mul(cur_mult);
+                delta >>= 1;
+            }
+            acc_mult.wrapping_mul(state).wrapping_add(acc_plus)
+        }
+    };
+}
+
+lcg_advance!(advance64, u64);
+lcg_advance!(advance128, u128);
+
 
 // The generator for PCG-XSH-RR-64/32 with LCG.
+#[derive(Debug, Clone)]
 pub struct PcgXshRr6432 {
     state: u64,
 }
 
 impl PcgXshRr6432 {
     #[inline]
-    pub fn with_seed(seed: u64) -> Self {
+    pub const fn with_seed(seed: u64) -> Self {
         Self { state: seed }
     }
 
+    /// the current state, from which `with_seed` resumes the sequence
+    #[inline]
+    pub const fn state(&self) -> u64 {
+        self.state
+    }
+
+    /// skip `delta` outputs in O(log delta) steps; `advance(delta.wrapping_neg())` goes back as many
+    #[inline]
+    pub fn advance(&mut self, delta: u64) {
+        self.state = advance64(self.state, MULTIPLIER, INCREMENT, delta);
+    }
+
     #[inline]
     // generate a pseudo random number with the current state of the generator
-    pub fn generate(&mut self) -> u32 {
+    pub const fn generate(&mut self) -> u32 {
         let mut x = self.state;
         let count = (self.state >> 59) as u32;
         self.state = x.wrapping_mul(MULTIPLIER)
@@ -60,9 +105,11 @@ impl PcgXshRr6432 {
         x ^= x >> 18;
         rotr32!((x>>27) as u32, count)
     }
-    generate_real32!();
+    generate_real!();
+    generate_array!(u32);
 }
 
+impl_generator32!(PcgXshRr6432);
 
 // The generator for PCG-XSL-RR-64/32 with MCG.
 pub struct PcgXslRr6432Mcg {
@@ -71,22 +118,36 @@ pub struct PcgXslRr6432Mcg {
 
 impl PcgXslRr6432Mcg {
     #[inline]
-    pub fn with_seed(seed: u64) -> Self {
+    pub const fn with_seed(seed: u64) -> Self {
         Self { state: seed }
     }
 
+    /// the current state, from which `with_seed` resumes the sequence
+    #[inline]
+    pub const fn state(&self) -> u64 {
+        self.state
+    }
+
+    /// skip `delta` outputs in O(log delta) steps; `advance(delta.wrapping_neg())` goes back as many
+    #[inline]
+    pub fn advance(&mut self, delta: u64) {
+        self.state = advance64(self.state, MULTIPLIER, 0, delta);
+    }
+
     #[inline]
     // generate a pseudo random number with the current state of the generator
-    pub fn generate(&mut self) -> u32 {
+    pub const fn generate(&mut self) -> u32 {
         let mut x = self.state;
         let count = (self.state >> 59) as u32;
         self.state = x.wrapping_mul(MULTIPLIER);
         x ^= x >> 18;
         rotr32!((x>>27) as u32, count)
     }
-    generate_real32!();
+    generate_real!();
+    generate_array!(u32);
 }
 
+impl_generator32!(PcgXslRr6432Mcg);
 
 // The generator for PCG-XSH-RS-64/32 with LCG.
 pub struct PcgXshRs6432 {
@@ -95,12 +156,24 @@ pub struct PcgXshRs6432 {
 
 impl PcgXshRs6432 {
     #[inline]
-    pub fn with_seed(seed: u64) -> Self {
+    pub const fn with_seed(seed: u64) -> Self {
         Self { state: seed }
     }
+
+    /// the current state, from which `with_seed` resumes the sequence
+    #[inline]
+    pub const fn state(&self) -> u64 {
+        self.state
+    }
+
+    /// skip `delta` outputs in O(log delta) steps; `advance(delta.wrapping_neg())` goes back as many
+    #[inline]
+    pub fn advance(&mut self, delta: u64) {
+        self.state = advance64(self.state, MULTIPLIER, INCREMENT, delta);
+    }
     #[inline]
     // generate a pseudo random number with the current state of the generator
-    pub fn generate(&mut self) -> u32 {
+    pub const fn generate(&mut self) -> u32 {
         let mut x = self.state;
         let count = 22 + (self.state >> 61) as u32;
         self.state = x.wrapping_mul(MULTIPLIER)
@@ -108,9 +181,11 @@ impl PcgXshRs6432 {
         x ^= x >> 22;
         (x >> count) as u32
     }
-    generate_real32!();
+    generate_real!();
+    generate_array!(u32);
 }
 
+impl_generator32!(PcgXshRs6432);
 
 // The generator for PCG-XSL-RS-128/64 with LCG.
 pub struct PcgXslRr {
@@ -120,12 +195,24 @@ pub struct PcgXslRr {
 
 impl PcgXslRr {
     #[inline]
-    pub fn with_seed(seed: u128) -> Self {
+    pub const fn with_seed(seed: u128) -> Self {
         Self { state: seed }
     }
+
+    /// the current state, from which `with_seed` resumes the sequence
+    #[inline]
+    pub const fn state(&self) -> u128 {
+        self.state
+    }
+
+    /// skip `delta` outputs in O(log delta) steps; `advance(delta.wrapping_neg())` goes back as many
+    #[inline]
+    pub fn advance(&mut self, delta: u128) {
+        self.state = advance128(self.state, MULTIPLIER128, INCREMENT128, delta);
+    }
     #[inline]
     // generate a pseudo random number with the current state of the generator
-    pub fn generate(&mut self) -> u64 {
+    pub const fn generate(&mut self) -> u64 {
         let mut x = self.state;
         let count = (self.state >> 122) as u64;
         self.state = x.wrapping_mul(MULTIPLIER128)
@@ -133,9 +220,12 @@ impl PcgXslRr {
         x ^= x >> 64;
         rotr64!(x as u64, count)
     }
-    generate_real64!();
+    generate_real!();
+    generate_array!(u64);
 }
 
+impl_generator64!(PcgXslRr);
+
 pub struct PcgXslRrMcg {
     state: u128,
 }
@@ -143,25 +233,142 @@ pub struct PcgXslRrMcg {
 // The generator for PCG-XSL-RR-128/64 with MCG.
 impl PcgXslRrMcg {
     #[inline]
-    pub fn with_seed(seed: u128) -> Self {
+    pub const fn with_seed(seed: u128) -> Self {
         Self { state: seed }
     }
 
+    /// the current state, from which `with_seed` resumes the sequence
+    #[inline]
+    pub const fn state(&self) -> u128 {
+        self.state
+    }
+
+    /// skip `delta` outputs in O(log delta) steps; `advance(delta.wrapping_neg())` goes back as many
+    #[inline]
+    pub fn advance(&mut self, delta: u128) {
+        self.state = advance128(self.state, MULTIPLIER128, 0, delta);
+    }
+
     #[inline]
     // generate a pseudo random number with the current state of the generator
-    pub fn generate(&mut self) -> u64 {
+    pub const fn generate(&mut self) -> u64 {
         let mut x = self.state;
         let count = (self.state >> 122) as u64;
         self.state = x.wrapping_mul(MULTIPLIER128);
         x ^= x >> 64;
         rotr64!(x as u64, count)
     }
-    generate_real64!();
+    generate_real!();
+    generate_array!(u64);
+}
+
+impl_generator64!(PcgXslRrMcg);
+
+// The generator for PCG-XSH-RR-64/32 with the constants and the seeding of pcg-c, `pcg32_random_r`.
+pub struct Pcg32 {
+    state: u64,
+    increment: u64,
+}
+
+impl Pcg32 {
+    /// seed on the default stream as `pcg32_srandom_r(seed, REFERENCE_INCREMENT >> 1)`
+    #[inline]
+    pub const fn with_seed(seed: u64) -> Self {
+        Self::with_stream(seed, REFERENCE_INCREMENT >> 1)
+    }
+
+    /// seed as `pcg32_srandom_r(seed, stream)`; the stream selects the increment `2 stream + 1`
+    #[inline]
+    pub const fn with_stream(seed: u64, stream: u64) -> Self {
+        let mut p = Self { state: 0, increment: (stream << 1) | 1 };
+        p.step();
+        p.state = p.state.wrapping_add(seed);
+        p.step();
+        p
+    }
+
+    #[inline]
+    const fn step(&mut self) {
+        self.state = self.state.wrapping_mul(REFERENCE_MULTIPLIER).wrapping_add(self.increment);
+    }
+
+    /// skip `delta` outputs in O(log delta) steps as `pcg32_advance_r`; `advance(delta.wrapping_neg())` goes back as many
+    #[inline]
+    pub fn advance(&mut self, delta: u64) {
+        self.state = advance64(self.state, REFERENCE_MULTIPLIER, self.increment, delta);
+    }
+
+    #[inline]
+    // generate a pseudo random number with the current state of the generator
+    pub const fn generate(&mut self) -> u32 {
+        let mut x = self.state;
+        let count = (self.state >> 59) as u32;
+        self.step();
+        x ^= x >> 18;
+        rotr32!((x>>27) as u32, count)
+    }
+    generate_real!();
+    generate_array!(u32);
 }
 
+impl_generator32!(Pcg32);
+
+// The generator for PCG-XSL-RR-128/64 with the constants and the seeding of pcg-c, `pcg64_random_r`.
+pub struct Pcg64 {
+    state: u128,
+    increment: u128,
+}
+
+impl Pcg64 {
+    /// seed on the default stream as `pcg64_srandom_r(seed, REFERENCE_INCREMENT128 >> 1)`
+    #[inline]
+    pub const fn with_seed(seed: u128) -> Self {
+        Self::with_stream(seed, REFERENCE_INCREMENT128 >> 1)
+    }
+
+    /// seed as `pcg64_srandom_r(seed, stream)`; the stream selects the increment `2 stream + 1`
+    #[inline]
+    pub const fn with_stream(seed: u128, stream: u128) -> Self {
+        let mut p = Self { state: 0, increment: (stream << 1) | 1 };
+        p.step();
+        p.state = p.state.wrapping_add(seed);
+        p.step();
+        p
+    }
+
+    #[inline]
+    const fn step(&mut self) {
+        self.state = self.state.wrapping_mul(REFERENCE_MULTIPLIER128).wrapping_add(self.increment);
+    }
+
+    /// skip `delta` outputs in O(log delta) steps as `pcg64_advance_r`; `advance(delta.wrapping_neg())` goes back as many
+    #[inline]
+    pub fn advance(&mut self, delta: u128) {
+        self.state = advance128(self.state, REFERENCE_MULTIPLIER128, self.increment, delta);
+    }
+
+    #[inline]
+    // generate a pseudo random number with the current state of the generator;
+    // unlike `PcgXslRr` and as pcg-c, the output permutes the state after the step
+    pub const fn generate(&mut self) -> u64 {
+        self.step();
+        let x = self.state;
+        let count = (x >> 122) as u64;
+        rotr64!((x ^ (x >> 64)) as u64, count)
+    }
+    generate_real!();
+    generate_array!(u64);
+}
+
+impl_generator64!(Pcg64);
+
+#[cfg(test)]
 mod tests {
-    use crate::{gen_delta_rate, generate_unit_test, generate_unit_test_real1, generate_unit_test_real2, generate_unit_test_real_ranged};
-    use super::{PcgXslRr6432Mcg, PcgXslRr, PcgXslRrMcg, PcgXshRs6432, PcgXshRr6432};
+    use crate::{gen_delta_rate, generate_unit_test, generate_unit_test_real1, generate_unit_test_real2, generate_unit_test_real_ranged, generate_unit_test_real_f32};
+    use super::{PcgXslRr6432Mcg, PcgXslRr, PcgXslRrMcg, PcgXshRs6432, PcgXshRr6432, Pcg32, Pcg64};
+    use super::{INCREMENT, INCREMENT128, MULTIPLIER, MULTIPLIER128};
+    use super::{REFERENCE_INCREMENT, REFERENCE_INCREMENT128, REFERENCE_MULTIPLIER, REFERENCE_MULTIPLIER128};
+    use proptest::prelude::*;
 
     const COUNT: usize = 100 * 1000;
 
@@ -170,30 +377,294 @@ mod tests {
     generate_unit_test_real1!(PcgXshRr6432, test_pcg_xsh_rr6432_real1_avr100k, f64, 0x1818729182367349, COUNT);
     generate_unit_test_real2!(PcgXshRr6432, test_pcg_xsh_rr6432_real2_avr100k, f64, 0x1818729182367349, COUNT);
     generate_unit_test_real_ranged!(PcgXshRr6432, test_pcg_xsh_rr6432_real_ranged_avr100k, f64, 0x1818729182367349, COUNT);
+    generate_unit_test_real_f32!(PcgXshRr6432, test_pcg_xsh_rr6432_real_f32_avr100k, 0x1818729182367349, COUNT);
 
     //pcg-xsh-rs-64/32
     generate_unit_test!(PcgXshRs6432, test_pcg_xsh_rs6432_avr100k, u32, 0x1818729182367349, COUNT);
     generate_unit_test_real1!(PcgXshRs6432, test_pcg_xsh_rs6432_real1_avr100k, f64, 0x1818729182367349, COUNT);
     generate_unit_test_real2!(PcgXshRs6432, test_pcg_xsh_rs6432_real2_avr100k, f64, 0x2828729282367349, COUNT);
     generate_unit_test_real_ranged!(PcgXshRs6432, test_pcg_xsh_rs6432_real_ranged_avr100k, f64, 0x2828729282367349, COUNT);
+    generate_unit_test_real_f32!(PcgXshRs6432, test_pcg_xsh_rs6432_real_f32_avr100k, 0x2828729282367349, COUNT);
 
     //pcg-xsl-rr-128/64
     generate_unit_test!(PcgXslRr, test_pcg_xsh_rr_avr100k_avr100k, u64, 0x1818729182367349, COUNT);
     generate_unit_test_real1!(PcgXslRr, test_pcg_xsh_rr_avr100k_real1_avr100k, f64, 0x1818729182367349, COUNT);
     generate_unit_test_real2!(PcgXslRr, test_pcg_xsh_rr_avr100k_real2_avr100k, f64, 0x1818729182367349, COUNT);
     generate_unit_test_real_ranged!(PcgXslRr, test_pcg_xsh_rr_avr100k_real_ranged_avr100k, f64, 0x1818729182367349, COUNT);
+    generate_unit_test_real_f32!(PcgXslRr, test_pcg_xsh_rr_avr100k_real_f32_avr100k, 0x1818729182367349, COUNT);
 
     //pcg-xsl-rr-64/32-mcg
     generate_unit_test!(PcgXslRrMcg, test_pcg_xsl_rr_mcg_avr100k, u64, 0x1818729182367349, COUNT);
     generate_unit_test_real1!(PcgXslRrMcg, test_pcg_xsl_rr_mcg_real1_avr100k, f64, 0x1818729182367349, COUNT);
     generate_unit_test_real2!(PcgXslRrMcg, test_pcg_xsl_rr_mcg_real2_avr100k, f64, 0x1818729182367349, COUNT);
     generate_unit_test_real_ranged!(PcgXslRrMcg, test_pcg_xsl_rr_mcg_real_ranged_avr100k, f64, 0x1818729182367349, COUNT);
+    generate_unit_test_real_f32!(PcgXslRrMcg, test_pcg_xsl_rr_mcg_real_f32_avr100k, 0x1818729182367349, COUNT);
 
     //pcg-xsl-rr-128/64-mcg
     generate_unit_test!(PcgXslRr6432Mcg, test_pcg_xsl_rr6432_mcg_avr100k, u32, 0x1818729182367349, COUNT);
     generate_unit_test_real1!(PcgXslRr6432Mcg, test_pcg_xsl_rr6432_mcg_real1_avr100k, f64, 0x1818729182367349, COUNT);
     generate_unit_test_real2!(PcgXslRr6432Mcg, test_pcg_xsl_rr6432_mcg_real2_avr100k, f64, 0x1818729182367349, COUNT);
     generate_unit_test_real_ranged!(PcgXslRr6432Mcg, test_pcg_xsl_rr6432_mcg_real_ranged_avr100k, f64, 0x1818729182367349, COUNT);
+    generate_unit_test_real_f32!(PcgXslRr6432Mcg, test_pcg_xsl_rr6432_mcg_real_f32_avr100k, 0x1818729182367349, COUNT);
+
+    //pcg32 and pcg64 of pcg-c
+    generate_unit_test!(Pcg32, test_pcg32_avr100k, u32, 0x1818729182367349, COUNT);
+    generate_unit_test_real1!(Pcg32, test_pcg32_real1_avr100k, f64, 0x1818729182367349, COUNT);
+    generate_unit_test!(Pcg64, test_pcg64_avr100k, u64, 0x1818729182367349, COUNT);
+    generate_unit_test_real1!(Pcg64, test_pcg64_real1_avr100k, f64, 0x1818729182367349, COUNT);
+
+    #[test]
+    fn test_pcg32_matches_pcg_c() {
+        // pcg32-demo of pcg-c seeded with pcg32_srandom_r(42, 54)
+        let mut p = Pcg32::with_stream(42, 54);
+        let expected = [0xa15c02b7, 0x7b47f409, 0xba1d3330, 0x83d2f293, 0xbfa4784b, 0xcbed606e];
+        assert_eq!(expected, [(); 6].map(|_| p.generate()));
+    }
+
+    #[test]
+    fn test_pcg64_matches_pcg_c() {
+        // pcg64-demo of pcg-c seeded with pcg64_srandom_r(42, 54)
+        let mut p = Pcg64::with_stream(42, 54);
+        let expected = [
+            0x86b1da1d72062b68, 0x1304aa46c9853d39, 0xa3670e9e0dd50358,
+            0xf9090e529a7dae00, 0xc85b9fd837996f2c, 0x606121f8e3919196,
+        ];
+        assert_eq!(expected, [(); 6].map(|_| p.generate()));
+    }
+
+    #[test]
+    fn test_const_generate_array() {
+        // evaluated at compile time
+        const PCG32: [u32; 3] = Pcg32::with_stream(42, 54).generate_array();
+        const PCG64: [u64; 2] = Pcg64::with_stream(42, 54).generate_array();
+        const XSH_RR: [u32; 8] = PcgXshRr6432::with_seed(0x1818729182367349).generate_array();
+        const XSL_RR_MCG: [u64; 8] = PcgXslRrMcg::with_seed(0x1818729182367349).generate_array();
+        assert_eq!([0xa15c02b7, 0x7b47f409, 0xba1d3330], PCG32);
+        assert_eq!([0x86b1da1d72062b68, 0x1304aa46c9853d39], PCG64);
+        let mut p = PcgXshRr6432::with_seed(0x1818729182367349);
+        assert!(XSH_RR.iter().all(|&x| x == p.generate()));
+        let mut p = PcgXslRrMcg::with_seed(0x1818729182367349);
+        assert!(XSL_RR_MCG.iter().all(|&x| x == p.generate()));
+        assert_eq!([0u64; 0], PcgXslRr::with_seed(1).generate_array());
+    }
+
+    #[test]
+    fn test_reference_default_stream() {
+        let (mut p, mut q) = (Pcg32::with_seed(42), Pcg32::with_stream(42, REFERENCE_INCREMENT >> 1));
+        assert_eq!(REFERENCE_INCREMENT, p.increment);
+        assert_eq!(p.generate(), q.generate());
+        let p = Pcg64::with_seed(42);
+        assert_eq!(REFERENCE_INCREMENT128, p.increment);
+        assert_eq!(REFERENCE_INCREMENT, REFERENCE_INCREMENT128 as u64);
+    }
+
+    #[test]
+    fn test_reference_lcg_hull_dobell() {
+        // unlike the custom 64bit one, the reference multipliers reach the full period
+        assert_eq!(1, REFERENCE_MULTIPLIER % 4);
+        assert_eq!(1, REFERENCE_MULTIPLIER128 % 4);
+        assert_eq!((1, 0), jump64(REFERENCE_MULTIPLIER, REFERENCE_INCREMENT, 64));
+        let (a, c) = jump64(REFERENCE_MULTIPLIER, REFERENCE_INCREMENT, 63);
+        assert!(no_fixed_point(a.wrapping_sub(1) as u128, c as u128));
+        assert_eq!((1, 0), jump128(REFERENCE_MULTIPLIER128, REFERENCE_INCREMENT128, 128));
+        let (a, c) = jump128(REFERENCE_MULTIPLIER128, REFERENCE_INCREMENT128, 127);
+        assert!(no_fixed_point(a - 1, c));
+    }
+
+    // advance(n) equals n steps, goes back with the negated delta, and the state resumes the sequence
+
+    macro_rules! advance_properties {
+        ($($name:ident: $t:ty, $seed:ty, |$s:ident| $new:expr, $period:expr),* $(,)?) => {
+            proptest! {
+                $(
+                    #[test]
+                    fn $name($s: $seed, delta in 0u16..3000) {
+                        let mut stepped = $new;
+                        for _ in 0..delta {
+                            stepped.generate();
+                        }
+                        let mut advanced = $new;
+                        advanced.advance(delta as _);
+                        prop_assert_eq!(stepped.generate(), advanced.generate());
+                        advanced.advance((delta as $seed + 1).wrapping_neg());
+                        prop_assert_eq!($new.generate(), advanced.generate());
+                        // a whole period returns to the same state
+                        let mut cycled = $new;
+                        cycled.advance($period);
+                        prop_assert_eq!($new.generate(), cycled.generate());
+                    }
+                )*
+            }
+        };
+    }
+
+    // the full periods of 2^64 and 2^128 wrap to 0
+    advance_properties!(
+        prop_advance_pcg_xsh_rr6432: PcgXshRr6432, u64, |s| PcgXshRr6432::with_seed(s), 1 << 61,
+        prop_advance_pcg_xsh_rs6432: PcgXshRs6432, u64, |s| PcgXshRs6432::with_seed(s), 1 << 61,
+        prop_advance_pcg_xsl_rr6432_mcg: PcgXslRr6432Mcg, u64, |s| PcgXslRr6432Mcg::with_seed(s | 1), 1 << 60,
+        prop_advance_pcg_xsl_rr: PcgXslRr, u128, |s| PcgXslRr::with_seed(s), 0,
+        prop_advance_pcg_xsl_rr_mcg: PcgXslRrMcg, u128, |s| PcgXslRrMcg::with_seed(s | 1), 1 << 126,
+        prop_advance_pcg32: Pcg32, u64, |s| Pcg32::with_stream(s, s.rotate_left(32)), 0,
+        prop_advance_pcg64: Pcg64, u128, |s| Pcg64::with_stream(s, s.rotate_left(64)), 0,
+    );
+
+    macro_rules! state_properties {
+        ($($name:ident: $t:ty, $seed:ty),* $(,)?) => {
+            proptest! {
+                $(
+                    #[test]
+                    fn $name(seed: $seed, steps in 0usize..100) {
+                        let mut g = <$t>::with_seed(seed);
+                        for _ in 0..steps {
+                            g.generate();
+                        }
+                        let mut resumed = <$t>::with_seed(g.state());
+                        prop_assert_eq!(g.state(), resumed.state());
+                        prop_assert_eq!(g.generate(), resumed.generate());
+                    }
+                )*
+            }
+        };
+    }
+
+    state_properties!(
+        prop_state_pcg_xsh_rr6432: PcgXshRr6432, u64,
+        prop_state_pcg_xsh_rs6432: PcgXshRs6432, u64,
+        prop_state_pcg_xsl_rr6432_mcg: PcgXslRr6432Mcg, u64,
+        prop_state_pcg_xsl_rr: PcgXslRr, u128,
+        prop_state_pcg_xsl_rr_mcg: PcgXslRrMcg, u128,
+    );
+
+    #[test]
+    fn test_advance_matches_jump() {
+        // the jump-ahead of the tests agrees on the powers of 2
+        let mut p = PcgXshRr6432::with_seed(0x1818729);
+        p.advance(1 << 40);
+        let (a, c) = jump64(MULTIPLIER, INCREMENT, 40);
+        assert_eq!(a.wrapping_mul(0x1818729).wrapping_add(c), p.state());
+        let mut p = PcgXslRr::with_seed(0x1818729);
+        p.advance(1 << 100);
+        let (a, c) = jump128(MULTIPLIER128, INCREMENT128, 100);
+        assert_eq!(a.wrapping_mul(0x1818729).wrapping_add(c), p.state());
+    }
+
+    // The state of an LCG x -> a x + c modulo 2^n after 2^k steps is the affine map x -> A x + C,
+    // obtained by squaring the map k times. Every state has the period 2^k exactly when the map after
+    // 2^k steps is the identity and the one after 2^(k - 1) steps has no fixed point.
+
+    /// the affine map of the 64bit LCG after `2^log2_steps` steps
+    fn jump64(a: u64, c: u64, log2_steps: u32) -> (u64, u64) {
+        (0..log2_steps).fold((a, c), |(a, c), _| (a.wrapping_mul(a), a.wrapping_mul(c).wrapping_add(c)))
+    }
+
+    /// the affine map of the 128bit LCG after `2^log2_steps` steps
+    fn jump128(a: u128, c: u128, log2_steps: u32) -> (u128, u128) {
+        (0..log2_steps).fold((a, c), |(a, c), _| (a.wrapping_mul(a), a.wrapping_mul(c).wrapping_add(c)))
+    }
+
+    /// whether `(a - 1) x + c = 0` has no solution, which holds if `c` has fewer trailing zeros than `a - 1`
+    fn no_fixed_point(a_minus_1: u128, c: u128) -> bool {
+        c != 0 && c.trailing_zeros() < a_minus_1.trailing_zeros()
+    }
+
+    #[test]
+    fn test_lcg128_hull_dobell() {
+        // the increment is odd and the multiplier is 1 modulo 4, so the period is the full 2^128
+        assert_eq!(1, INCREMENT128 % 2);
+        assert_eq!(1, MULTIPLIER128 % 4);
+        assert_eq!((1, 0), jump128(MULTIPLIER128, INCREMENT128, 128));
+        let (a, c) = jump128(MULTIPLIER128, INCREMENT128, 127);
+        assert!(no_fixed_point(a - 1, c));
+    }
+
+    #[test]
+    fn test_lcg64_period() {
+        // the multiplier is 3 modulo 4, which breaks the condition of Hull and Dobell,
+        // and the period of PCG-XSH-RR-64/32 and PCG-XSH-RS-64/32 is 2^61 on every state
+        assert_eq!(1, INCREMENT % 2);
+        assert_eq!(3, MULTIPLIER % 4);
+        assert_eq!((1, 0), jump64(MULTIPLIER, INCREMENT, 61));
+        let (a, c) = jump64(MULTIPLIER, INCREMENT, 60);
+        assert!(no_fixed_point(a.wrapping_sub(1) as u128, c as u128));
+    }
+
+    #[test]
+    fn test_mcg_periods() {
+        // an MCG keeps odd states odd, and their period is the multiplicative order of the multiplier:
+        // 2^126 for the multiplier of 5 modulo 8, the maximum, and 2^60 for the 64bit one
+        assert_eq!(5, MULTIPLIER128 % 8);
+        assert_eq!(1, jump128(MULTIPLIER128, 0, 126).0);
+        assert_ne!(1, jump128(MULTIPLIER128, 0, 125).0);
+        assert_eq!(1, jump64(MULTIPLIER, 0, 60).0);
+        assert_ne!(1, jump64(MULTIPLIER, 0, 59).0);
+    }
+
+    #[test]
+    fn test_jump_matches_generator() {
+        let (a, c) = jump64(MULTIPLIER, INCREMENT, 10);
+        let mut p = PcgXshRr6432::with_seed(0x1818729);
+        for _ in 0..1 << 10 {
+            p.generate();
+        }
+        assert_eq!(a.wrapping_mul(0x1818729).wrapping_add(c), p.state);
+    }
+
+    /// the lengths of every cycle of the LCG truncated to the lower `bits` bits, enumerated exhaustively
+    fn truncated_cycles(a: u128, c: u128, bits: u32) -> Vec<u64> {
+        let mask = (1u128 << bits) - 1;
+        let mut visited = vec![false; 1 << bits];
+        let mut lengths = vec![];
+        for start in 0..1u128 << bits {
+            if visited[start as usize] {
+                continue;
+            }
+            let (mut x, mut length) = (start, 0);
+            loop {
+                visited[x as usize] = true;
+                x = a.wrapping_mul(x).wrapping_add(c) & mask;
+                length += 1;
+                if x == start {
+                    break;
+                }
+            }
+            lengths.push(length);
+        }
+        lengths
+    }
+
+    #[test]
+    fn test_truncated_lcg_cycles() {
+        // the lower bits of an LCG state follow the LCG truncated to them, whose period bounds the whole one
+        assert_eq!(vec![1 << 20], truncated_cycles(MULTIPLIER128, INCREMENT128, 20));
+        let cycles = truncated_cycles(MULTIPLIER as u128, INCREMENT as u128, 20);
+        assert_eq!(8, cycles.len());
+        assert!(cycles.iter().all(|&c| c == 1 << 17));
+        // and so do the lower bits of the generator
+        let mut p = PcgXshRr6432::with_seed(0x1818729);
+        for _ in 0..1 << 17 {
+            p.generate();
+        }
+        assert_eq!(0x1818729 & 0xfffff, p.state & 0xfffff);
+    }
+
+    /// the length of the cycle through zero of the LCG truncated to the lower 32 bits
+    fn truncated_cycle_32bit(a: u128, c: u128) -> u64 {
+        let (a, c) = (a as u32, c as u32);
+        let (mut x, mut length) = (c, 1);
+        while x != 0 {
+            x = a.wrapping_mul(x).wrapping_add(c);
+            length += 1;
+        }
+        length
+    }
+
+    #[test]
+    #[ignore = "walks up to 2^32 states; run with --release --ignored"]
+    fn test_truncated_lcg_cycle_32bit() {
+        assert_eq!(1 << 32, truncated_cycle_32bit(MULTIPLIER128, INCREMENT128));
+        assert_eq!(1 << 29, truncated_cycle_32bit(MULTIPLIER as u128, INCREMENT as u128));
+    }
 
     #[bench]
     fn bench_pcgxslrr12864_10mil(b: &mut test::Bencher) {
@@ -1,67 +1,168 @@
+/// Implements the real number generation of the `Generator` trait as inherent methods,
+/// so that they are available without importing the trait.
 #[macro_export]
-macro_rules! generate_real64 {
+macro_rules! generate_real {
     () => {
-         pub fn generate_real(&mut self) -> f64 {
-            let r = self.generate();
-            let upper = (r>>32) as f64;
-            let lower = (r&0xfffffffc) as f64; // 53bit
-            upper * (1.0/(4294967295.0)) + lower * (1.0/(18446744073709551615.0))
+        /// generate a double in `[0, 1)` with 53bit precision
+        pub fn generate_real(&mut self) -> f64 {
+            <Self as $crate::Generator>::generate_real(self)
+        }
+
+        /// generate a double in `[0, 1]` with 53bit precision
+        pub fn generate_real_closed(&mut self) -> f64 {
+            <Self as $crate::Generator>::generate_real_closed(self)
+        }
+
+        /// generate a double in `(0, 1)` with 52bit precision
+        pub fn generate_real_open(&mut self) -> f64 {
+            <Self as $crate::Generator>::generate_real_open(self)
+        }
+
+        /// generate a double in `(0, 1]` with 53bit precision
+        pub fn generate_real_open_closed(&mut self) -> f64 {
+            <Self as $crate::Generator>::generate_real_open_closed(self)
+        }
+
+        /// generate a double in `[lower, upper)`
+        pub fn generate_real_in_range(&mut self, lower: f64, upper: f64) -> f64 {
+            <Self as $crate::Generator>::generate_real_in_range(self, lower, upper)
+        }
+
+        /// generate a float in `[0, 1)` with 24bit precision
+        pub fn generate_real_f32(&mut self) -> f32 {
+            <Self as $crate::Generator>::generate_real_f32(self)
+        }
+
+        /// generate a float in `[0, 1]` with 24bit precision
+        pub fn generate_real_closed_f32(&mut self) -> f32 {
+            <Self as $crate::Generator>::generate_real_closed_f32(self)
+        }
+
+        /// generate a float in `(0, 1)` with 23bit precision
+        pub fn generate_real_open_f32(&mut self) -> f32 {
+            <Self as $crate::Generator>::generate_real_open_f32(self)
         }
 
-         pub fn generate_real_in_range(&mut self, lower: f64, upper: f64) -> f64 {
-            let v = self.generate_real();
-            lower + (upper - lower) * v
+        /// generate a float in `(0, 1]` with 24bit precision
+        pub fn generate_real_open_closed_f32(&mut self) -> f32 {
+            <Self as $crate::Generator>::generate_real_open_closed_f32(self)
         }
 
-        pub fn generate_real_closed(&mut self) -> f64{
-            let r = self.generate();
-            let upper = (r>>32) as f64;
-            let lower = (r&0xfffffffc) as f64; // 53bit
-            upper * (1.0/(4294967296.0)) + lower * (1.0/(18446744073709551616.0))
+        /// generate a float in `[lower, upper)`
+        pub fn generate_real_in_range_f32(&mut self, lower: f32, upper: f32) -> f32 {
+            <Self as $crate::Generator>::generate_real_in_range_f32(self, lower, upper)
         }
     }
 }
 
+/// Implements `generate_array` for the generators whose `generate` is a `const fn`.
 #[macro_export]
-macro_rules! generate_real32 {
-    () => {
-    pub fn generate_real(&mut self) -> f64 {
-        (self.generate() as f64) *(1.0/4294967295.0)
+macro_rules! generate_array {
+    ($word:ty) => {
+        /// generate `N` outputs into an array; as a `const fn` it computes tables and salts at compile time
+        pub const fn generate_array<const N: usize>(&mut self) -> [$word; N] {
+            let mut a = [0; N];
+            let mut i = 0;
+            while i < N {
+                a[i] = self.generate();
+                i += 1;
+            }
+            a
+        }
     }
+}
 
-    pub fn generate_real_in_range(&mut self, lower: f64, upper: f64) -> f64 {
-        let v = self.generate_real();
-        lower + (upper - lower) * v
-    }
+#[macro_export]
+macro_rules! impl_generator32 {
+    // the optional method fills a slice with the outputs faster than the word by word default
+    ($t:ty $(, $fill:ident)?) => {
+        impl $t {
+            /// iterate over the outputs of the generator
+            pub fn iter(&mut self) -> impl Iterator<Item = u32> {
+                std::iter::repeat_with(move || self.generate())
+            }
+        }
+
+        /// The generator is an endless iterator over its outputs.
+        impl Iterator for $t {
+            type Item = u32;
+
+            #[inline]
+            fn next(&mut self) -> Option<u32> {
+                Some(self.generate())
+            }
 
-    pub fn generate_real_closed(&mut self) -> f64{
-            (self.generate() as f64) *(1.0/4294967296.0)
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (usize::MAX, None)
+            }
         }
-    }
+
+        impl $crate::Generator for $t {
+            #[inline]
+            fn generate_u32(&mut self) -> u32 {
+                self.generate()
+            }
+
+            #[inline]
+            fn generate_u64(&mut self) -> u64 {
+                let upper = self.generate() as u64;
+                (upper << 32) | self.generate() as u64
+            }
+
+            $(
+                #[inline]
+                fn fill_u32(&mut self, dest: &mut [u32]) {
+                    self.$fill(dest)
+                }
+            )?
+        }
+    };
 }
 
 #[macro_export]
-macro_rules! generate_real128 {
-    () => {
-        pub fn generate_real(&mut self) -> f64 {
-            let r = self.generate();
-            let upper1 = (r >> 96) as f64;
-            let upper2 = ((r >> 64) & 0xfffffffc) as f64;
-            upper1 * (1.0 / (4294967295.0)) + upper2 * (1.0 / (18446744073709551615.0))
+macro_rules! impl_generator64 {
+    // the optional method fills a slice with the outputs faster than the word by word default
+    ($t:ty $(, $fill:ident)?) => {
+        impl $t {
+            /// iterate over the outputs of the generator
+            pub fn iter(&mut self) -> impl Iterator<Item = u64> {
+                std::iter::repeat_with(move || self.generate())
+            }
         }
 
-        pub fn generate_real_in_range(&mut self, lower: f64, upper: f64) -> f64 {
-            let v = self.generate_real();
-            lower + (upper - lower) * v
+        /// The generator is an endless iterator over its outputs.
+        impl Iterator for $t {
+            type Item = u64;
+
+            #[inline]
+            fn next(&mut self) -> Option<u64> {
+                Some(self.generate())
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (usize::MAX, None)
+            }
         }
 
-        pub fn generate_real_closed(&mut self) -> f64 {
-            let r = self.generate();
-            let upper1 = (r >> 96) as f64;
-            let upper2 = ((r >> 64) & 0xfffffffc) as f64; // 53bit
-            upper1 * (1.0 / (4294967296.0)) + upper2 * (1.0 / (18446744073709551616.0))
+        impl $crate::Generator for $t {
+            #[inline]
+            fn generate_u32(&mut self) -> u32 {
+                (self.generate() >> 32) as u32
+            }
+
+            #[inline]
+            fn generate_u64(&mut self) -> u64 {
+                self.generate()
+            }
+
+            $(
+                #[inline]
+                fn fill_u64(&mut self, dest: &mut [u64]) {
+                    self.$fill(dest)
+                }
+            )?
         }
-    }
+    };
 }
 
 #[macro_export]
@@ -154,4 +255,27 @@ macro_rules! generate_unit_test_real_ranged {
             assert_eq!(true, diff < acceptable_delta);
         }
     };
-}
\ No newline at end of file
+}
+#[macro_export]
+macro_rules! generate_unit_test_real_f32 {
+    ($constructor:ident, $name:ident, $seed:literal, $count:ident) => {
+        #[test]
+        fn $name() {
+            let mut p = $constructor::with_seed($seed);
+            let mut sum = 0.0f64;
+            let mut ranged_sum = 0.0f64;
+            let max_count = $count;
+            let acceptable_delta = 1.0 / (gen_delta_rate!() as f64);
+            for _ in 0..max_count {
+                let v = p.generate_real_f32();
+                assert!((0.0..1.0).contains(&v));
+                sum += v as f64 / max_count as f64;
+                let v = p.generate_real_in_range_f32(-1.0, 1.0);
+                assert!((-1.0..1.0).contains(&v));
+                ranged_sum += v as f64 / max_count as f64;
+            }
+            assert!((sum - 0.5).abs() < acceptable_delta);
+            assert!(ranged_sum.abs() < 2.0 * acceptable_delta);
+        }
+    };
+}
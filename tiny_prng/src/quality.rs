@@ -0,0 +1,443 @@
+//! The `quality` module implements a battery of statistical tests on the outputs of any generator.
+//!
+//! The battery follows the spirit of SmallCrush in TestU01: the frequency, serial, gap, poker, runs,
+//! birthday spacings, collision, binary matrix rank and Kolmogorov-Smirnov tests each reduce a stream
+//! to a statistic and its p-value. A p-value outside `[0.001, 0.999]` is `Suspect` and one beyond
+//! `1e-10` from either end is a `Fail`, as in TestU01, since a too good fit is as suspicious as a bad one.
+//! A single suspect p-value is expected now and then from a good generator; repeated ones are not.
+//! The Poisson counts take the mid p-value, so that a zero count is not judged too good where few are expected.
+//!
+//! ```rust
+//! use tiny_prng::quality;
+//! use tiny_prng::xorshift::Xorshift1024star;
+//!
+//! fn main(){
+//!     let mut x = Xorshift1024star::with_seed([0x1818729; 16]);
+//!     let report = quality::battery(&mut x);
+//!     println!("{}", report);
+//!     assert_ne!(quality::Verdict::Fail, report.verdict());
+//! }
+//! ```
+
+use std::fmt;
+use crate::Generator;
+
+mod special;
+
+use special::{chi_squared, chi_squared_sf, kolmogorov_sf, poisson_mid_p};
+
+/// the p-value below which, or above one minus which, a result is suspect
+pub const SUSPECT_LEVEL: f64 = 1e-3;
+/// the p-value below which, or above one minus which, a result fails
+pub const FAIL_LEVEL: f64 = 1e-10;
+
+/// The verdict on a p-value, ordered from the best.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verdict {
+    Pass,
+    Suspect,
+    Fail,
+}
+
+impl Verdict {
+    /// judge a p-value with `SUSPECT_LEVEL` and `FAIL_LEVEL` on both tails
+    pub fn from_p_value(p: f64) -> Self {
+        let tail = p.min(1.0 - p);
+        match () {
+            _ if tail.is_nan() || tail < FAIL_LEVEL => Verdict::Fail,
+            _ if tail < SUSPECT_LEVEL => Verdict::Suspect,
+            _ => Verdict::Pass,
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Verdict::Pass => "pass",
+            Verdict::Suspect => "suspect",
+            Verdict::Fail => "FAIL",
+        };
+        f.pad(s)
+    }
+}
+
+/// The statistic and the p-value of a test.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestResult {
+    pub name: &'static str,
+    pub statistic: f64,
+    pub p_value: f64,
+}
+
+impl TestResult {
+    pub fn verdict(&self) -> Verdict {
+        Verdict::from_p_value(self.p_value)
+    }
+}
+
+/// The results of a battery.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Report {
+    pub results: Vec<TestResult>,
+}
+
+impl Report {
+    /// the worst verdict of the results
+    pub fn verdict(&self) -> Verdict {
+        self.results.iter().map(TestResult::verdict).max().unwrap_or(Verdict::Pass)
+    }
+
+    /// the number of the results with the verdict
+    pub fn count(&self, verdict: Verdict) -> usize {
+        self.results.iter().filter(|r| r.verdict() == verdict).count()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<20} {:>14} {:>12}  verdict", "test", "statistic", "p-value")?;
+        for r in &self.results {
+            writeln!(f, "{:<20} {:>14.4} {:>12.4e}  {}", r.name, r.statistic, r.p_value, r.verdict())?;
+        }
+        write!(
+            f,
+            "summary: {} passed, {} suspect, {} failed",
+            self.count(Verdict::Pass),
+            self.count(Verdict::Suspect),
+            self.count(Verdict::Fail)
+        )
+    }
+}
+
+/// run every test with the default parameters, consuming about 5 million outputs
+pub fn battery<G: Generator + ?Sized>(g: &mut G) -> Report {
+    Report {
+        results: vec![
+            frequency(g, 1 << 20, 8),
+            serial(g, 1 << 20, 8),
+            gap(g, 50000, 0.0, 0.125),
+            poker(g, 200000, 5, 8),
+            runs(g, 200000),
+            birthday_spacings(g, 4096, 36, 100),
+            collision(g, 1 << 14, 26, 50),
+            matrix_rank(g, 20000),
+            kolmogorov_smirnov(g, 100000),
+        ],
+    }
+}
+
+/// the chi-squared test result of the counts against the cell probabilities
+fn chi_squared_result(name: &'static str, observed: &[u64], probabilities: &[f64]) -> TestResult {
+    let (statistic, df) = chi_squared(observed, probabilities);
+    TestResult { name, statistic, p_value: chi_squared_sf(statistic, df) }
+}
+
+/// the frequency test: the top `bits` bits of `n` 32bit outputs fall evenly into `2^bits` cells
+pub fn frequency<G: Generator + ?Sized>(g: &mut G, n: usize, bits: u32) -> TestResult {
+    assert!((1..=16).contains(&bits), "frequency: bits must be in 1..=16");
+    let cells = 1usize << bits;
+    let mut counts = vec![0u64; cells];
+    for _ in 0..n {
+        counts[(g.generate_u32() >> (32 - bits)) as usize] += 1;
+    }
+    chi_squared_result("frequency", &counts, &vec![1.0 / cells as f64; cells])
+}
+
+/// the serial test: `n` non-overlapping pairs of the top `bits` bits fall evenly into `2^(2 bits)` cells
+pub fn serial<G: Generator + ?Sized>(g: &mut G, n: usize, bits: u32) -> TestResult {
+    assert!((1..=10).contains(&bits), "serial: bits must be in 1..=10");
+    let cells = 1usize << (2 * bits);
+    let mut counts = vec![0u64; cells];
+    for _ in 0..n {
+        let x = g.generate_u32() >> (32 - bits);
+        let y = g.generate_u32() >> (32 - bits);
+        counts[((x << bits) | y) as usize] += 1;
+    }
+    chi_squared_result("serial", &counts, &vec![1.0 / cells as f64; cells])
+}
+
+/// the gap test: the lengths of `n` gaps between the reals in `[lower, upper)` are geometric
+pub fn gap<G: Generator + ?Sized>(g: &mut G, n: usize, lower: f64, upper: f64) -> TestResult {
+    assert!(0.0 <= lower && lower < upper && upper <= 1.0, "gap: invalid interval");
+    let p = upper - lower;
+    // the longest gap counted separately, beyond which the gaps share the last cell
+    let longest = ((5.0 / n as f64).ln() / (1.0 - p).ln()).clamp(1.0, 1000.0) as usize;
+    let mut counts = vec![0u64; longest + 2];
+    for _ in 0..n {
+        let mut length = 0;
+        loop {
+            let u = g.generate_real();
+            if lower <= u && u < upper {
+                break;
+            }
+            length += 1;
+        }
+        counts[length.min(longest + 1)] += 1;
+    }
+    let mut probabilities: Vec<f64> = (0..=longest).map(|r| p * (1.0 - p).powi(r as i32)).collect();
+    probabilities.push((1.0 - p).powi(longest as i32 + 1));
+    chi_squared_result("gap", &counts, &probabilities)
+}
+
+/// the poker test: the numbers of the distinct values in `n` hands of `k` values out of `d` are as expected
+pub fn poker<G: Generator + ?Sized>(g: &mut G, n: usize, k: usize, d: u32) -> TestResult {
+    assert!((1..=16).contains(&k) && (2..=256).contains(&d), "poker: invalid hand");
+    let mut counts = vec![0u64; k + 1];
+    let mut seen = vec![false; d as usize];
+    for _ in 0..n {
+        seen.iter_mut().for_each(|s| *s = false);
+        let mut distinct = 0;
+        for _ in 0..k {
+            let v = ((g.generate_u32() as u64 * d as u64) >> 32) as usize;
+            if !seen[v] {
+                seen[v] = true;
+                distinct += 1;
+            }
+        }
+        counts[distinct] += 1;
+    }
+    // P(r distinct) = d (d - 1) ... (d - r + 1) S(k, r) / d^k with the Stirling numbers of the second kind
+    let mut stirling = vec![vec![0.0f64; k + 1]; k + 1];
+    stirling[0][0] = 1.0;
+    for i in 1..=k {
+        for r in 1..=i {
+            stirling[i][r] = r as f64 * stirling[i - 1][r] + stirling[i - 1][r - 1];
+        }
+    }
+    let probabilities: Vec<f64> = (0..=k)
+        .map(|r| {
+            let falling: f64 = (0..r).map(|i| (d as f64 - i as f64) / d as f64).product();
+            falling * stirling[k][r] / (d as f64).powi((k - r) as i32)
+        })
+        .collect();
+    chi_squared_result("poker", &counts, &probabilities)
+}
+
+/// the runs test: the lengths of `n` ascending runs, each followed by a discarded real, are as expected
+///
+/// Discarding the real which ends a run makes the lengths independent, with `P(r) = r / (r + 1)!`.
+pub fn runs<G: Generator + ?Sized>(g: &mut G, n: usize) -> TestResult {
+    const LONGEST: usize = 6;
+    let mut counts = [0u64; LONGEST];
+    for _ in 0..n {
+        let mut length = 1;
+        let mut last = g.generate_real();
+        loop {
+            let u = g.generate_real();
+            if u <= last {
+                break;
+            }
+            last = u;
+            length += 1;
+        }
+        counts[length.min(LONGEST) - 1] += 1;
+    }
+    let factorial = |r: usize| (1..=r).map(|i| i as f64).product::<f64>();
+    let mut probabilities: Vec<f64> = (1..LONGEST).map(|r| r as f64 / factorial(r + 1)).collect();
+    // the last cell takes every run of at least LONGEST, 1 / LONGEST!
+    probabilities.push(1.0 / factorial(LONGEST));
+    chi_squared_result("runs", &counts, &probabilities)
+}
+
+/// the birthday spacings test: `n` birthdays out of `2^bits` days, the top bits of 64bit outputs,
+/// repeat spacings as a Poisson variable with the mean `n^3 / (4 * 2^bits)`, summed over `repeats`
+pub fn birthday_spacings<G: Generator + ?Sized>(g: &mut G, n: usize, bits: u32, repeats: usize) -> TestResult {
+    assert!((1..=64).contains(&bits) && n >= 2, "birthday_spacings: invalid parameters");
+    let mut birthdays = vec![0u64; n];
+    let mut spacings = vec![0u64; n];
+    let mut total = 0;
+    for _ in 0..repeats {
+        birthdays.iter_mut().for_each(|b| *b = g.generate_u64() >> (64 - bits));
+        birthdays.sort_unstable();
+        spacings[0] = birthdays[0];
+        for i in 1..n {
+            spacings[i] = birthdays[i] - birthdays[i - 1];
+        }
+        spacings.sort_unstable();
+        total += spacings.windows(2).filter(|w| w[0] == w[1]).count() as u64;
+    }
+    let lambda = repeats as f64 * (n as f64).powi(3) / (4.0 * (bits as f64).exp2());
+    TestResult { name: "birthday spacings", statistic: total as f64, p_value: poisson_mid_p(total, lambda) }
+}
+
+/// the collision test: `n` balls thrown into `2^bits` urns by the top bits of 64bit outputs collide
+/// as a Poisson variable in the sparse case, summed over `repeats`
+pub fn collision<G: Generator + ?Sized>(g: &mut G, n: usize, bits: u32, repeats: usize) -> TestResult {
+    assert!((1..=64).contains(&bits), "collision: bits must be in 1..=64");
+    let mut balls = vec![0u64; n];
+    let mut total = 0;
+    for _ in 0..repeats {
+        balls.iter_mut().for_each(|b| *b = g.generate_u64() >> (64 - bits));
+        balls.sort_unstable();
+        total += balls.windows(2).filter(|w| w[0] == w[1]).count() as u64;
+    }
+    // the expected number of collisions n - m (1 - (1 - 1 / m)^n) with m urns
+    let m = (bits as f64).exp2();
+    let expected = n as f64 - m * -(n as f64 * (-1.0 / m).ln_1p()).exp_m1();
+    let lambda = repeats as f64 * expected;
+    TestResult { name: "collision", statistic: total as f64, p_value: poisson_mid_p(total, lambda) }
+}
+
+/// the rank of the binary matrix whose rows are the words
+fn binary_rank(mut rows: [u32; 32]) -> usize {
+    let mut rank = 0;
+    for bit in (0..32).rev() {
+        let mask = 1u32 << bit;
+        if let Some(pivot) = (rank..32).find(|&i| rows[i] & mask != 0) {
+            rows.swap(rank, pivot);
+            for i in 0..32 {
+                if i != rank && rows[i] & mask != 0 {
+                    rows[i] ^= rows[rank];
+                }
+            }
+            rank += 1;
+        }
+    }
+    rank
+}
+
+/// the binary matrix rank test: the ranks over GF(2) of `n` 32x32 matrices of 32bit outputs are as expected
+pub fn matrix_rank<G: Generator + ?Sized>(g: &mut G, n: usize) -> TestResult {
+    // the cells of the ranks 30 or less, 31 and 32
+    let mut counts = [0u64; 3];
+    for _ in 0..n {
+        let mut rows = [0u32; 32];
+        rows.iter_mut().for_each(|r| *r = g.generate_u32());
+        counts[binary_rank(rows).max(30) - 30] += 1;
+    }
+    // P(r) = 2^(r (2m - r) - m^2) prod_{i < r} (1 - 2^(i - m))^2 / (1 - 2^(i - r)) for m x m matrices
+    let rank_probability = |r: i32| -> f64 {
+        let m = 32;
+        let product: f64 = (0..r).map(|i| (1.0 - 2f64.powi(i - m)).powi(2) / (1.0 - 2f64.powi(i - r))).product();
+        2f64.powi(r * (2 * m - r) - m * m) * product
+    };
+    let (p32, p31) = (rank_probability(32), rank_probability(31));
+    chi_squared_result("matrix rank", &counts, &[1.0 - p32 - p31, p31, p32])
+}
+
+/// the Kolmogorov-Smirnov test of `n` reals against the uniform distribution on `[0, 1)`
+pub fn kolmogorov_smirnov<G: Generator + ?Sized>(g: &mut G, n: usize) -> TestResult {
+    let mut samples: Vec<f64> = (0..n).map(|_| g.generate_real()).collect();
+    samples.sort_unstable_by(f64::total_cmp);
+    let len = n as f64;
+    let d = samples
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| ((i as f64 + 1.0) / len - x).max(x - i as f64 / len))
+        .fold(0.0, f64::max);
+    // Stephens' correction for a finite sample
+    let sqrt_n = len.sqrt();
+    let lambda = (sqrt_n + 0.12 + 0.11 / sqrt_n) * d;
+    TestResult { name: "kolmogorov-smirnov", statistic: d, p_value: kolmogorov_sf(lambda) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mt64::Mt19937;
+    use crate::pcg::PcgXslRr;
+    use crate::xorshift::Xorshift1024star;
+
+    /// RANDU, the infamous LCG whose lowest bit is constant
+    struct Randu(u32);
+
+    impl Generator for Randu {
+        fn generate_u32(&mut self) -> u32 {
+            self.0 = self.0.wrapping_mul(65539) & 0x7fffffff;
+            self.0 << 1
+        }
+
+        fn generate_u64(&mut self) -> u64 {
+            (self.generate_u32() as u64) << 32 | self.generate_u32() as u64
+        }
+    }
+
+    /// a Weyl sequence, evenly spread but with no randomness
+    struct Counter(u64);
+
+    impl Generator for Counter {
+        fn generate_u32(&mut self) -> u32 {
+            (self.generate_u64() >> 32) as u32
+        }
+
+        fn generate_u64(&mut self) -> u64 {
+            self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_verdict() {
+        assert_eq!(Verdict::Pass, Verdict::from_p_value(0.5));
+        assert_eq!(Verdict::Pass, Verdict::from_p_value(0.001));
+        assert_eq!(Verdict::Suspect, Verdict::from_p_value(0.0005));
+        assert_eq!(Verdict::Suspect, Verdict::from_p_value(0.9999));
+        assert_eq!(Verdict::Fail, Verdict::from_p_value(1e-12));
+        assert_eq!(Verdict::Fail, Verdict::from_p_value(1.0));
+        assert_eq!(Verdict::Fail, Verdict::from_p_value(f64::NAN));
+        assert!(Verdict::Pass < Verdict::Suspect && Verdict::Suspect < Verdict::Fail);
+    }
+
+    #[test]
+    fn test_good_generators_pass() {
+        let reports = [
+            battery(&mut Xorshift1024star::with_seed([0x1818729; 16])),
+            battery(&mut PcgXslRr::with_seed(0x123456789abcdef)),
+            battery(&mut Mt19937::with_array(vec![0x123, 0x234, 0x345, 0x456])),
+        ];
+        for report in &reports {
+            assert_eq!(9, report.results.len());
+            assert_eq!(Verdict::Pass, report.verdict(), "{}", report);
+        }
+    }
+
+    #[test]
+    fn test_bad_generators_fail() {
+        let randu = battery(&mut Randu(1));
+        assert_eq!(Verdict::Fail, randu.verdict(), "{}", randu);
+        // the constant lowest bit lowers the ranks
+        assert_eq!(Verdict::Fail, matrix_rank(&mut Randu(1), 1000).verdict());
+        let counter = battery(&mut Counter(0));
+        assert_eq!(Verdict::Fail, counter.verdict(), "{}", counter);
+        // the Weyl sequence is far too even
+        assert_eq!(Verdict::Fail, kolmogorov_smirnov(&mut Counter(0), 100000).verdict());
+    }
+
+    #[test]
+    fn test_zero_counts_pass() {
+        // about 6e-8 repeats and 5e-9 collisions are expected, so zero is the usual count
+        let mut g = PcgXslRr::with_seed(0x123456789abcdef);
+        let birthdays = birthday_spacings(&mut g, 64, 40, 1);
+        assert_eq!((0.0, Verdict::Pass), (birthdays.statistic, birthdays.verdict()));
+        let collisions = collision(&mut g, 100, 40, 1);
+        assert_eq!((0.0, Verdict::Pass), (collisions.statistic, collisions.verdict()));
+    }
+
+    #[test]
+    fn test_binary_rank() {
+        let mut identity = [0u32; 32];
+        identity.iter_mut().enumerate().for_each(|(i, r)| *r = 1 << i);
+        assert_eq!(32, binary_rank(identity));
+        identity[5] = identity[3] ^ identity[7];
+        assert_eq!(31, binary_rank(identity));
+        assert_eq!(0, binary_rank([0; 32]));
+        assert_eq!(1, binary_rank([u32::MAX; 32]));
+    }
+
+    #[test]
+    fn test_report_display() {
+        let report = Report {
+            results: vec![
+                TestResult { name: "a", statistic: 1.0, p_value: 0.5 },
+                TestResult { name: "b", statistic: 2.0, p_value: 1e-4 },
+                TestResult { name: "c", statistic: 3.0, p_value: 1e-20 },
+            ],
+        };
+        assert_eq!(Verdict::Fail, report.verdict());
+        let s = report.to_string();
+        assert!(s.ends_with("summary: 1 passed, 1 suspect, 1 failed"));
+        assert_eq!(5, s.lines().count());
+        assert_eq!(Verdict::Pass, Report::default().verdict());
+    }
+}
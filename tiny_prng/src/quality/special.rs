@@ -0,0 +1,178 @@
+//! The tail probabilities giving the p-values of the tests.
+
+use crate::distributions::ln_gamma;
+
+const EPSILON: f64 = 1e-15;
+const TINY: f64 = 1e-300;
+
+/// the regularized incomplete gamma functions `(P(a, x), Q(a, x))`, computed without cancellation in the smaller one
+pub(super) fn incomplete_gamma(a: f64, x: f64) -> (f64, f64) {
+    if x <= 0.0 {
+        return (0.0, 1.0);
+    }
+    let front = (a * x.ln() - x - ln_gamma(a)).exp();
+    match x < a + 1.0 {
+        true => {
+            // series expansion of the lower function
+            let (mut term, mut sum, mut n) = (1.0 / a, 1.0 / a, a);
+            while term.abs() > sum.abs() * EPSILON {
+                n += 1.0;
+                term *= x / n;
+                sum += term;
+            }
+            let p = (front * sum).min(1.0);
+            (p, 1.0 - p)
+        }
+        false => {
+            // continued fraction of the upper function (modified Lentz)
+            let mut b = x + 1.0 - a;
+            let mut c = 1.0 / TINY;
+            let mut d = 1.0 / b;
+            let mut h = d;
+            for i in 1..1000 {
+                let an = -(i as f64) * (i as f64 - a);
+                b += 2.0;
+                d = an * d + b;
+                d = match d.abs() < TINY { true => TINY, false => d };
+                c = b + an / c;
+                c = match c.abs() < TINY { true => TINY, false => c };
+                d = 1.0 / d;
+                let delta = d * c;
+                h *= delta;
+                if (delta - 1.0).abs() < EPSILON {
+                    break;
+                }
+            }
+            let q = (front * h).min(1.0);
+            (1.0 - q, q)
+        }
+    }
+}
+
+/// the probability that the chi-squared statistic with `df` degrees of freedom is at least `x`
+pub(super) fn chi_squared_sf(x: f64, df: usize) -> f64 {
+    incomplete_gamma(df as f64 / 2.0, x / 2.0).1
+}
+
+/// the probability that a Poisson variable with the mean `lambda` is at least `k`
+pub(super) fn poisson_sf(k: u64, lambda: f64) -> f64 {
+    match k {
+        0 => 1.0,
+        _ => incomplete_gamma(k as f64, lambda).0,
+    }
+}
+
+/// the mid p-value `P(X > k) + P(X = k) / 2` of a Poisson variable with the mean `lambda`,
+/// which stays below 1 for a zero count unlike `P(X >= k)`
+pub(super) fn poisson_mid_p(k: u64, lambda: f64) -> f64 {
+    0.5 * (poisson_sf(k, lambda) + poisson_sf(k + 1, lambda))
+}
+
+/// the probability that the Kolmogorov distribution exceeds `lambda`
+pub(super) fn kolmogorov_sf(lambda: f64) -> f64 {
+    if lambda <= 0.0 {
+        return 1.0;
+    }
+    match lambda < 1.18 {
+        true => {
+            // the series of the cdf converging fast for small arguments
+            let t = -std::f64::consts::PI.powi(2) / (8.0 * lambda * lambda);
+            let sum: f64 = (1..=20).map(|k| ((2 * k - 1) as f64).powi(2) * t).map(f64::exp).sum();
+            (1.0 - (2.0 * std::f64::consts::PI).sqrt() / lambda * sum).clamp(0.0, 1.0)
+        }
+        false => {
+            let sum: f64 = (1..=100)
+                .map(|k| {
+                    let term = (-2.0 * (k * k) as f64 * lambda * lambda).exp();
+                    match k % 2 {
+                        1 => term,
+                        _ => -term,
+                    }
+                })
+                .sum();
+            (2.0 * sum).clamp(0.0, 1.0)
+        }
+    }
+}
+
+/// the chi-squared statistic and its degrees of freedom of the counts against the cell probabilities
+///
+/// The neighbouring cells are merged from the left until each expects at least 5 observations.
+pub(super) fn chi_squared(observed: &[u64], probabilities: &[f64]) -> (f64, usize) {
+    let n = observed.iter().sum::<u64>() as f64;
+    let (mut cells, mut o, mut e) = (vec![], 0.0, 0.0);
+    for (&c, &p) in observed.iter().zip(probabilities) {
+        o += c as f64;
+        e += p * n;
+        if e >= 5.0 {
+            cells.push((o, e));
+            (o, e) = (0.0, 0.0);
+        }
+    }
+    if e > 0.0 || o > 0.0 {
+        match cells.last_mut() {
+            Some(last) => {
+                last.0 += o;
+                last.1 += e;
+            }
+            None => cells.push((o, e)),
+        }
+    }
+    let statistic = cells.iter().map(|&(o, e)| (o - e).powi(2) / e).sum();
+    (statistic, cells.len().max(2) - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chi_squared_sf() {
+        // with 2 degrees of freedom the survival function is exp(-x / 2)
+        for x in [0.1, 1.0, 5.0, 30.0, 100.0] {
+            let p = chi_squared_sf(x, 2);
+            assert!((p - (-x / 2.0f64).exp()).abs() < 1e-12 * p.max(1e-300) + 1e-300);
+        }
+        assert!((chi_squared_sf(3.841459, 1) - 0.05).abs() < 1e-6);
+        assert!((chi_squared_sf(124.342, 100) - 0.05).abs() < 1e-5);
+        assert!(chi_squared_sf(2000.0, 10) < 1e-300);
+    }
+
+    #[test]
+    fn test_poisson_sf() {
+        let lambda = 3.5f64;
+        let mut cdf = 0.0;
+        let mut pmf = (-lambda).exp();
+        for k in 0..20u64 {
+            assert!((poisson_sf(k, lambda) - (1.0 - cdf)).abs() < 1e-12);
+            cdf += pmf;
+            pmf *= lambda / (k + 1) as f64;
+        }
+    }
+
+    #[test]
+    fn test_poisson_mid_p() {
+        let lambda = 3.5f64;
+        let pmf = |k: i32| (-lambda).exp() * lambda.powi(k) / (1..=k).product::<i32>() as f64;
+        assert!((poisson_mid_p(0, lambda) - (1.0 - 0.5 * pmf(0))).abs() < 1e-12);
+        assert!((poisson_mid_p(2, lambda) - (poisson_sf(3, lambda) + 0.5 * pmf(2))).abs() < 1e-12);
+        assert!((poisson_mid_p(0, 1e-6) - (1.0 - 0.5 * (-1e-6f64).exp())).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_kolmogorov_sf() {
+        assert!((kolmogorov_sf(0.5) - 0.963945).abs() < 1e-5);
+        assert!((kolmogorov_sf(1.0) - 0.269999).abs() < 1e-5);
+        assert!((kolmogorov_sf(1.36) - 0.049486).abs() < 1e-5);
+        // both series agree where they switch
+        assert!((kolmogorov_sf(1.1799999) - kolmogorov_sf(1.18)).abs() < 1e-7);
+        assert_eq!(1.0, kolmogorov_sf(0.0));
+    }
+
+    #[test]
+    fn test_chi_squared_merges_cells() {
+        let (statistic, df) = chi_squared(&[50, 50, 1, 0], &[0.5, 0.49, 0.009, 0.001]);
+        assert_eq!(1, df);
+        assert!((statistic - ((50.0f64 - 50.5).powi(2) / 50.5 + (51.0f64 - 50.5).powi(2) / 50.5)).abs() < 1e-12);
+    }
+}
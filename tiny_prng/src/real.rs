@@ -0,0 +1,148 @@
+//! The `real` module provides the conversions from random words to floating point numbers.
+//!
+//! A double is built from the upper 53 bits of a 64bit word and a float from the upper 24 bits of a 32bit word,
+//! so every result lies on a uniform lattice of 2<sup>-53</sup> (resp. 2<sup>-24</sup>) and no bit is used twice.
+//!
+//! | function                              | interval | lattice                                                        |
+//! |---------------------------------------|----------|----------------------------------------------------------------|
+//! | `f64_half_open` / `f32_half_open`     | `[0, 1)` | `k / 2^53` for `k` in `0..2^53`                                |
+//! | `f64_open_closed` / `f32_open_closed` | `(0, 1]` | `k / 2^53` for `k` in `1..=2^53`                               |
+//! | `f64_open` / `f32_open`               | `(0, 1)` | `(k + 0.5) / 2^52` for `k` in `0..2^52`                        |
+//! | `f64_closed` / `f32_closed`           | `[0, 1]` | `k / (2^53 - 1)` for `k` in `0..2^53`                          |
+//!
+//! (the `f32` variants use 24 bits in place of 53 bits)
+//!
+//! ```rust
+//! use tiny_prng::real;
+//!
+//! fn main(){
+//!     assert_eq!(0.0, real::f64_half_open(0));
+//!     assert!(real::f64_half_open(u64::MAX) < 1.0);
+//!     assert_eq!(1.0, real::f64_closed(u64::MAX));
+//!     assert!(real::f32_open(0) > 0.0);
+//! }
+//! ```
+
+/// 2^-53
+const SCALE53: f64 = 1.0 / (1u64 << 53) as f64;
+/// 2^-52
+const SCALE52: f64 = 1.0 / (1u64 << 52) as f64;
+/// 1 / (2^53 - 1)
+const SCALE53_CLOSED: f64 = 1.0 / ((1u64 << 53) - 1) as f64;
+/// 2^-24
+const SCALE24: f32 = 1.0 / (1u32 << 24) as f32;
+/// 2^-23
+const SCALE23: f32 = 1.0 / (1u32 << 23) as f32;
+/// 1 / (2^24 - 1)
+const SCALE24_CLOSED: f32 = 1.0 / ((1u32 << 24) - 1) as f32;
+
+/// convert a word to a double in `[0, 1)` with 53bit precision
+#[inline]
+pub fn f64_half_open(x: u64) -> f64 {
+    (x >> 11) as f64 * SCALE53
+}
+
+/// convert a word to a double in `(0, 1]` with 53bit precision
+#[inline]
+pub fn f64_open_closed(x: u64) -> f64 {
+    ((x >> 11) + 1) as f64 * SCALE53
+}
+
+/// convert a word to a double in `(0, 1)` with 52bit precision
+#[inline]
+pub fn f64_open(x: u64) -> f64 {
+    ((x >> 12) as f64 + 0.5) * SCALE52
+}
+
+/// convert a word to a double in `[0, 1]` with 53bit precision
+#[inline]
+pub fn f64_closed(x: u64) -> f64 {
+    (x >> 11) as f64 * SCALE53_CLOSED
+}
+
+/// convert a word to a float in `[0, 1)` with 24bit precision
+#[inline]
+pub fn f32_half_open(x: u32) -> f32 {
+    (x >> 8) as f32 * SCALE24
+}
+
+/// convert a word to a float in `(0, 1]` with 24bit precision
+#[inline]
+pub fn f32_open_closed(x: u32) -> f32 {
+    ((x >> 8) + 1) as f32 * SCALE24
+}
+
+/// convert a word to a float in `(0, 1)` with 23bit precision
+#[inline]
+pub fn f32_open(x: u32) -> f32 {
+    ((x >> 9) as f32 + 0.5) * SCALE23
+}
+
+/// convert a word to a float in `[0, 1]` with 24bit precision
+#[inline]
+pub fn f32_closed(x: u32) -> f32 {
+    (x >> 8) as f32 * SCALE24_CLOSED
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_f64_boundaries() {
+        assert_eq!(0.0, f64_half_open(0));
+        assert_eq!(1.0 - SCALE53, f64_half_open(u64::MAX));
+        assert_eq!(SCALE53, f64_open_closed(0));
+        assert_eq!(1.0, f64_open_closed(u64::MAX));
+        assert_eq!(SCALE53, f64_open(0));
+        assert_eq!(1.0 - SCALE53, f64_open(u64::MAX));
+        assert_eq!(0.0, f64_closed(0));
+        assert_eq!(1.0, f64_closed(u64::MAX));
+    }
+
+    #[test]
+    fn test_f32_boundaries() {
+        assert_eq!(0.0, f32_half_open(0));
+        assert_eq!(1.0 - SCALE24, f32_half_open(u32::MAX));
+        assert_eq!(SCALE24, f32_open_closed(0));
+        assert_eq!(1.0, f32_open_closed(u32::MAX));
+        assert_eq!(SCALE24, f32_open(0));
+        assert_eq!(1.0 - SCALE24, f32_open(u32::MAX));
+        assert_eq!(0.0, f32_closed(0));
+        assert_eq!(1.0, f32_closed(u32::MAX));
+    }
+
+    #[test]
+    fn test_f64_lattice() {
+        // the lowest 11 bits are discarded, the next bit is the lattice unit
+        assert_eq!(0.0, f64_half_open(0x7ff));
+        assert_eq!(SCALE53, f64_half_open(0x800));
+        assert_eq!(0.5, f64_half_open(1 << 63));
+        assert_eq!(0.5, f64_open_closed((1 << 63) - 1));
+        assert_eq!(0.5 + SCALE53, f64_open(1 << 63));
+    }
+
+    #[test]
+    fn test_f32_lattice() {
+        assert_eq!(0.0, f32_half_open(0xff));
+        assert_eq!(SCALE24, f32_half_open(0x100));
+        assert_eq!(0.5, f32_half_open(1 << 31));
+        assert_eq!(0.5, f32_open_closed((1 << 31) - 1));
+        assert_eq!(0.5 + SCALE24, f32_open(1 << 31));
+    }
+
+    #[test]
+    fn test_conversion_is_exact() {
+        // every lattice point is representable, so the conversion never rounds up to the excluded bound
+        for k in [1u64, 2, 3, (1 << 52) + 1, (1 << 53) - 2, (1 << 53) - 1] {
+            let v = f64_half_open(k << 11);
+            assert_eq!(k, (v * (1u64 << 53) as f64) as u64);
+            assert!(v < 1.0);
+        }
+        for k in [1u32, 2, 3, (1 << 23) + 1, (1 << 24) - 2, (1 << 24) - 1] {
+            let v = f32_half_open(k << 8);
+            assert_eq!(k, (v * (1u32 << 24) as f32) as u32);
+            assert!(v < 1.0);
+        }
+    }
+}
@@ -0,0 +1,414 @@
+//! The `seed` module provides `SeedSequence`, which turns arbitrary entropy into well mixed seeds.
+//!
+//! The entropy words are hashed into a pool of 4 words with the mixing of NumPy's `SeedSequence`
+//! (after O'Neill's `seed_seq_fe`), and the pool is stretched into as many seed words as a generator needs.
+//! A sequence spawns children which share the entropy but differ in their spawn keys, for seeding
+//! parallel generators hierarchically without overlapping seeds.
+//!
+//! ```rust
+//! use tiny_prng::seed::SeedSequence;
+//! use tiny_prng::mt64::Mt19937;
+//! use tiny_prng::pcg::PcgXslRr;
+//! use tiny_prng::xorshift::Xorshift1024star;
+//!
+//! fn main(){
+//!     let mut root = SeedSequence::from_u128(0x8c3f_0aa5_4d6e_29b1);
+//!     let mut pcg = PcgXslRr::with_seed(root.seed_u128());
+//!     let mut xs = Xorshift1024star::with_seed(root.seed_array());
+//!     // one independent generator per worker
+//!     let workers: Vec<Mt19937> = root.spawn(4).iter().map(|s| Mt19937::with_array(s.key(8))).collect();
+//!     println!("{} {} {}", pcg.generate(), xs.generate(), workers.len());
+//! }
+//! ```
+
+use crate::{mt, mt64, pcg, xorshift};
+
+/// the number of the words in the pool
+const POOL_SIZE: usize = 4;
+
+const INIT_A: u32 = 0x43b0d7e5;
+const MULT_A: u32 = 0x931e8875;
+const INIT_B: u32 = 0x8b51f9dd;
+const MULT_B: u32 = 0x58f38ded;
+const MIX_MULT_L: u32 = 0xca01f9dd;
+const MIX_MULT_R: u32 = 0x4973f715;
+const XSHIFT: u32 = 16;
+
+/// hash a word with the multiplier advanced on every call
+#[inline]
+fn hashmix(value: u32, hash_const: &mut u32) -> u32 {
+    let mut value = value ^ *hash_const;
+    *hash_const = hash_const.wrapping_mul(MULT_A);
+    value = value.wrapping_mul(*hash_const);
+    value ^ (value >> XSHIFT)
+}
+
+#[inline]
+fn mix(x: u32, y: u32) -> u32 {
+    let result = MIX_MULT_L.wrapping_mul(x).wrapping_sub(MIX_MULT_R.wrapping_mul(y));
+    result ^ (result >> XSHIFT)
+}
+
+/// split an integer into 32bit words from the least significant, keeping at least one word
+fn words(mut value: u128) -> Vec<u32> {
+    let mut v = vec![value as u32];
+    value >>= 32;
+    while value != 0 {
+        v.push(value as u32);
+        value >>= 32;
+    }
+    v
+}
+
+/// The mixer of entropy which produces the seeds of the generators.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeedSequence {
+    entropy: Vec<u32>,
+    spawn_key: Vec<u32>,
+    pool: [u32; POOL_SIZE],
+    children_spawned: u32,
+}
+
+impl SeedSequence {
+    /// construct the sequence from entropy words of any length
+    pub fn new(entropy: &[u32]) -> Self {
+        Self::with_spawn_key(entropy, &[])
+    }
+
+    /// construct the sequence from an integer, taken as 32bit words from the least significant
+    pub fn from_u128(entropy: u128) -> Self {
+        Self::new(&words(entropy))
+    }
+
+    /// construct the sequence from bytes such as a string, identically on every platform
+    ///
+    /// The bytes are packed into 32bit words in little-endian order with the last word padded by zeros,
+    /// followed by the number of the bytes as a 64bit word with the lower half first, and the words are
+    /// mixed as the entropy. The hash is part of the stable interface: a string seeds the same sequence in
+    /// every release and on every target including WASM.
+    pub fn from_bytes<B: AsRef<[u8]> + ?Sized>(bytes: &B) -> Self {
+        let bytes = bytes.as_ref();
+        let mut entropy: Vec<u32> = bytes
+            .chunks(4)
+            .map(|c| c.iter().rev().fold(0, |w, &b| w << 8 | b as u32))
+            .collect();
+        let len = bytes.len() as u64;
+        entropy.push(len as u32);
+        entropy.push((len >> 32) as u32);
+        Self::new(&entropy)
+    }
+
+    /// construct the sequence for a spawn key, the path of the child from the root sequence
+    pub fn with_spawn_key(entropy: &[u32], spawn_key: &[u32]) -> Self {
+        let mut material = entropy.to_vec();
+        if !spawn_key.is_empty() && material.len() < POOL_SIZE {
+            // keeps the spawn key apart from the entropy
+            material.resize(POOL_SIZE, 0);
+        }
+        material.extend_from_slice(spawn_key);
+
+        let mut pool = [0; POOL_SIZE];
+        let mut hash_const = INIT_A;
+        for (i, p) in pool.iter_mut().enumerate() {
+            *p = hashmix(material.get(i).copied().unwrap_or(0), &mut hash_const);
+        }
+        for src in 0..POOL_SIZE {
+            for dst in 0..POOL_SIZE {
+                if src != dst {
+                    pool[dst] = mix(pool[dst], hashmix(pool[src], &mut hash_const));
+                }
+            }
+        }
+        for &word in material.iter().skip(POOL_SIZE) {
+            for p in pool.iter_mut() {
+                *p = mix(*p, hashmix(word, &mut hash_const));
+            }
+        }
+        Self {
+            entropy: entropy.to_vec(),
+            spawn_key: spawn_key.to_vec(),
+            pool,
+            children_spawned: 0,
+        }
+    }
+
+    pub fn entropy(&self) -> &[u32] {
+        &self.entropy
+    }
+
+    pub fn spawn_key(&self) -> &[u32] {
+        &self.spawn_key
+    }
+
+    /// spawn `n` children; successive calls never spawn the same child twice
+    pub fn spawn(&mut self, n: usize) -> Vec<SeedSequence> {
+        (0..n)
+            .map(|_| {
+                let mut key = self.spawn_key.clone();
+                key.push(self.children_spawned);
+                self.children_spawned += 1;
+                Self::with_spawn_key(&self.entropy, &key)
+            })
+            .collect()
+    }
+
+    /// generate `n` seed words
+    pub fn generate_state(&self, n: usize) -> Vec<u32> {
+        let mut hash_const = INIT_B;
+        self.pool
+            .iter()
+            .cycle()
+            .take(n)
+            .map(|&p| {
+                let mut v = p ^ hash_const;
+                hash_const = hash_const.wrapping_mul(MULT_B);
+                v = v.wrapping_mul(hash_const);
+                v ^ (v >> XSHIFT)
+            })
+            .collect()
+    }
+
+    /// generate `n` 64bit seed words, each from two 32bit words with the lower one first
+    pub fn generate_state_u64(&self, n: usize) -> Vec<u64> {
+        self.generate_state(2 * n)
+            .chunks_exact(2)
+            .map(|w| (w[1] as u64) << 32 | w[0] as u64)
+            .collect()
+    }
+
+    /// the seed of `Xorshift32`
+    pub fn seed_u32(&self) -> u32 {
+        self.generate_state(1)[0]
+    }
+
+    /// the seed of the generators with a 64bit state such as `Xorshift64` and `PcgXshRr6432`
+    pub fn seed_u64(&self) -> u64 {
+        self.generate_state_u64(1)[0]
+    }
+
+    /// the seed of `Xorshift128`, `PcgXslRr` and `PcgXslRrMcg`
+    pub fn seed_u128(&self) -> u128 {
+        let w = self.generate_state_u64(2);
+        (w[1] as u128) << 64 | w[0] as u128
+    }
+
+    /// the seed array of `Xorshift1024star` (`N = 16`) and the like
+    pub fn seed_array<const N: usize>(&self) -> [u64; N] {
+        let mut a = [0; N];
+        a.copy_from_slice(&self.generate_state_u64(N));
+        a
+    }
+
+    /// the key of `n` 32bit words for `Mt19937::with_array`, as the initialization by array takes 32bit keys
+    pub fn key(&self, n: usize) -> Vec<u64> {
+        self.generate_state(n).into_iter().map(u64::from).collect()
+    }
+}
+
+/// The generators which are seeded from a `SeedSequence`.
+///
+/// The seeds of the xorshift generators are kept off the all-zero state and those of the MCG variants are made odd,
+/// so every sequence yields a generator of the full period.
+pub trait FromSeedSequence: Sized {
+    fn from_seed_sequence(seq: &SeedSequence) -> Self;
+
+    /// construct the generator from bytes such as a string via `SeedSequence::from_bytes`
+    fn from_bytes<B: AsRef<[u8]> + ?Sized>(bytes: &B) -> Self {
+        Self::from_seed_sequence(&SeedSequence::from_bytes(bytes))
+    }
+
+    /// construct the generator from the entropy of the operating system
+    #[cfg(feature = "entropy")]
+    fn from_entropy() -> Result<Self, crate::entropy::Error> {
+        crate::entropy::seed_sequence().map(|s| Self::from_seed_sequence(&s))
+    }
+
+    /// construct the generator from the entropy of the operating system, or from the fallback if it is unavailable
+    #[cfg(feature = "entropy")]
+    fn from_entropy_or_fallback() -> Self {
+        Self::from_seed_sequence(&crate::entropy::seed_sequence_or_fallback())
+    }
+}
+
+/// the number of the 32bit key words seeding Mersenne Twister
+const MT_KEY_WORDS: usize = 8;
+
+/// replace the all-zero seed, which a xorshift generator never leaves
+#[inline]
+fn nonzero<T: Default + PartialEq>(seed: T, replacement: T) -> T {
+    match seed == T::default() {
+        true => replacement,
+        false => seed,
+    }
+}
+
+macro_rules! impl_from_seed_sequence {
+    ($t:ty, |$s:ident| $e:expr) => {
+        impl FromSeedSequence for $t {
+            fn from_seed_sequence($s: &SeedSequence) -> Self {
+                $e
+            }
+        }
+    };
+}
+
+impl_from_seed_sequence!(xorshift::Xorshift32, |s| Self::with_seed(nonzero(s.seed_u32(), 1)));
+impl_from_seed_sequence!(xorshift::Xorshift64, |s| Self::with_seed(nonzero(s.seed_u64(), 1)));
+impl_from_seed_sequence!(xorshift::Xorshift64star, |s| Self::with_seed(nonzero(s.seed_u64(), 1)));
+impl_from_seed_sequence!(xorshift::Xorshift128, |s| Self::with_seed(nonzero(s.seed_u128(), 1)));
+impl_from_seed_sequence!(xorshift::Xorshift1024star, |s| {
+    let mut seed: [u64; 16] = s.seed_array();
+    if seed.iter().all(|&w| w == 0) {
+        seed[0] = 1;
+    }
+    Self::with_seed(seed)
+});
+impl_from_seed_sequence!(xorshift::Xoshiro256plusplus, |s| {
+    let mut seed: [u64; 4] = s.seed_array();
+    if seed.iter().all(|&w| w == 0) {
+        seed[0] = 1;
+    }
+    Self::with_seed(seed)
+});
+impl_from_seed_sequence!(pcg::PcgXshRr6432, |s| Self::with_seed(s.seed_u64()));
+impl_from_seed_sequence!(pcg::PcgXshRs6432, |s| Self::with_seed(s.seed_u64()));
+impl_from_seed_sequence!(pcg::PcgXslRr6432Mcg, |s| Self::with_seed(s.seed_u64() | 1));
+impl_from_seed_sequence!(pcg::PcgXslRr, |s| Self::with_seed(s.seed_u128()));
+impl_from_seed_sequence!(pcg::PcgXslRrMcg, |s| Self::with_seed(s.seed_u128() | 1));
+impl_from_seed_sequence!(pcg::Pcg32, |s| {
+    let [seed, stream]: [u64; 2] = s.seed_array();
+    Self::with_stream(seed, stream)
+});
+impl_from_seed_sequence!(pcg::Pcg64, |s| {
+    let w: [u64; 4] = s.seed_array();
+    Self::with_stream((w[1] as u128) << 64 | w[0] as u128, (w[3] as u128) << 64 | w[2] as u128)
+});
+impl_from_seed_sequence!(mt::Mt19937, |s| Self::with_array(s.key(MT_KEY_WORDS)));
+impl_from_seed_sequence!(mt64::Mt19937, |s| Self::with_array(s.key(MT_KEY_WORDS)));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xorshift::Xorshift1024star;
+
+    const GOLDEN_SEED: u64 = 0x9432e0a5bcb1c9c5;
+    const GOLDEN_OUTPUT: u64 = 0xfe8dde9914ae7b16;
+
+    #[test]
+    fn test_words() {
+        assert_eq!(vec![0], words(0));
+        assert_eq!(vec![0x89abcdef, 0x1234567], words(0x123456789abcdef));
+        assert_eq!(vec![0, 0, 1], words(1 << 64));
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let a = SeedSequence::new(&[1, 2, 3]);
+        let b = SeedSequence::new(&[1, 2, 3]);
+        assert_eq!(a.generate_state(10), b.generate_state(10));
+        assert_ne!(a.generate_state(10), SeedSequence::new(&[1, 2, 4]).generate_state(10));
+        assert_eq!(a.generate_state(3), a.generate_state(10)[..3]);
+        assert_eq!(SeedSequence::from_u128(0x123456789abcdef), SeedSequence::new(&[0x89abcdef, 0x1234567]));
+    }
+
+    #[test]
+    fn test_state_u64_composition() {
+        let s = SeedSequence::new(&[0x1818729]);
+        let w = s.generate_state(4);
+        let d = s.generate_state_u64(2);
+        assert_eq!((w[1] as u64) << 32 | w[0] as u64, d[0]);
+        assert_eq!((w[3] as u64) << 32 | w[2] as u64, d[1]);
+        assert_eq!(d[0], s.seed_u64());
+        assert_eq!((d[1] as u128) << 64 | d[0] as u128, s.seed_u128());
+        assert_eq!(w[0], s.seed_u32());
+        let a: [u64; 16] = s.seed_array();
+        assert_eq!(s.generate_state_u64(16), a.to_vec());
+        assert_eq!(s.key(4), w.iter().map(|&x| x as u64).collect::<Vec<u64>>());
+        let mut x = Xorshift1024star::with_seed(a);
+        assert_ne!(0, x.generate());
+    }
+
+    #[test]
+    fn test_spawn() {
+        let mut root = SeedSequence::from_u128(42);
+        let children = root.spawn(3);
+        let more = root.spawn(2);
+        let keys: Vec<&[u32]> = children.iter().chain(more.iter()).map(|c| c.spawn_key()).collect();
+        assert_eq!(vec![&[0][..], &[1], &[2], &[3], &[4]], keys);
+        let grandchild = children[1].clone().spawn(1).remove(0);
+        assert_eq!(vec![1, 0], grandchild.spawn_key());
+        // every sequence yields a distinct seed
+        let mut seeds: Vec<u128> = children.iter().chain(more.iter()).map(|c| c.seed_u128()).collect();
+        seeds.push(root.seed_u128());
+        seeds.push(grandchild.seed_u128());
+        seeds.sort();
+        seeds.dedup();
+        assert_eq!(7, seeds.len());
+        assert_eq!(children[2], SeedSequence::with_spawn_key(&[42], &[2]));
+    }
+
+    #[test]
+    fn test_from_seed_sequence() {
+        let s = SeedSequence::from_u128(7);
+        let mut a = xorshift::Xorshift64::from_seed_sequence(&s);
+        let mut b = xorshift::Xorshift64::with_seed(s.seed_u64());
+        assert_eq!(a.generate(), b.generate());
+        let mut m = mt::Mt19937::from_seed_sequence(&s);
+        let mut n = mt::Mt19937::with_array(s.key(MT_KEY_WORDS));
+        assert_eq!(m.generate(), n.generate());
+        let mut p = pcg::PcgXslRrMcg::from_seed_sequence(&s);
+        assert_ne!(0, p.generate());
+        let [seed, stream]: [u64; 2] = s.seed_array();
+        let mut p = pcg::Pcg32::from_seed_sequence(&s);
+        let mut q = pcg::Pcg32::with_stream(seed, stream);
+        assert_eq!(p.generate(), q.generate());
+    }
+
+    #[test]
+    fn test_from_bytes() {
+        assert_eq!(SeedSequence::new(&[0x65736564, 0x342d7472, 0x32, 9, 0]), SeedSequence::from_bytes("desert-42"));
+        assert_eq!(SeedSequence::from_bytes("desert-42"), SeedSequence::from_bytes(b"desert-42"));
+        // the length tells trailing zero bytes apart
+        assert_ne!(SeedSequence::from_bytes(b"a"), SeedSequence::from_bytes(b"a\0"));
+        assert_ne!(SeedSequence::from_bytes(""), SeedSequence::from_bytes(b"\0"));
+        let mut a = xorshift::Xorshift64::from_bytes("desert-42");
+        let mut b = xorshift::Xorshift64::from_bytes("desert-43");
+        assert_ne!(a.generate(), b.generate());
+    }
+
+    #[test]
+    fn test_from_bytes_stable() {
+        // the values pin the hash, which must not change between releases and platforms
+        assert_eq!(GOLDEN_SEED, SeedSequence::from_bytes("desert-42").seed_u64());
+        let mut x = xorshift::Xorshift64::from_bytes("desert-42");
+        assert_eq!(GOLDEN_OUTPUT, x.generate());
+    }
+
+    #[test]
+    fn test_nonzero() {
+        assert_eq!(1u32, nonzero(0, 1));
+        assert_eq!(5u64, nonzero(5, 1));
+    }
+
+    #[test]
+    fn test_spawn_key_apart_from_entropy() {
+        // a short entropy is padded, so the spawn key does not act as entropy words
+        assert_ne!(SeedSequence::new(&[7, 0]).seed_u64(), SeedSequence::with_spawn_key(&[7], &[0]).seed_u64());
+    }
+
+    #[test]
+    fn test_bit_balance() {
+        // consecutive integers yield seeds whose bits are balanced
+        let count = 20000;
+        let mut ones = [0usize; 64];
+        for i in 0..count {
+            let s = SeedSequence::from_u128(i).seed_u64();
+            for (b, o) in ones.iter_mut().enumerate() {
+                *o += ((s >> b) & 1) as usize;
+            }
+        }
+        // 5 sigma of the binomial distribution
+        let sigma = (count as f64 * 0.25).sqrt();
+        assert!(ones.iter().all(|&o| (o as f64 - count as f64 / 2.0).abs() < 5.0 * sigma));
+    }
+}
@@ -0,0 +1,307 @@
+//! The `seq` module provides shuffling and sampling of slices and iterators with any generator.
+//!
+//! Every index is drawn with the unbiased bounded integer generation of the `Generator` trait,
+//! so the results are reproducible for a given seed.
+//!
+//! ```rust
+//! use tiny_prng::seq;
+//! use tiny_prng::xorshift::Xorshift64;
+//!
+//! fn main(){
+//!     let mut x = Xorshift64::with_seed(0x1818729);
+//!     let mut cards: Vec<u32> = (1..=52).collect();
+//!     seq::shuffle(&mut x, &mut cards);
+//!     let hand = seq::choose_multiple(&mut x, &cards, 5);
+//!     let winners = seq::sample_indices(&mut x, 1_000_000, 3);
+//!     println!("{:?} {:?} {:?}", seq::choose(&mut x, &cards), hand, winners);
+//! }
+//! ```
+
+use std::collections::HashMap;
+use crate::generator::{Generator, UniformInt};
+
+/// the largest amount sampled with Floyd's algorithm, whose cost is quadratic in the amount
+const FLOYD_THRESHOLD: usize = 16;
+
+/// shuffle the slice in place with the Fisher-Yates algorithm
+pub fn shuffle<G: Generator + ?Sized, T>(g: &mut G, slice: &mut [T]) {
+    for i in (1..slice.len()).rev() {
+        let j = usize::sample_below(g, i + 1);
+        slice.swap(i, j);
+    }
+}
+
+/// move `amount` elements chosen at random to the front of the slice in random order
+///
+/// Returns the chosen elements and the rest. An amount beyond the length shuffles the whole slice.
+pub fn partial_shuffle<'a, G: Generator + ?Sized, T>(g: &mut G, slice: &'a mut [T], amount: usize) -> (&'a mut [T], &'a mut [T]) {
+    let len = slice.len();
+    let amount = amount.min(len);
+    for i in 0..amount {
+        let j = i + usize::sample_below(g, len - i);
+        slice.swap(i, j);
+    }
+    slice.split_at_mut(amount)
+}
+
+/// choose an element of the slice, or `None` for an empty one
+pub fn choose<'a, G: Generator + ?Sized, T>(g: &mut G, slice: &'a [T]) -> Option<&'a T> {
+    match slice.is_empty() {
+        true => None,
+        false => Some(&slice[usize::sample_below(g, slice.len())]),
+    }
+}
+
+/// choose `amount` distinct elements of the slice in random order
+///
+/// An amount beyond the length chooses every element.
+pub fn choose_multiple<'a, G: Generator + ?Sized, T>(g: &mut G, slice: &'a [T], amount: usize) -> Vec<&'a T> {
+    sample_indices(g, slice.len(), amount.min(slice.len()))
+        .into_iter()
+        .map(|i| &slice[i])
+        .collect()
+}
+
+/// sample `amount` distinct indices in `[0, length)` in random order
+///
+/// A small amount is sampled with Floyd's algorithm, an amount comparable to the length with a partial
+/// shuffle of all the indices, and the others with a partial shuffle over a sparse set of the moved indices.
+///
+/// Panics if the amount exceeds the length.
+pub fn sample_indices<G: Generator + ?Sized>(g: &mut G, length: usize, amount: usize) -> Vec<usize> {
+    assert!(amount <= length, "sample_indices: amount must not exceed length");
+    match amount {
+        a if a <= FLOYD_THRESHOLD => sample_floyd(g, length, amount),
+        a if a >= length / 4 => {
+            let mut indices: Vec<usize> = (0..length).collect();
+            partial_shuffle(g, &mut indices, amount);
+            indices.truncate(amount);
+            indices
+        }
+        _ => sample_sparse(g, length, amount),
+    }
+}
+
+/// Floyd's algorithm; inserting a repeated pick before the colliding index keeps the order uniform
+fn sample_floyd<G: Generator + ?Sized>(g: &mut G, length: usize, amount: usize) -> Vec<usize> {
+    let mut indices = Vec::with_capacity(amount);
+    for j in length - amount..length {
+        let t = usize::sample_below(g, j + 1);
+        match indices.iter().position(|&x| x == t) {
+            Some(pos) => indices.insert(pos, j),
+            None => indices.push(t),
+        }
+    }
+    indices
+}
+
+/// the partial Fisher-Yates shuffle of `0..length` recording only the displaced entries
+fn sample_sparse<G: Generator + ?Sized>(g: &mut G, length: usize, amount: usize) -> Vec<usize> {
+    let mut moved: HashMap<usize, usize> = HashMap::with_capacity(amount);
+    let mut indices = Vec::with_capacity(amount);
+    for i in 0..amount {
+        let j = i + usize::sample_below(g, length - i);
+        let picked = *moved.get(&j).unwrap_or(&j);
+        let current = *moved.get(&i).unwrap_or(&i);
+        moved.insert(j, current);
+        indices.push(picked);
+    }
+    indices
+}
+
+/// sample `amount` items of the iterator with the reservoir sampling (algorithm R)
+///
+/// The items are returned in random order; all of them are returned if the iterator is shorter than the amount.
+pub fn reservoir_sample<G: Generator + ?Sized, I: IntoIterator>(g: &mut G, iter: I, amount: usize) -> Vec<I::Item> {
+    let mut iter = iter.into_iter();
+    let mut reservoir: Vec<I::Item> = iter.by_ref().take(amount).collect();
+    if reservoir.len() < amount {
+        shuffle(g, &mut reservoir);
+        return reservoir;
+    }
+    for (i, item) in iter.enumerate() {
+        let j = usize::sample_below(g, amount + i + 1);
+        if j < amount {
+            reservoir[j] = item;
+        }
+    }
+    shuffle(g, &mut reservoir);
+    reservoir
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::tests::{chi_squared, chi_squared_critical};
+    use crate::pcg::PcgXslRr;
+    use crate::xorshift::Xorshift64;
+
+    const COUNT: usize = 100 * 1000;
+
+    /// chi-squared test of the counts against the uniform distribution
+    fn uniform(observed: &[usize]) -> bool {
+        let total: usize = observed.iter().sum();
+        let expected = vec![total as f64 / observed.len() as f64; observed.len()];
+        chi_squared(observed, &expected) < chi_squared_critical(observed.len() - 1)
+    }
+
+    /// the rank of a permutation of 0..4 in 0..24
+    fn rank(p: &[usize]) -> usize {
+        p.iter().enumerate().fold(0, |acc, (i, &x)| {
+            let smaller = p[i + 1..].iter().filter(|&&y| y < x).count();
+            acc * (p.len() - i) + smaller
+        })
+    }
+
+    #[test]
+    fn test_shuffle_permutations() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        let mut observed = vec![0; 24];
+        for _ in 0..COUNT {
+            let mut v = [0, 1, 2, 3];
+            shuffle(&mut g, &mut v);
+            observed[rank(&v)] += 1;
+        }
+        assert!(uniform(&observed));
+    }
+
+    #[test]
+    fn test_shuffle_trivial() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        let mut empty: [u8; 0] = [];
+        shuffle(&mut g, &mut empty);
+        let mut one = [7];
+        shuffle(&mut g, &mut one);
+        assert_eq!([7], one);
+    }
+
+    #[test]
+    fn test_shuffle_reproducible() {
+        let mut v: Vec<u32> = (0..100).collect();
+        let mut w = v.clone();
+        shuffle(&mut PcgXslRr::with_seed(0x1818729182367349), &mut v);
+        shuffle(&mut PcgXslRr::with_seed(0x1818729182367349), &mut w);
+        assert_eq!(v, w);
+        v.sort();
+        assert_eq!((0..100).collect::<Vec<u32>>(), v);
+    }
+
+    #[test]
+    fn test_partial_shuffle() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        let mut observed = vec![0; 10];
+        for _ in 0..COUNT {
+            let mut v: Vec<usize> = (0..10).collect();
+            let (chosen, rest) = partial_shuffle(&mut g, &mut v, 3);
+            assert_eq!((3, 7), (chosen.len(), rest.len()));
+            observed[chosen[0]] += 1;
+            let mut all: Vec<usize> = chosen.iter().chain(rest.iter()).copied().collect();
+            all.sort();
+            assert_eq!((0..10).collect::<Vec<usize>>(), all);
+        }
+        assert!(uniform(&observed));
+        let mut v = [1, 2, 3];
+        let (chosen, rest) = partial_shuffle(&mut g, &mut v, 5);
+        assert_eq!((3, 0), (chosen.len(), rest.len()));
+    }
+
+    #[test]
+    fn test_choose() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        let v: Vec<usize> = (0..7).collect();
+        let mut observed = vec![0; 7];
+        for _ in 0..COUNT {
+            observed[*choose(&mut g, &v).unwrap()] += 1;
+        }
+        assert!(uniform(&observed));
+        let empty: [u8; 0] = [];
+        assert_eq!(None, choose(&mut g, &empty));
+    }
+
+    #[test]
+    fn test_choose_multiple() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        let v = ['a', 'b', 'c', 'd'];
+        let mut chosen = choose_multiple(&mut g, &v, 10);
+        chosen.sort();
+        assert_eq!(vec![&'a', &'b', &'c', &'d'], chosen);
+        assert!(choose_multiple(&mut g, &v, 0).is_empty());
+    }
+
+    /// checks the distinctness, the inclusion frequency and the order of the sampled indices
+    fn check_sample_indices(length: usize, amount: usize, rounds: usize) {
+        let mut g = PcgXslRr::with_seed(0x1818729182367349);
+        let mut included = vec![0; length];
+        let mut first = vec![0; length];
+        for _ in 0..rounds {
+            let v = sample_indices(&mut g, length, amount);
+            assert_eq!(amount, v.len());
+            let mut sorted = v.clone();
+            sorted.sort();
+            sorted.dedup();
+            assert_eq!(amount, sorted.len());
+            assert!(sorted.iter().all(|&i| i < length));
+            for &i in &v {
+                included[i] += 1;
+            }
+            first[v[0]] += 1;
+        }
+        assert!(uniform(&included), "inclusion of {} of {}", amount, length);
+        assert!(uniform(&first), "order of {} of {}", amount, length);
+    }
+
+    #[test]
+    fn test_sample_indices_floyd() {
+        check_sample_indices(50, 10, COUNT / 10);
+    }
+
+    #[test]
+    fn test_sample_indices_dense() {
+        check_sample_indices(50, 30, COUNT / 10);
+    }
+
+    #[test]
+    fn test_sample_indices_sparse() {
+        check_sample_indices(200, 20, COUNT / 10);
+    }
+
+    #[test]
+    fn test_sample_indices_large() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        let mut v = sample_indices(&mut g, usize::MAX, 1000);
+        v.sort();
+        v.dedup();
+        assert_eq!(1000, v.len());
+        assert!(sample_indices(&mut g, 10, 0).is_empty());
+        let mut all = sample_indices(&mut g, 10, 10);
+        all.sort();
+        assert_eq!((0..10).collect::<Vec<usize>>(), all);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_sample_indices_too_many() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        sample_indices(&mut g, 3, 4);
+    }
+
+    #[test]
+    fn test_reservoir_sample() {
+        let mut g = Xorshift64::with_seed(0x1818729);
+        let mut included = vec![0; 30];
+        let mut first = vec![0; 30];
+        for _ in 0..COUNT / 10 {
+            let v = reservoir_sample(&mut g, 0..30usize, 5);
+            assert_eq!(5, v.len());
+            for &i in &v {
+                included[i] += 1;
+            }
+            first[v[0]] += 1;
+        }
+        assert!(uniform(&included));
+        assert!(uniform(&first));
+        let mut short = reservoir_sample(&mut g, 0..3, 5);
+        short.sort();
+        assert_eq!(vec![0, 1, 2], short);
+    }
+}
@@ -0,0 +1,314 @@
+//! The `spectral` module implements the spectral test of the multipliers of linear congruential generators.
+//!
+//! The `t`-tuples of successive states of an LCG modulo `m` lie on families of parallel hyperplanes, and the test
+//! finds the largest distance `1 / ν_t` between the hyperplanes of a family. `ν_t` is the length of the shortest
+//! nonzero vector of the dual lattice, found exactly by the integral LLL reduction and an enumeration.
+//! The figure of merit `ν_t / (γ_t^(1/2) m^(1/t))` divides it by the bound of Hermite's constant `γ_t`, so it lies
+//! between 0 and 1 and the multipliers above 0.7 or so are good ones. The test of a multiplier does not depend on
+//! the increment. An MCG with a multiplier of 5 modulo 8 is tested with the quarter of its modulus,
+//! which its odd states span.
+//!
+//! ```rust
+//! use tiny_prng::pcg::REFERENCE_MULTIPLIER;
+//! use tiny_prng::spectral::spectral_test;
+//!
+//! fn main(){
+//!     let report = spectral_test(REFERENCE_MULTIPLIER as u128, 64, 8);
+//!     println!("{}", report);
+//!     assert!(report.merit() > 0.5);
+//! }
+//! ```
+
+mod int;
+
+use std::fmt;
+use int::Int;
+use crate::pcg::{MULTIPLIER, MULTIPLIER128, REFERENCE_MULTIPLIER, REFERENCE_MULTIPLIER128};
+
+/// the highest dimension with a known Hermite's constant
+pub const MAX_DIMENSION: usize = 8;
+
+/// Hermite's constants to the power of the dimension, `γ_t^t`, for `t` up to 8
+const HERMITE_POWERS: [f64; MAX_DIMENSION + 1] = [1.0, 1.0, 4.0 / 3.0, 2.0, 4.0, 8.0, 64.0 / 3.0, 64.0, 256.0];
+
+/// The multipliers of the LCGs in the `pcg` module with the bits of their moduli.
+pub static LCG_MULTIPLIERS: [(&str, u128, u32); 4] = [
+    ("PcgXshRr6432, PcgXshRs6432", MULTIPLIER as u128, 64),
+    ("Pcg32", REFERENCE_MULTIPLIER as u128, 64),
+    ("PcgXslRr", MULTIPLIER128, 128),
+    ("Pcg64", REFERENCE_MULTIPLIER128, 128),
+];
+
+/// The result of the spectral test in one dimension.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Figure {
+    /// the dimension `t`
+    pub dimension: usize,
+    /// the length `ν_t` of the shortest nonzero vector of the dual lattice
+    pub nu: f64,
+    /// the figure of merit `ν_t / (γ_t^(1/2) m^(1/t))` between 0 and 1
+    pub merit: f64,
+}
+
+/// The results of the spectral test of a multiplier from the dimension 2.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub multiplier: u128,
+    pub modulus_bits: u32,
+    pub figures: Vec<Figure>,
+}
+
+impl Report {
+    /// the worst figure of merit over the dimensions, `M_t` of L'Ecuyer
+    pub fn merit(&self) -> f64 {
+        self.figures.iter().map(|f| f.merit).fold(1.0, f64::min)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "multiplier {:#x} modulo 2^{}", self.multiplier, self.modulus_bits)?;
+        writeln!(f, "{:>3}  {:>10}  {:>7}", "t", "log2 nu", "merit")?;
+        for figure in &self.figures {
+            writeln!(f, "{:>3}  {:>10.4}  {:>7.4}", figure.dimension, figure.nu.log2(), figure.merit)?;
+        }
+        write!(f, "figure of merit M_{}: {:.4}", self.figures.len() + 1, self.merit())
+    }
+}
+
+/// run the spectral test of the multiplier modulo `2^modulus_bits` in the dimensions from 2 to `max_dimension`
+pub fn spectral_test(multiplier: u128, modulus_bits: u32, max_dimension: usize) -> Report {
+    assert!((1..=128).contains(&modulus_bits), "the modulus must be from 2^1 to 2^128");
+    assert!((2..=MAX_DIMENSION).contains(&max_dimension), "the dimension must be from 2 to {}", MAX_DIMENSION);
+    let mask = match modulus_bits {
+        128 => u128::MAX,
+        _ => (1 << modulus_bits) - 1,
+    };
+    let figures = (2..=max_dimension)
+        .map(|t| {
+            let nu = shortest_dual_vector(multiplier & mask, modulus_bits, t).to_f64().sqrt();
+            let bound = HERMITE_POWERS[t].powf(0.5 / t as f64) * 2f64.powf(modulus_bits as f64 / t as f64);
+            Figure { dimension: t, nu, merit: nu / bound }
+        })
+        .collect();
+    Report { multiplier, modulus_bits, figures }
+}
+
+fn dot(a: &[Int], b: &[Int]) -> Int {
+    a.iter().zip(b).fold(Int::zero(), |sum, (x, y)| &sum + &(x * y))
+}
+
+/// the squared length of the shortest nonzero vector `x` with `x_0 + a x_1 + ... + a^(t-1) x_(t-1) = 0` modulo `2^bits`
+fn shortest_dual_vector(a: u128, bits: u32, t: usize) -> Int {
+    let mask = match bits {
+        128 => u128::MAX,
+        _ => (1 << bits) - 1,
+    };
+    // the basis of the dual lattice: (m, 0, ..., 0) and (-a^j mod m, e_j) for j from 1
+    let mut basis = vec![vec![Int::zero(); t]; t];
+    basis[0][0] = Int::pow2(bits);
+    let mut power = 1u128;
+    for (j, row) in basis.iter_mut().enumerate().skip(1) {
+        power = power.wrapping_mul(a) & mask;
+        row[0] = -&Int::from(power);
+        row[j] = Int::from(1u128);
+    }
+    let (mu, lengths) = reduce(&mut basis);
+    shortest_vector(&basis, &mu, &lengths)
+}
+
+/// reduce the basis by the integral LLL algorithm (Cohen, Algorithm 2.6.7), keeping every value exact,
+/// and return the Gram-Schmidt coefficients and the squared lengths of the orthogonalized vectors
+fn reduce(basis: &mut [Vec<Int>]) -> (Vec<Vec<f64>>, Vec<f64>) {
+    let n = basis.len();
+    // 1-based as the algorithm: `d[k]` is the Gram determinant of the first `k` vectors
+    let mut d = vec![Int::from(1u128); n + 1];
+    let mut lambda = vec![vec![Int::zero(); n + 1]; n + 1];
+    d[1] = dot(&basis[0], &basis[0]);
+    let (mut k, mut k_max) = (2, 1);
+
+    // size reduction of the vector `k` by the vector `l`
+    let red = |basis: &mut [Vec<Int>], lambda: &mut [Vec<Int>], d: &[Int], k: usize, l: usize| {
+        let twice = &lambda[k][l] + &lambda[k][l];
+        if twice.abs() <= d[l] {
+            return;
+        }
+        let q = lambda[k][l].div_round(&d[l]);
+        let subtrahend: Vec<Int> = basis[l - 1].iter().map(|x| &q * x).collect();
+        for (x, s) in basis[k - 1].iter_mut().zip(&subtrahend) {
+            *x = &*x - s;
+        }
+        lambda[k][l] = &lambda[k][l] - &(&q * &d[l]);
+        let (upper, lower) = lambda.split_at_mut(k);
+        for (x, y) in lower[0][1..l].iter_mut().zip(&upper[l][1..l]) {
+            *x = &*x - &(&q * y);
+        }
+    };
+
+    while k <= n {
+        if k > k_max {
+            k_max = k;
+            for j in 1..=k {
+                let mut u = dot(&basis[k - 1], &basis[j - 1]);
+                for i in 1..j {
+                    u = (&(&d[i] * &u) - &(&lambda[k][i] * &lambda[j][i])).div_exact(&d[i - 1]);
+                }
+                match j < k {
+                    true => lambda[k][j] = u,
+                    false => d[k] = u,
+                }
+            }
+            debug_assert!(!d[k].is_zero(), "the vectors are dependent");
+        }
+        red(basis, &mut lambda, &d, k, k - 1);
+        // the condition of Lovász with 3/4: d_k d_(k-2) >= 3/4 d_(k-1)^2 - lambda_(k,k-1)^2
+        let four = Int::from(4u128);
+        let left = &four * &(&d[k] * &d[k - 2]);
+        let right = &(&Int::from(3u128) * &(&d[k - 1] * &d[k - 1])) - &(&four * &(&lambda[k][k - 1] * &lambda[k][k - 1]));
+        if left < right {
+            basis.swap(k - 1, k - 2);
+            for j in 1..k - 1 {
+                let (upper, lower) = lambda.split_at_mut(k);
+                std::mem::swap(&mut upper[k - 1][j], &mut lower[0][j]);
+            }
+            let l = lambda[k][k - 1].clone();
+            let b = (&(&d[k - 2] * &d[k]) + &(&l * &l)).div_exact(&d[k - 1]);
+            for row in lambda.iter_mut().take(k_max + 1).skip(k + 1) {
+                let t = row[k].clone();
+                row[k] = (&(&d[k] * &row[k - 1]) - &(&l * &t)).div_exact(&d[k - 1]);
+                row[k - 1] = (&(&b * &t) + &(&l * &row[k])).div_exact(&d[k]);
+            }
+            d[k - 1] = b;
+            k = 2.max(k - 1);
+            continue;
+        }
+        for l in (1..k - 1).rev() {
+            red(basis, &mut lambda, &d, k, l);
+        }
+        k += 1;
+    }
+
+    let mu = (1..=n).map(|k| (1..=n).map(|j| lambda[k][j].to_f64() / d[j].to_f64()).collect()).collect();
+    let lengths = (1..=n).map(|k| d[k].to_f64() / d[k - 1].to_f64()).collect();
+    (mu, lengths)
+}
+
+/// the squared length of the shortest nonzero vector of the reduced basis by the enumeration of Fincke and Pohst
+fn shortest_vector(basis: &[Vec<Int>], mu: &[Vec<f64>], lengths: &[f64]) -> Int {
+    let n = basis.len();
+    let norm = |x: &[i64]| {
+        let v: Vec<Int> = (0..n)
+            .map(|c| (0..n).fold(Int::zero(), |sum, i| &sum + &(&Int::from(x[i]) * &basis[i][c])))
+            .collect();
+        dot(&v, &v)
+    };
+    let mut best = basis.iter().map(|b| dot(b, b)).min().unwrap();
+    let mut x = vec![0i64; n];
+
+    // walk the coefficients from the last vector down; the floating bound has a margin and the lengths are exact
+    fn search(
+        level: usize, partial: f64, x: &mut [i64], best: &mut Int, mu: &[Vec<f64>], lengths: &[f64],
+        norm: &dyn Fn(&[i64]) -> Int,
+    ) {
+        let n = x.len();
+        let radius = |best: &Int| best.to_f64() * (1.0 + 1e-9) + 1.0;
+        let center = -(level + 1..n).map(|j| x[j] as f64 * mu[j][level]).sum::<f64>();
+        let width = ((radius(best) - partial) / lengths[level]).max(0.0).sqrt();
+        for xi in (center - width).ceil() as i64..=(center + width).floor() as i64 {
+            // the bound shrinks as shorter vectors are found
+            let step = partial + lengths[level] * (xi as f64 - center).powi(2);
+            if step > radius(best) {
+                continue;
+            }
+            x[level] = xi;
+            match level {
+                0 => {
+                    if x.iter().any(|&c| c != 0) {
+                        let length = norm(x);
+                        if length < *best {
+                            *best = length;
+                        }
+                    }
+                }
+                _ => search(level - 1, step, x, best, mu, lengths, norm),
+            }
+        }
+        x[level] = 0;
+    }
+
+    search(n - 1, 0.0, &mut x, &mut best, mu, lengths, &norm);
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// the squared length of the shortest dual vector by the exhaustive search of a box
+    fn brute_force(a: u128, bits: u32, t: usize) -> u128 {
+        let m = 1i128 << bits;
+        let bound = (2.0 * (m as f64).powf(1.0 / t as f64)).ceil() as i128;
+        let powers: Vec<i128> = (0..t).map(|j| (a as i128).pow(j as u32) % m).collect();
+        let mut best = u128::MAX;
+        let mut x = vec![-bound; t];
+        loop {
+            let dot: i128 = x.iter().zip(&powers).map(|(x, p)| x * p).sum();
+            if dot.rem_euclid(m) == 0 && x.iter().any(|&c| c != 0) {
+                best = best.min(x.iter().map(|c| (c * c) as u128).sum());
+            }
+            match x.iter().position(|&c| c < bound) {
+                Some(i) => {
+                    x[i] += 1;
+                    x[..i].iter_mut().for_each(|c| *c = -bound);
+                }
+                None => return best,
+            }
+        }
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        for &(a, bits) in &[(5u128, 10u32), (69, 12), (1021, 12), (3, 10), (4093, 12), (0x5bd1e995, 12)] {
+            for t in 2..=4 {
+                let nu2 = shortest_dual_vector(a & ((1 << bits) - 1), bits, t);
+                assert_eq!(brute_force(a & ((1 << bits) - 1), bits, t) as f64, nu2.to_f64(), "a = {}, t = {}", a, t);
+            }
+        }
+    }
+
+    #[test]
+    fn test_randu() {
+        // the triples of RANDU lie on 15 planes, from 9 x - 6 y + z = 0 modulo 2^31
+        let report = spectral_test(65539, 31, 4);
+        assert_eq!(118.0, report.figures[1].nu * report.figures[1].nu);
+        assert!(report.figures[1].merit < 0.02);
+        assert!(report.merit() < 0.02);
+    }
+
+    #[test]
+    fn test_bad_multipliers() {
+        // a = 1 puts the pairs on the diagonal: x - y = 0
+        assert_eq!(2f64.sqrt(), spectral_test(1, 64, 2).figures[0].nu);
+        assert!(spectral_test(1 + (1 << 32), 64, 3).figures[1].nu <= 2f64.sqrt() * 2.0);
+    }
+
+    #[test]
+    fn test_pcg_multipliers() {
+        for &(name, multiplier, bits) in &LCG_MULTIPLIERS {
+            let report = spectral_test(multiplier, bits, MAX_DIMENSION);
+            assert_eq!(7, report.figures.len());
+            for f in &report.figures {
+                assert!(f.merit > 0.0 && f.merit <= 1.0, "{}: {}", name, report);
+            }
+        }
+        assert!(spectral_test(REFERENCE_MULTIPLIER as u128, 64, 8).merit() > 0.5);
+        assert!(spectral_test(REFERENCE_MULTIPLIER128, 128, 8).merit() > 0.5);
+    }
+
+    #[test]
+    fn test_display() {
+        let text = spectral_test(65539, 31, 3).to_string();
+        assert!(text.starts_with("multiplier 0x10003 modulo 2^31\n"));
+        assert!(text.ends_with(&format!("figure of merit M_3: {:.4}", spectral_test(65539, 31, 3).merit())));
+    }
+}
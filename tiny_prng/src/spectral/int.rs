@@ -0,0 +1,266 @@
+//! A minimal signed integer of arbitrary size for the exact lattice reduction.
+
+use std::cmp::Ordering;
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// A signed integer stored as its sign and its magnitude in 32bit limbs from the least significant.
+///
+/// The magnitude has no leading zero limbs, and zero is never negative.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Int {
+    negative: bool,
+    magnitude: Vec<u32>,
+}
+
+fn trim(mut v: Vec<u32>) -> Vec<u32> {
+    while v.last() == Some(&0) {
+        v.pop();
+    }
+    v
+}
+
+fn cmp_magnitude(a: &[u32], b: &[u32]) -> Ordering {
+    a.len().cmp(&b.len()).then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut sum = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0u64;
+    for i in 0..a.len().max(b.len()) {
+        let s = *a.get(i).unwrap_or(&0) as u64 + *b.get(i).unwrap_or(&0) as u64 + carry;
+        sum.push(s as u32);
+        carry = s >> 32;
+    }
+    sum.push(carry as u32);
+    trim(sum)
+}
+
+/// `a - b` for `a >= b`
+fn sub_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut difference = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (i, &x) in a.iter().enumerate() {
+        let mut d = x as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        borrow = (d < 0) as i64;
+        d += borrow << 32;
+        difference.push(d as u32);
+    }
+    trim(difference)
+}
+
+fn mul_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut product = vec![0u32; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &y) in b.iter().enumerate() {
+            let p = x as u64 * y as u64 + product[i + j] as u64 + carry;
+            product[i + j] = p as u32;
+            carry = p >> 32;
+        }
+        product[i + b.len()] = carry as u32;
+    }
+    trim(product)
+}
+
+/// the quotient and the remainder of the magnitudes by the long division bit by bit
+fn divmod_magnitude(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    assert!(!b.is_empty(), "division by zero");
+    let mut quotient = vec![0u32; a.len()];
+    let mut remainder: Vec<u32> = vec![];
+    for bit in (0..a.len() * 32).rev() {
+        // remainder = remainder * 2 + the bit
+        let mut carry = (a[bit / 32] >> (bit % 32)) & 1;
+        for limb in remainder.iter_mut() {
+            let next = *limb >> 31;
+            *limb = (*limb << 1) | carry;
+            carry = next;
+        }
+        if carry != 0 {
+            remainder.push(carry);
+        }
+        if cmp_magnitude(&remainder, b) != Ordering::Less {
+            remainder = sub_magnitude(&remainder, b);
+            quotient[bit / 32] |= 1 << (bit % 32);
+        }
+    }
+    (trim(quotient), remainder)
+}
+
+impl Int {
+    fn new(negative: bool, magnitude: Vec<u32>) -> Self {
+        let magnitude = trim(magnitude);
+        Self { negative: negative && !magnitude.is_empty(), magnitude }
+    }
+
+    pub(super) fn zero() -> Self {
+        Self::new(false, vec![])
+    }
+
+    /// `2^exponent`
+    pub(super) fn pow2(exponent: u32) -> Self {
+        let mut magnitude = vec![0u32; exponent as usize / 32 + 1];
+        magnitude[exponent as usize / 32] = 1 << (exponent % 32);
+        Self::new(false, magnitude)
+    }
+
+    pub(super) fn is_zero(&self) -> bool {
+        self.magnitude.is_empty()
+    }
+
+    pub(super) fn abs(&self) -> Self {
+        Self::new(false, self.magnitude.clone())
+    }
+
+    /// the quotient truncated toward zero and the remainder with the sign of `self`
+    fn divmod(&self, divisor: &Int) -> (Int, Int) {
+        let (q, r) = divmod_magnitude(&self.magnitude, &divisor.magnitude);
+        (Int::new(self.negative != divisor.negative, q), Int::new(self.negative, r))
+    }
+
+    /// the quotient of an exact division
+    pub(super) fn div_exact(&self, divisor: &Int) -> Int {
+        let (q, r) = self.divmod(divisor);
+        debug_assert!(r.is_zero(), "inexact division");
+        q
+    }
+
+    /// the nearest integer to `self / divisor` for a positive divisor, rounding halves up
+    pub(super) fn div_round(&self, divisor: &Int) -> Int {
+        debug_assert!(!divisor.negative && !divisor.is_zero());
+        // floor((2 self + divisor) / (2 divisor))
+        let two = Int::from(2u128);
+        let numerator = &(&two * self) + divisor;
+        let denominator = &two * divisor;
+        let (q, r) = numerator.divmod(&denominator);
+        match r.negative {
+            true => &q - &Int::from(1u128),
+            false => q,
+        }
+    }
+
+    pub(super) fn to_f64(&self) -> f64 {
+        let m = self.magnitude.iter().rev().fold(0.0, |acc, &limb| acc * 4294967296.0 + limb as f64);
+        match self.negative {
+            true => -m,
+            false => m,
+        }
+    }
+}
+
+impl From<u128> for Int {
+    fn from(value: u128) -> Self {
+        Int::new(false, (0..4).map(|i| (value >> (32 * i)) as u32).collect())
+    }
+}
+
+impl From<i64> for Int {
+    fn from(value: i64) -> Self {
+        let magnitude = Int::from(value.unsigned_abs() as u128).magnitude;
+        Int::new(value < 0, magnitude)
+    }
+}
+
+impl PartialOrd for Int {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Int {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_magnitude(&self.magnitude, &other.magnitude),
+            (true, true) => cmp_magnitude(&other.magnitude, &self.magnitude),
+        }
+    }
+}
+
+impl Neg for &Int {
+    type Output = Int;
+
+    fn neg(self) -> Int {
+        Int::new(!self.negative, self.magnitude.clone())
+    }
+}
+
+impl Add for &Int {
+    type Output = Int;
+
+    fn add(self, other: &Int) -> Int {
+        match (self.negative == other.negative, cmp_magnitude(&self.magnitude, &other.magnitude)) {
+            (true, _) => Int::new(self.negative, add_magnitude(&self.magnitude, &other.magnitude)),
+            (false, Ordering::Less) => Int::new(other.negative, sub_magnitude(&other.magnitude, &self.magnitude)),
+            (false, _) => Int::new(self.negative, sub_magnitude(&self.magnitude, &other.magnitude)),
+        }
+    }
+}
+
+impl Sub for &Int {
+    type Output = Int;
+
+    fn sub(self, other: &Int) -> Int {
+        self + &-other
+    }
+}
+
+impl Mul for &Int {
+    type Output = Int;
+
+    fn mul(self, other: &Int) -> Int {
+        Int::new(self.negative != other.negative, mul_magnitude(&self.magnitude, &other.magnitude))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn int(v: i64) -> Int {
+        Int::from(v)
+    }
+
+    fn big(v: i128) -> Int {
+        match v < 0 {
+            true => -&Int::from(v.unsigned_abs()),
+            false => Int::from(v as u128),
+        }
+    }
+
+    #[test]
+    fn test_arithmetic_matches_i128() {
+        let values = [0i64, 1, -1, 7, -7, 123456789, -987654321, i64::MAX, i64::MIN + 1, 1 << 40, -(1 << 33)];
+        for &a in &values {
+            for &b in &values {
+                let (x, y) = (a as i128, b as i128);
+                assert_eq!(big(x + y), &int(a) + &int(b), "{} + {}", a, b);
+                assert_eq!(big(x - y), &int(a) - &int(b), "{} - {}", a, b);
+                assert_eq!(big(x * y), &int(a) * &int(b), "{} * {}", a, b);
+                assert_eq!(x.cmp(&y), int(a).cmp(&int(b)));
+                if b > 0 {
+                    let rounded = (2 * x + y).div_euclid(2 * y);
+                    assert_eq!(big(rounded), int(a).div_round(&int(b)), "round({} / {})", a, b);
+                }
+                if b != 0 {
+                    assert_eq!(int(a), (&int(a) * &int(b)).div_exact(&int(b)));
+                }
+                assert_eq!(x as f64, int(a).to_f64());
+            }
+        }
+    }
+
+    #[test]
+    fn test_large_values() {
+        let m = Int::pow2(128);
+        assert_eq!(&Int::from(u128::MAX) + &Int::from(1u128), m);
+        let square = &m * &m;
+        assert_eq!(Int::pow2(256), square);
+        assert_eq!(m, square.div_exact(&m));
+        assert_eq!(2f64.powi(256), square.to_f64());
+        assert!(-&m < Int::zero() && m > Int::zero());
+        assert_eq!(m, (-&m).abs());
+        assert!((&m - &m).is_zero());
+        assert_eq!(Int::zero(), &int(5) - &int(5));
+    }
+}
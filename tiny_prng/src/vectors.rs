@@ -0,0 +1,345 @@
+//! The `vectors` module reads, writes and checks the known-answer files of the generators.
+//!
+//! The files in the `vectors` directory of the crate hold the first outputs of every generator from fixed seeds,
+//! in a line-based text format shared with the implementations in other languages (see `vectors/README.md`):
+//!
+//! ```text
+//! algorithm: xorshift64
+//! seed: 0x0000000000000005
+//! skip: 1000
+//! outputs: 0x... 0x...
+//! real: 0.125... 0.75...
+//! real_f32: 0.5... 0.25...
+//! ```
+//!
+//! A case seeds the generator natively with `seed`, discards `skip` outputs and lists the following
+//! native outputs in hex. `real` and `real_f32` are the `generate_real` and `generate_real_f32` of a generator
+//! seeded and skipped the same, in the shortest decimals which read back exactly.
+//!
+//! ```rust
+//! use tiny_prng::vectors::Case;
+//!
+//! fn main(){
+//!     let case = Case::compute("pcg32", &[42, 54], 0, 2, 0, 0).unwrap();
+//!     assert_eq!(vec![0xa15c02b7, 0x7b47f409], case.outputs);
+//!     assert_eq!(Ok(()), case.check());
+//! }
+//! ```
+
+use std::fmt;
+use crate::{mt, mt64, pcg, xorshift, Generator};
+
+/// the directory of the known-answer files in the source tree of the crate
+pub const DIRECTORY: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/vectors");
+
+/// The algorithms with known answers and the bits of their native outputs.
+pub const ALGORITHMS: &[(&str, u32)] = &[
+    ("mt19937", 32),
+    ("mt19937-64", 64),
+    ("xorshift32", 32),
+    ("xorshift64", 64),
+    ("xorshift128", 32),
+    ("xorshift64star", 64),
+    ("xorshift1024star", 64),
+    ("xoshiro256plusplus", 64),
+    ("pcg-xsh-rr-64-32", 32),
+    ("pcg-xsh-rs-64-32", 32),
+    ("pcg-xsl-rr-64-32-mcg", 32),
+    ("pcg-xsl-rr", 64),
+    ("pcg-xsl-rr-mcg", 64),
+    ("pcg32", 32),
+    ("pcg64", 64),
+];
+
+/// the bits of the native outputs of the algorithm
+pub fn width(algorithm: &str) -> Option<u32> {
+    ALGORITHMS.iter().find(|(name, _)| *name == algorithm).map(|&(_, width)| width)
+}
+
+/// narrow a seed word to the seed type of the generator
+fn word<T: TryFrom<u128>>(seed: u128) -> Result<T, String> {
+    T::try_from(seed).map_err(|_| format!("seed word out of range: {:#x}", seed))
+}
+
+/// construct the generator seeded natively: the key of `with_array` for Mersenne Twister,
+/// the 16 words of `Xorshift1024star`, the 4 words of `Xoshiro256plusplus`, the seed and the stream of `Pcg32` and `Pcg64`
+/// and the single word of `with_seed` for the others
+pub fn build(algorithm: &str, seed: &[u128]) -> Result<Box<dyn Generator>, String> {
+    let g: Box<dyn Generator> = match (algorithm, seed) {
+        ("mt19937", key) => {
+            let key = key.iter().map(|&k| word::<u32>(k).map(u64::from)).collect::<Result<Vec<_>, _>>()?;
+            Box::new(mt::Mt19937::with_array(key))
+        }
+        ("mt19937-64", key) => {
+            let key = key.iter().map(|&k| word::<u64>(k)).collect::<Result<Vec<_>, _>>()?;
+            Box::new(mt64::Mt19937::with_array(key))
+        }
+        ("xorshift32", &[s]) => Box::new(xorshift::Xorshift32::with_seed(word(s)?)),
+        ("xorshift64", &[s]) => Box::new(xorshift::Xorshift64::with_seed(word(s)?)),
+        ("xorshift128", &[s]) => Box::new(xorshift::Xorshift128::with_seed(s)),
+        ("xorshift64star", &[s]) => Box::new(xorshift::Xorshift64star::with_seed(word(s)?)),
+        ("xorshift1024star", s) if s.len() == 16 => {
+            let mut state = [0u64; 16];
+            for (w, &s) in state.iter_mut().zip(s) {
+                *w = word(s)?;
+            }
+            Box::new(xorshift::Xorshift1024star::with_seed(state))
+        }
+        ("xoshiro256plusplus", &[a, b, c, d]) => {
+            Box::new(xorshift::Xoshiro256plusplus::with_seed([word(a)?, word(b)?, word(c)?, word(d)?]))
+        }
+        ("pcg-xsh-rr-64-32", &[s]) => Box::new(pcg::PcgXshRr6432::with_seed(word(s)?)),
+        ("pcg-xsh-rs-64-32", &[s]) => Box::new(pcg::PcgXshRs6432::with_seed(word(s)?)),
+        ("pcg-xsl-rr-64-32-mcg", &[s]) => Box::new(pcg::PcgXslRr6432Mcg::with_seed(word(s)?)),
+        ("pcg-xsl-rr", &[s]) => Box::new(pcg::PcgXslRr::with_seed(s)),
+        ("pcg-xsl-rr-mcg", &[s]) => Box::new(pcg::PcgXslRrMcg::with_seed(s)),
+        ("pcg32", &[s, stream]) => Box::new(pcg::Pcg32::with_stream(word(s)?, word(stream)?)),
+        ("pcg64", &[s, stream]) => Box::new(pcg::Pcg64::with_stream(s, stream)),
+        _ => return Err(format!("unknown algorithm or seed: {} with {} seed words", algorithm, seed.len())),
+    };
+    Ok(g)
+}
+
+/// the next native output of the generator of the width
+fn native(g: &mut dyn Generator, width: u32) -> u128 {
+    match width {
+        32 => g.generate_u32() as u128,
+        64 => g.generate_u64() as u128,
+        _ => g.generate_u128(),
+    }
+}
+
+/// A known-answer case of a generator.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Case {
+    pub algorithm: String,
+    pub seed: Vec<u128>,
+    /// the number of the outputs discarded before the listed ones
+    pub skip: u64,
+    pub outputs: Vec<u128>,
+    pub reals: Vec<f64>,
+    pub reals_f32: Vec<f32>,
+}
+
+impl Case {
+    /// compute the case from the current implementation
+    pub fn compute(algorithm: &str, seed: &[u128], skip: u64, outputs: usize, reals: usize, reals_f32: usize) -> Result<Self, String> {
+        let width = width(algorithm).ok_or_else(|| format!("unknown algorithm: {}", algorithm))?;
+        let skipped = || -> Result<Box<dyn Generator>, String> {
+            let mut g = build(algorithm, seed)?;
+            for _ in 0..skip {
+                native(g.as_mut(), width);
+            }
+            Ok(g)
+        };
+        let mut g = skipped()?;
+        let outputs = (0..outputs).map(|_| native(g.as_mut(), width)).collect();
+        let mut g = skipped()?;
+        let reals = (0..reals).map(|_| g.generate_real()).collect();
+        let mut g = skipped()?;
+        let reals_f32 = (0..reals_f32).map(|_| g.generate_real_f32()).collect();
+        Ok(Self { algorithm: algorithm.to_string(), seed: seed.to_vec(), skip, outputs, reals, reals_f32 })
+    }
+
+    /// compare the case with the current implementation, describing the first mismatch
+    pub fn check(&self) -> Result<(), String> {
+        let actual = Self::compute(&self.algorithm, &self.seed, self.skip, self.outputs.len(), self.reals.len(), self.reals_f32.len())?;
+        let mismatch = |key: &str, i: usize, expected: String, actual: String| {
+            Err(format!("{} seeded with {:#x?}: {}[{}] is {}, expected {}", self.algorithm, self.seed, key, i, actual, expected))
+        };
+        if let Some(i) = (0..self.outputs.len()).find(|&i| self.outputs[i] != actual.outputs[i]) {
+            return mismatch("outputs", i, format!("{:#x}", self.outputs[i]), format!("{:#x}", actual.outputs[i]));
+        }
+        // the reals are compared bit by bit
+        if let Some(i) = (0..self.reals.len()).find(|&i| self.reals[i].to_bits() != actual.reals[i].to_bits()) {
+            return mismatch("real", i, self.reals[i].to_string(), actual.reals[i].to_string());
+        }
+        if let Some(i) = (0..self.reals_f32.len()).find(|&i| self.reals_f32[i].to_bits() != actual.reals_f32[i].to_bits()) {
+            return mismatch("real_f32", i, self.reals_f32[i].to_string(), actual.reals_f32[i].to_string());
+        }
+        Ok(())
+    }
+}
+
+/// the values of a line separated by spaces
+fn join<T>(values: &[T], f: impl Fn(&T) -> String) -> String {
+    values.iter().map(f).collect::<Vec<_>>().join(" ")
+}
+
+/// The case in the format of the known-answer files, with the hex outputs zero-padded to their width.
+impl fmt::Display for Case {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = width(&self.algorithm).unwrap_or(128) as usize / 4 + 2;
+        writeln!(f, "algorithm: {}", self.algorithm)?;
+        writeln!(f, "seed: {}", join(&self.seed, |s| format!("{:#x}", s)))?;
+        writeln!(f, "skip: {}", self.skip)?;
+        writeln!(f, "outputs: {}", join(&self.outputs, |o| format!("{:#0digits$x}", o)))?;
+        writeln!(f, "real: {}", join(&self.reals, f64::to_string))?;
+        writeln!(f, "real_f32: {}", join(&self.reals_f32, f32::to_string))
+    }
+}
+
+fn parse_values<T>(values: &str, f: impl Fn(&str) -> Option<T>) -> Result<Vec<T>, String> {
+    values.split_whitespace().map(|v| f(v).ok_or_else(|| format!("invalid value: {}", v))).collect()
+}
+
+fn parse_hex(value: &str) -> Option<u128> {
+    u128::from_str_radix(value.strip_prefix("0x")?, 16).ok()
+}
+
+/// parse the cases of a known-answer file
+///
+/// Blank lines and the lines from `#` are ignored, and every case begins with its `algorithm` line.
+pub fn parse(text: &str) -> Result<Vec<Case>, String> {
+    let mut cases: Vec<Case> = vec![];
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let at = |e: String| format!("line {}: {}", number + 1, e);
+        let (key, values) = line.split_once(':').ok_or_else(|| at(format!("expected `key: values`: {}", line)))?;
+        let values = values.trim();
+        if key == "algorithm" {
+            cases.push(Case {
+                algorithm: values.to_string(),
+                seed: vec![],
+                skip: 0,
+                outputs: vec![],
+                reals: vec![],
+                reals_f32: vec![],
+            });
+            continue;
+        }
+        let case = cases.last_mut().ok_or_else(|| at("a case must begin with its algorithm".to_string()))?;
+        match key {
+            "seed" => case.seed = parse_values(values, parse_hex).map_err(at)?,
+            "skip" => case.skip = values.parse().map_err(|_| at(format!("invalid skip: {}", values)))?,
+            "outputs" => case.outputs = parse_values(values, parse_hex).map_err(at)?,
+            "real" => case.reals = parse_values(values, |v| v.parse().ok()).map_err(at)?,
+            "real_f32" => case.reals_f32 = parse_values(values, |v| v.parse().ok()).map_err(at)?,
+            _ => return Err(at(format!("unknown key: {}", key))),
+        }
+    }
+    Ok(cases)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+
+    const OUTPUTS: usize = 32;
+    const REALS: usize = 8;
+
+    /// the cases of the files by the algorithm: the seed words and the outputs to skip
+    fn cases(algorithm: &str) -> Vec<(Vec<u128>, u64)> {
+        let xorshift1024 = (1..=16u128).map(|i| (i * 0x9e3779b97f4a7c15) & 0xffffffffffffffff).collect();
+        match algorithm {
+            // the keys of the reference outputs, mt19937ar.out and mt19937-64.out
+            "mt19937" => vec![(vec![0x123, 0x234, 0x345, 0x456], 0), (vec![0x1818729], 1000)],
+            "mt19937-64" => vec![(vec![0x12345, 0x23456, 0x34567, 0x45678], 0), (vec![5], 1000)],
+            // the initial values in the paper of Marsaglia
+            "xorshift32" => vec![(vec![2463534242], 0), (vec![0x1818729], 1000)],
+            "xorshift64" => vec![(vec![88172645463325252], 0), (vec![5], 1000)],
+            "xorshift128" => vec![(vec![88675123 << 96 | 521288629 << 64 | 362436069 << 32 | 123456789], 0), (vec![5], 1000)],
+            "xorshift1024star" => vec![(xorshift1024, 0)],
+            // the state of the reference output of xoshiro256plusplus.c
+            "xoshiro256plusplus" => vec![(vec![1, 2, 3, 4], 0), (vec![0x1818729, 5, 0x89178726ab1f8ab3, 7], 1000)],
+            // the demo of pcg-c, and the words of its static initializers as seeds
+            "pcg32" => vec![(vec![42, 54], 0), (vec![0x853c49e6748fea9b, 0xda3e39cb94b95bdb], 1000)],
+            "pcg64" => vec![(vec![42, 54], 0), (vec![0x979c9a98d84620057d3e9cb6cfe0549b, 0x0000000000000001da3e39cb94b95bdb], 1000)],
+            "pcg-xsl-rr" => vec![(vec![0x1818729182367349], 0), (vec![5], 1000)],
+            _ => vec![(vec![0x1818729182367349], 0), (vec![0x89178726ab1f8ab3], 1000)],
+        }
+    }
+
+    fn path(algorithm: &str) -> String {
+        format!("{}/{}.txt", DIRECTORY, algorithm)
+    }
+
+    #[test]
+    #[ignore = "rewrites the known-answer files from the current outputs"]
+    fn write_vectors() {
+        for &(algorithm, _) in ALGORITHMS {
+            let mut text = format!("# known answers of {}, written by `cargo test -- --ignored write_vectors`\n", algorithm);
+            for (seed, skip) in cases(algorithm) {
+                text += &format!("\n{}", Case::compute(algorithm, &seed, skip, OUTPUTS, REALS, REALS).unwrap());
+            }
+            fs::write(path(algorithm), text).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_known_answers() {
+        let mut checked = vec![];
+        for entry in fs::read_dir(DIRECTORY).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().and_then(|e| e.to_str()) != Some("txt") {
+                continue;
+            }
+            let cases = parse(&fs::read_to_string(&path).unwrap()).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+            assert!(!cases.is_empty(), "{}", path.display());
+            for case in cases {
+                assert_eq!(OUTPUTS, case.outputs.len());
+                if let Err(e) = case.check() {
+                    panic!("{}: {}", path.display(), e);
+                }
+                checked.push(case.algorithm);
+            }
+        }
+        // every algorithm has a file
+        for &(algorithm, _) in ALGORITHMS {
+            assert!(Path::new(&path(algorithm)).exists(), "{}", algorithm);
+            assert!(checked.iter().any(|a| a == algorithm), "{}", algorithm);
+        }
+    }
+
+    #[test]
+    fn test_reference_outputs() {
+        // the first outputs of the reference implementations for the same seeds
+        let case = |algorithm: &str| parse(&fs::read_to_string(path(algorithm)).unwrap()).unwrap().remove(0);
+        // `with_array` leaves out `mt[0] = 0x80000000` of init_by_array, which changes the first output
+        assert_eq!(&[955945823, 477289528, 4107218783, 4228976476], &case("mt19937").outputs[1..5]);
+        assert_ne!(1067595299, case("mt19937").outputs[0]);
+        assert_eq!(&[0xa15c02b7, 0x7b47f409, 0xba1d3330], &case("pcg32").outputs[..3]);
+        assert_eq!(&[0x86b1da1d72062b68, 0x1304aa46c9853d39], &case("pcg64").outputs[..2]);
+        assert_eq!(723471715, case("xorshift32").outputs[0]);
+        assert_eq!(8748534153485358512, case("xorshift64").outputs[0]);
+        assert_eq!(&[3701687786, 458299110, 2500872618, 3633119408], &case("xorshift128").outputs[..4]);
+        assert_eq!(&[41943041, 58720359], &case("xoshiro256plusplus").outputs[..2]);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let case = Case::compute("xorshift128", &[5], 3, 4, 4, 4).unwrap();
+        assert_eq!(vec![case.clone()], parse(&case.to_string()).unwrap());
+        let case = Case::compute("mt19937", &[1, 2, 3], 700, 4, 4, 4).unwrap();
+        assert_eq!(vec![case.clone()], parse(&format!("# comment\n\n{}", case)).unwrap());
+        assert!(case.to_string().contains("outputs: 0x"));
+    }
+
+    #[test]
+    fn test_check_detects_changes() {
+        let mut case = Case::compute("pcg-xsl-rr", &[5], 0, 4, 4, 4).unwrap();
+        assert_eq!(Ok(()), case.check());
+        case.outputs[2] ^= 1;
+        assert!(case.check().unwrap_err().contains("outputs[2]"));
+        let mut case = Case::compute("pcg-xsl-rr", &[5], 0, 4, 4, 4).unwrap();
+        case.reals_f32[3] = 0.5;
+        assert!(case.check().unwrap_err().contains("real_f32[3]"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("seed: 0x1").unwrap_err().contains("line 1"));
+        assert!(parse("algorithm: xorshift64\nseed: 12").is_err());
+        assert!(parse("algorithm: xorshift64\ncolor: red").unwrap_err().contains("unknown key"));
+        assert!(parse("algorithm: xorshift64\noutputs 0x1").unwrap_err().contains("line 2"));
+        assert!(build("xorshift32", &[1 << 32]).is_err());
+        assert!(build("xorshift64", &[1, 2]).is_err());
+        assert!(build("randu", &[1]).is_err());
+        assert!(Case::compute("randu", &[1], 0, 1, 0, 0).is_err());
+    }
+}
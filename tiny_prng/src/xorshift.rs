@@ -17,7 +17,7 @@
 //! }
 //! ```
 
-use crate::{generate_real64, generate_real32, generate_real128};
+use crate::{generate_array, generate_real, impl_generator32, impl_generator64};
 
 pub struct Xorshift32 {
     state: u32,
@@ -25,47 +25,66 @@ pub struct Xorshift32 {
 
 impl Xorshift32 {
     #[inline]
-    pub fn with_seed(seed: u32) -> Self {
+    pub const fn with_seed(seed: u32) -> Self {
         Self { state: seed }
     }
 
+    /// the current state, from which `with_seed` resumes the sequence
     #[inline]
-    pub fn generate(&mut self) -> u32 {
+    pub const fn state(&self) -> u32 {
+        self.state
+    }
+
+    #[inline]
+    pub const fn generate(&mut self) -> u32 {
         self.state ^= self.state << 13;
         self.state ^= self.state >> 17;
         self.state ^= self.state << 5;
-        self.state.clone()
+        self.state
     }
-    generate_real32!();
+    generate_real!();
+    generate_array!(u32);
 }
 
+impl_generator32!(Xorshift32);
+
+#[derive(Debug, Clone)]
 pub struct Xorshift64 {
     state: u64,
 }
 
 impl Xorshift64 {
     #[inline]
-    pub fn with_seed(seed: u64) -> Self {
+    pub const fn with_seed(seed: u64) -> Self {
         Self { state: seed }
     }
 
+    /// the current state, from which `with_seed` resumes the sequence
+    #[inline]
+    pub const fn state(&self) -> u64 {
+        self.state
+    }
+
     #[inline]
-    pub fn generate(&mut self) -> u64 {
+    pub const fn generate(&mut self) -> u64 {
         self.state ^= self.state << 13;
         self.state ^= self.state >> 7;
         self.state ^= self.state << 17;
-        self.state.clone()
+        self.state
     }
-    generate_real64!();
+    generate_real!();
+    generate_array!(u64);
 }
 
+impl_generator64!(Xorshift64);
+
 pub struct Xorshift128 {
     state: [u32; 4],
 }
 
 impl Xorshift128 {
     #[inline]
-    pub fn with_seed(seed: u128) -> Self {
+    pub const fn with_seed(seed: u128) -> Self {
         Self {
             state: [
                 (seed >> 96) as u32,
@@ -76,8 +95,17 @@ impl Xorshift128 {
         }
     }
 
+    /// the current state, from which `with_seed` resumes the sequence
+    #[inline]
+    pub const fn state(&self) -> u128 {
+        (self.state[0] as u128) << 96
+            | (self.state[1] as u128) << 64
+            | (self.state[2] as u128) << 32
+            | self.state[3] as u128
+    }
+
     #[inline]
-    pub fn generate(&mut self) -> u128 {
+    pub const fn generate(&mut self) -> u32 {
         let mut t: u32 = self.state[3];
         let s: u32 = self.state[0];
         self.state[3] = self.state[2];
@@ -86,41 +114,51 @@ impl Xorshift128 {
         t ^= t << 11;
         t ^= t >> 8;
         self.state[0] = t ^ s ^ (s >> 19);
-        ((self.state[0].clone() as u128) << 96)
-            | ((self.state[1].clone() as u128) << 64)
-            | ((self.state[2].clone() as u128) << 32)
-            | (self.state[3].clone() as u128)
+        self.state[0]
     }
-    generate_real128!();
+    generate_real!();
+    generate_array!(u32);
 }
 
+impl_generator32!(Xorshift128);
+
+#[derive(Debug, Clone)]
 pub struct Xorshift64star {
     state: u64,
 }
 
 impl Xorshift64star {
     #[inline]
-    pub fn with_seed(seed: u64) -> Self {
+    pub const fn with_seed(seed: u64) -> Self {
         Self { state: seed }
     }
 
+    /// the current state, from which `with_seed` resumes the sequence
     #[inline]
-    pub fn generate(&mut self) -> u64 {
+    pub const fn state(&self) -> u64 {
+        self.state
+    }
+
+    #[inline]
+    pub const fn generate(&mut self) -> u64 {
         self.state ^= self.state >> 12;
         self.state ^= self.state << 25;
         self.state ^= self.state >> 27;
         self.state.wrapping_mul(0xa738f8117ca1d037)
     }
-    generate_real64!();
+    generate_real!();
+    generate_array!(u64);
 }
 
+impl_generator64!(Xorshift64star);
+
 pub struct Xorshift1024star {
     state: [u64; 16],
     index: usize,
 }
 impl Xorshift1024star {
     #[inline]
-    pub fn with_seed(seed: [u64; 16]) -> Self {
+    pub const fn with_seed(seed: [u64; 16]) -> Self {
         Self {
             state: seed,
             index: 0,
@@ -128,7 +166,7 @@ impl Xorshift1024star {
     }
 
     #[inline]
-    pub fn generate(&mut self) -> u64 {
+    pub const fn generate(&mut self) -> u64 {
         let mut index = self.index;
         let s = self.state[index];
         index += 1;
@@ -145,11 +183,56 @@ impl Xorshift1024star {
         self.state[index].wrapping_mul(0xaac17d8efa43cab7)
     }
 
-    generate_real64!();
+    generate_real!();
+    generate_array!(u64);
 }
+
+impl_generator64!(Xorshift1024star);
+
+/// xoshiro256++ of Blackman and Vigna, the xorshift family with a rotation and the `++` scrambler.
+#[derive(Debug, Clone)]
+pub struct Xoshiro256plusplus {
+    state: [u64; 4],
+}
+
+impl Xoshiro256plusplus {
+    /// seed with the whole state, which must not be all zero
+    #[inline]
+    pub const fn with_seed(seed: [u64; 4]) -> Self {
+        Self { state: seed }
+    }
+
+    /// the current state, from which `with_seed` resumes the sequence
+    #[inline]
+    pub const fn state(&self) -> [u64; 4] {
+        self.state
+    }
+
+    #[inline]
+    pub const fn generate(&mut self) -> u64 {
+        let s = &mut self.state;
+        let result = s[0].wrapping_add(s[3]).rotate_left(23).wrapping_add(s[0]);
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+        result
+    }
+
+    generate_real!();
+    generate_array!(u64);
+}
+
+impl_generator64!(Xoshiro256plusplus);
+
+#[cfg(test)]
 mod tests {
-    use crate::{generate_unit_test, gen_delta_rate, generate_unit_test_real_ranged, generate_unit_test_real1, generate_unit_test_real2};
+    use crate::{generate_unit_test, gen_delta_rate, generate_unit_test_real_ranged, generate_unit_test_real1, generate_unit_test_real2, generate_unit_test_real_f32};
     use super::*;
+    use proptest::prelude::*;
 
     const COUNT: usize = 100 * 1000;
 
@@ -158,24 +241,75 @@ mod tests {
     generate_unit_test_real1!(Xorshift32, test_xorshift32_real1_avr100k, f64, 0x1818729, COUNT);
     generate_unit_test_real2!(Xorshift32, test_xorshift32_real2_avr100k, f64, 0x1818729, COUNT);
     generate_unit_test_real_ranged!(Xorshift32, test_xorshift32_real_ranged_avr100k, f64, 0x1818729, COUNT);
+    generate_unit_test_real_f32!(Xorshift32, test_xorshift32_real_f32_avr100k, 0x1818729, COUNT);
 
     //xorshift64
     generate_unit_test!(Xorshift64, test_xorshift64_avr100k, u64, 0x1818729, COUNT);
     generate_unit_test_real1!(Xorshift64, test_xorshift64_real1_avr100k, f64, 0x1818729, COUNT);
     generate_unit_test_real2!(Xorshift64, test_xorshift64_real2_avr100k, f64, 0x1818729, COUNT);
     generate_unit_test_real_ranged!(Xorshift64, test_xorshift64_real_ranged_avr100k, f64, 0x1818729, COUNT);
+    generate_unit_test_real_f32!(Xorshift64, test_xorshift64_real_f32_avr100k, 0x1818729, COUNT);
 
     //xorshift64star
     generate_unit_test!(Xorshift64star, test_xorshift64star_avr100k, u64, 0x1818729, COUNT);
     generate_unit_test_real1!(Xorshift64star, test_xorshift64star_real1_avr100k, f64, 0x1818729, COUNT);
     generate_unit_test_real2!(Xorshift64star, test_xorshift64star_real2_avr100k, f64, 0x1818729, COUNT);
     generate_unit_test_real_ranged!(Xorshift64star, test_xorshift64star_real_ranged_avr100k, f64, 0x1818729, COUNT);
+    generate_unit_test_real_f32!(Xorshift64star, test_xorshift64star_real_f32_avr100k, 0x1818729, COUNT);
 
     //xorshift128
-    generate_unit_test!(Xorshift128, test_xorshift128_avr100k, u128, 0x1818729, COUNT);
+    generate_unit_test!(Xorshift128, test_xorshift128_avr100k, u32, 0x1818729, COUNT);
     generate_unit_test_real1!(Xorshift128, test_xorshift128_real1_avr100k, f64, 0x1818729, COUNT);
     generate_unit_test_real2!(Xorshift128, test_xorshift128_real2_avr100k, f64, 0x1818729, COUNT);
     generate_unit_test_real_ranged!(Xorshift128, test_xorshift128_real_ranged_avr100k, f64, 0x1818729, COUNT);
+    generate_unit_test_real_f32!(Xorshift128, test_xorshift128_real_f32_avr100k, 0x1818729, COUNT);
+
+    #[test]
+    fn test_const_generate_array() {
+        // evaluated at compile time; the first output of xorshift32 from Marsaglia (2003)
+        const X32: [u32; 1] = Xorshift32::with_seed(2463534242).generate_array();
+        const X64: [u64; 8] = Xorshift64::with_seed(0x1818729).generate_array();
+        const X64STAR: [u64; 8] = Xorshift64star::with_seed(0x1818729).generate_array();
+        const X1024STAR: [u64; 20] = Xorshift1024star::with_seed([0x1818729; 16]).generate_array();
+        assert_eq!([723471715], X32);
+        let mut x = Xorshift64::with_seed(0x1818729);
+        assert!(X64.iter().all(|&v| v == x.generate()));
+        let mut x = Xorshift64star::with_seed(0x1818729);
+        assert!(X64STAR.iter().all(|&v| v == x.generate()));
+        let mut x = Xorshift1024star::with_seed([0x1818729; 16]);
+        assert!(X1024STAR.iter().all(|&v| v == x.generate()));
+        const XOSHIRO: [u64; 2] = Xoshiro256plusplus::with_seed([1, 2, 3, 4]).generate_array();
+        assert_eq!([41943041, 58720359], XOSHIRO);
+    }
+
+    // the state resumes the sequence from any seed
+
+    macro_rules! state_properties {
+        ($($name:ident: $t:ty, $seed:ty),* $(,)?) => {
+            proptest! {
+                $(
+                    #[test]
+                    fn $name(seed: $seed, steps in 0usize..100) {
+                        let mut x = <$t>::with_seed(seed);
+                        for _ in 0..steps {
+                            x.generate();
+                        }
+                        let mut resumed = <$t>::with_seed(x.state());
+                        prop_assert_eq!(x.state(), resumed.state());
+                        prop_assert_eq!(x.generate(), resumed.generate());
+                    }
+                )*
+            }
+        };
+    }
+
+    state_properties!(
+        prop_state_xorshift32: Xorshift32, u32,
+        prop_state_xorshift64: Xorshift64, u64,
+        prop_state_xorshift64star: Xorshift64star, u64,
+        prop_state_xorshift128: Xorshift128, u128,
+        prop_state_xoshiro256plusplus: Xoshiro256plusplus, [u64; 4],
+    );
 
     #[test]
     fn test_xorshift64star() {
@@ -207,6 +341,155 @@ mod tests {
         assert!(delta < acceptable_delta);
     }
 
+    #[test]
+    fn test_xoshiro256plusplus() {
+        let mut s = Xoshiro256plusplus::with_seed([0xab91937581, 0x1818729, 0x5, 0x7]);
+        let mut sum = 0;
+        let acceptable_delta = u64::MAX / 100;
+        for _ in 0..10000 {
+            sum += s.generate() / 10000;
+        }
+        let delta = match sum > u64::MAX / 2 {
+            true => sum - u64::MAX / 2,
+            false => u64::MAX / 2 - sum,
+        };
+        assert!(delta < acceptable_delta);
+    }
+
+    #[test]
+    fn test_xoshiro256plusplus_reference() {
+        // xoshiro256plusplus.c of Blackman and Vigna from the state {1, 2, 3, 4}
+        let mut s = Xoshiro256plusplus::with_seed([1, 2, 3, 4]);
+        let expected = [
+            41943041, 58720359, 3588806011781223, 3591011842654386, 9228616714210784205,
+            9973669472204895162, 14011001112246962877, 12406186145184390807, 15849039046786891736,
+            10450023813501588000,
+        ];
+        assert_eq!(expected, [(); 10].map(|_| s.generate()));
+    }
+
+    /// A linear map over GF(2) on the states of up to 128 bits, stored as the images of the unit vectors.
+    struct Gf2Map {
+        columns: Vec<u128>,
+    }
+
+    impl Gf2Map {
+        fn new(bits: u32, f: impl Fn(u128) -> u128) -> Self {
+            Self { columns: (0..bits).map(|i| f(1 << i)).collect() }
+        }
+
+        fn apply(&self, v: u128) -> u128 {
+            self.columns.iter().enumerate().filter(|&(i, _)| (v >> i) & 1 == 1).fold(0, |acc, (_, c)| acc ^ c)
+        }
+
+        /// the map applying `other` and then `self`
+        fn compose(&self, other: &Self) -> Self {
+            Self { columns: other.columns.iter().map(|&c| self.apply(c)).collect() }
+        }
+
+        fn pow(&self, mut e: u128) -> Self {
+            let mut result = Self::new(self.columns.len() as u32, |v| v);
+            let mut base = Self { columns: self.columns.clone() };
+            while e > 0 {
+                if e & 1 == 1 {
+                    result = base.compose(&result);
+                }
+                base = base.compose(&base);
+                e >>= 1;
+            }
+            result
+        }
+
+        fn is_identity(&self) -> bool {
+            self.columns.iter().enumerate().all(|(i, &c)| c == 1 << i)
+        }
+
+        /// whether the map cycles through every nonzero state, i.e. its order is `2^bits - 1`,
+        /// given the distinct prime factors of `2^bits - 1`
+        fn has_full_period(&self, factors: &[u128]) -> bool {
+            let order = u128::MAX >> (128 - self.columns.len());
+            self.pow(order).is_identity() && factors.iter().all(|&p| !self.pow(order / p).is_identity())
+        }
+    }
+
+    const FACTORS_32: [u128; 5] = [3, 5, 17, 257, 65537];
+    const FACTORS_64: [u128; 7] = [3, 5, 17, 257, 641, 65537, 6700417];
+    const FACTORS_128: [u128; 9] = [3, 5, 17, 257, 641, 65537, 6700417, 274177, 67280421310721];
+
+    #[test]
+    fn test_factors() {
+        assert_eq!(u32::MAX as u128, FACTORS_32.iter().product());
+        assert_eq!(u64::MAX as u128, FACTORS_64.iter().product());
+        assert_eq!(u128::MAX, FACTORS_128.iter().product());
+    }
+
+    #[test]
+    fn test_gf2_map_detects_short_period() {
+        // the rotation by one bit has the order 32, far from 2^32 - 1
+        let rotation = Gf2Map::new(32, |v| (v as u32).rotate_left(1) as u128);
+        assert!(rotation.pow(32).is_identity());
+        assert!(!rotation.has_full_period(&FACTORS_32));
+        // so does the xorshift with the shifts out of the table of Marsaglia
+        let bad = Gf2Map::new(32, |v| {
+            let mut x = Xorshift32::with_seed(v as u32);
+            x.state ^= x.state << 1;
+            x.state ^= x.state >> 1;
+            x.state as u128
+        });
+        assert!(!bad.has_full_period(&FACTORS_32));
+    }
+
+    // The transition of a xorshift generator is linear over GF(2), and its period is 2^n - 1 on every
+    // nonzero state if and only if the order of the transition is 2^n - 1. The 1024bit state of
+    // xorshift1024* and the 256bit state of xoshiro256++ are beyond these matrices, and their periods
+    // rest on Vigna (2016) and Blackman and Vigna (2021).
+
+    #[test]
+    fn test_xorshift32_full_period() {
+        let map = Gf2Map::new(32, |v| Xorshift32::with_seed(v as u32).generate() as u128);
+        assert!(map.has_full_period(&FACTORS_32));
+    }
+
+    #[test]
+    fn test_xorshift64_full_period() {
+        let map = Gf2Map::new(64, |v| Xorshift64::with_seed(v as u64).generate() as u128);
+        assert!(map.has_full_period(&FACTORS_64));
+    }
+
+    #[test]
+    fn test_xorshift64star_full_period() {
+        // the multiplication scrambles the output only, so the state follows xorshift with (12, 25, 27)
+        let map = Gf2Map::new(64, |v| {
+            let mut x = Xorshift64star::with_seed(v as u64);
+            x.generate();
+            x.state as u128
+        });
+        assert!(map.has_full_period(&FACTORS_64));
+    }
+
+    #[test]
+    fn test_xorshift128_full_period() {
+        let map = Gf2Map::new(128, |v| {
+            let mut x = Xorshift128::with_seed(v);
+            x.generate();
+            x.state()
+        });
+        assert!(map.has_full_period(&FACTORS_128));
+    }
+
+    #[test]
+    #[ignore = "enumerates 2^32 - 1 states; run with --release --ignored"]
+    fn test_xorshift32_exhaustive_cycle() {
+        let mut x = Xorshift32::with_seed(1);
+        let mut period = 1u64;
+        while x.generate() != 1 {
+            period += 1;
+        }
+        assert_eq!(u32::MAX as u64, period);
+        // zero is the fixed point outside the cycle
+        assert_eq!(0, Xorshift32::with_seed(0).generate());
+    }
+
     #[bench]
     fn bench_xorshift64_10mil(b: &mut test::Bencher) {
         b.iter(|| {
@@ -230,4 +513,16 @@ mod tests {
             println!("{:x}", v);
         })
     }
+
+    #[bench]
+    fn bench_xoshiro256plusplus_10mil(b: &mut test::Bencher) {
+        b.iter(|| {
+            let mut s = Xoshiro256plusplus::with_seed([0x13378593, 1, 2, 3]);
+            let mut v: u64 = 0;
+            for _ in 0..10000000usize {
+                v = s.generate();
+            };
+            println!("{:x}", v);
+        })
+    }
 }
@@ -1,7 +1,8 @@
 mod utils;
 
 use wasm_bindgen::prelude::*;
-use tiny_prng::{mt64, xorshift, pcg};
+use tiny_prng::{mt64, xorshift, pcg, Generator};
+use tiny_prng::seed::FromSeedSequence;
 
 #[wasm_bindgen(js_name = Pcg)]
 pub struct Pcg {
@@ -16,6 +17,15 @@ impl Pcg {
             generator: pcg::PcgXslRr::with_seed(seed as u128),
         }
     }
+
+    /// seed from a string, yielding the same sequence as `pcg::PcgXslRr::from_bytes` in Rust
+    #[wasm_bindgen(js_name = from_string)]
+    pub fn from_string(seed: &str) -> Self {
+        Self {
+            generator: pcg::PcgXslRr::from_bytes(seed),
+        }
+    }
+
     #[wasm_bindgen(js_name = generate)]
     pub fn generate(&mut self) -> u64 {
         self.generator.generate()
@@ -23,10 +33,8 @@ impl Pcg {
 
     #[wasm_bindgen(js_name = generate_list)]
     pub fn generate_list(&mut self, count: usize) -> Vec<u64> {
-        let mut v = Vec::with_capacity(count);
-        for _ in 0..count {
-            v.push(self.generator.generate());
-        }
+        let mut v = vec![0u64; count];
+        self.generator.fill_u64(&mut v);
         v
     }
 
@@ -37,10 +45,8 @@ impl Pcg {
 
     #[wasm_bindgen(js_name = generate_real_list)]
     pub fn generate_real_list(&mut self, count: usize) -> Vec<f64> {
-        let mut v = Vec::with_capacity(count);
-        for _ in 0..count {
-            v.push(self.generator.generate_real());
-        }
+        let mut v = vec![0.0; count];
+        self.generator.fill_f64(&mut v);
         v
     }
 
@@ -51,10 +57,22 @@ impl Pcg {
 
     #[wasm_bindgen(js_name = generate_real_ranged_list)]
     pub fn generate_real_ranged_list(&mut self, min: f64, max: f64, count: usize) -> Vec<f64> {
-        let mut v = Vec::with_capacity(count);
-        for _ in 0..count {
-            v.push(self.generator.generate_real_in_range(min, max));
-        }
+        let mut v = vec![0.0; count];
+        self.generator.fill_f64_in_range(&mut v, min, max);
+        v
+    }
+
+    #[wasm_bindgen(js_name = generate_real_f32_list)]
+    pub fn generate_real_f32_list(&mut self, count: usize) -> Vec<f32> {
+        let mut v = vec![0.0; count];
+        self.generator.fill_f32(&mut v);
+        v
+    }
+
+    #[wasm_bindgen(js_name = generate_real_ranged_f32_list)]
+    pub fn generate_real_ranged_f32_list(&mut self, min: f32, max: f32, count: usize) -> Vec<f32> {
+        let mut v = vec![0.0; count];
+        self.generator.fill_f32_in_range(&mut v, min, max);
         v
     }
 }
@@ -73,6 +91,14 @@ impl Xorshift64 {
         }
     }
 
+    /// seed from a string, yielding the same sequence as `xorshift::Xorshift64::from_bytes` in Rust
+    #[wasm_bindgen(js_name = from_string)]
+    pub fn from_string(seed: &str) -> Self {
+        Self {
+            generator: xorshift::Xorshift64::from_bytes(seed),
+        }
+    }
+
     #[wasm_bindgen(js_name = generate)]
     pub fn generate(&mut self) -> u64 {
         self.generator.generate()
@@ -80,10 +106,8 @@ impl Xorshift64 {
 
     #[wasm_bindgen(js_name = generate_list)]
     pub fn generate_list(&mut self, count: usize) -> Vec<u64> {
-        let mut v = Vec::with_capacity(count);
-        for _ in 0..count {
-            v.push(self.generator.generate());
-        }
+        let mut v = vec![0u64; count];
+        self.generator.fill_u64(&mut v);
         v
     }
 
@@ -94,10 +118,8 @@ impl Xorshift64 {
 
     #[wasm_bindgen(js_name = generate_real_list)]
     pub fn generate_real_list(&mut self, count: usize) -> Vec<f64> {
-        let mut v = Vec::with_capacity(count);
-        for _ in 0..count {
-            v.push(self.generator.generate_real());
-        }
+        let mut v = vec![0.0; count];
+        self.generator.fill_f64(&mut v);
         v
     }
 
@@ -108,10 +130,22 @@ impl Xorshift64 {
 
     #[wasm_bindgen(js_name = generate_real_ranged_list)]
     pub fn generate_real_ranged_list(&mut self, min: f64, max: f64, count: usize) -> Vec<f64> {
-        let mut v = Vec::with_capacity(count);
-        for _ in 0..count {
-            v.push(self.generator.generate_real_in_range(min, max));
-        }
+        let mut v = vec![0.0; count];
+        self.generator.fill_f64_in_range(&mut v, min, max);
+        v
+    }
+
+    #[wasm_bindgen(js_name = generate_real_f32_list)]
+    pub fn generate_real_f32_list(&mut self, count: usize) -> Vec<f32> {
+        let mut v = vec![0.0; count];
+        self.generator.fill_f32(&mut v);
+        v
+    }
+
+    #[wasm_bindgen(js_name = generate_real_ranged_f32_list)]
+    pub fn generate_real_ranged_f32_list(&mut self, min: f32, max: f32, count: usize) -> Vec<f32> {
+        let mut v = vec![0.0; count];
+        self.generator.fill_f32_in_range(&mut v, min, max);
         v
     }
 }
@@ -130,6 +164,14 @@ impl Mt64 {
         }
     }
 
+    /// seed from a string, yielding the same sequence as `mt64::Mt19937::from_bytes` in Rust
+    #[wasm_bindgen(js_name = from_string)]
+    pub fn from_string(seed: &str) -> Self {
+        Self {
+            generator: mt64::Mt19937::from_bytes(seed),
+        }
+    }
+
     #[wasm_bindgen(js_name = generate)]
     pub fn generate(&mut self) -> u64 {
         self.generator.generate()
@@ -137,10 +179,8 @@ impl Mt64 {
 
     #[wasm_bindgen(js_name = generate_list)]
     pub fn generate_list(&mut self, count: usize) -> Vec<u64> {
-        let mut v = Vec::with_capacity(count);
-        for _ in 0..count {
-            v.push(self.generator.generate());
-        }
+        let mut v = vec![0u64; count];
+        self.generator.fill_u64(&mut v);
         v
     }
 
@@ -151,10 +191,8 @@ impl Mt64 {
 
     #[wasm_bindgen(js_name = generate_real_list)]
     pub fn generate_real_list(&mut self, count: usize) -> Vec<f64> {
-        let mut v = Vec::with_capacity(count);
-        for _ in 0..count {
-            v.push(self.generator.generate_real());
-        }
+        let mut v = vec![0.0; count];
+        self.generator.fill_f64(&mut v);
         v
     }
 
@@ -165,14 +203,27 @@ impl Mt64 {
 
     #[wasm_bindgen(js_name = generate_real_ranged_list)]
     pub fn generate_real_ranged_list(&mut self, min: f64, max: f64, count: usize) -> Vec<f64> {
-        let mut v = Vec::with_capacity(count);
-        for _ in 0..count {
-            v.push(self.generator.generate_real_in_range(min, max));
-        }
+        let mut v = vec![0.0; count];
+        self.generator.fill_f64_in_range(&mut v, min, max);
+        v
+    }
+
+    #[wasm_bindgen(js_name = generate_real_f32_list)]
+    pub fn generate_real_f32_list(&mut self, count: usize) -> Vec<f32> {
+        let mut v = vec![0.0; count];
+        self.generator.fill_f32(&mut v);
+        v
+    }
+
+    #[wasm_bindgen(js_name = generate_real_ranged_f32_list)]
+    pub fn generate_real_ranged_f32_list(&mut self, min: f32, max: f32, count: usize) -> Vec<f32> {
+        let mut v = vec![0.0; count];
+        self.generator.fill_f32_in_range(&mut v, min, max);
         v
     }
 }
 
+#[cfg(test)]
 mod tests {
     use super::*;
 
@@ -186,6 +237,16 @@ mod tests {
         assert_ne!(a, b);
     }
 
+    #[test]
+    fn test_from_string() {
+        let mut p = Pcg::from_string("desert-42");
+        assert_eq!(pcg::PcgXslRr::from_bytes("desert-42").generate(), p.generate());
+        let mut x = Xorshift64::from_string("desert-42");
+        assert_eq!(xorshift::Xorshift64::from_bytes(b"desert-42").generate(), x.generate());
+        let mut m = Mt64::from_string("desert-42");
+        assert_ne!(Mt64::from_string("desert-43").generate(), m.generate());
+    }
+
     #[test]
     fn test_pcg_list() {
         let mut p = Pcg::new(5);
@@ -212,8 +273,8 @@ mod tests {
     fn test_pcg_real_ranged() {
         let mut p = Pcg::new(5);
         let v = p.generate_real_ranged(0.0, std::f64::consts::PI);
-        assert!(v > 0.0);
-        assert!(v <= std::f64::consts::PI);
+        assert!(v >= 0.0);
+        assert!(v < std::f64::consts::PI);
     }
 
     #[test]
@@ -221,9 +282,29 @@ mod tests {
         let mut p = Pcg::new(5);
         let v = p.generate_real_ranged_list(0.0, std::f64::consts::PI, 10);
         assert_eq!(v.len(), 10);
-        for i in 0..v.len() {
-            assert!(v[i] > 0.0);
-            assert!(v[i] <= std::f64::consts::PI);
+        for x in v {
+            assert!(x >= 0.0);
+            assert!(x < std::f64::consts::PI);
+        }
+    }
+
+    #[test]
+    fn test_pcg_real_f32_list() {
+        let mut p = Pcg::new(5);
+        let v = p.generate_real_f32_list(10);
+        assert_eq!(v.len(), 10);
+        for x in v {
+            assert!((0.0..1.0).contains(&x));
+        }
+    }
+
+    #[test]
+    fn test_pcg_real_ranged_f32_list() {
+        let mut p = Pcg::new(5);
+        let v = p.generate_real_ranged_f32_list(-1.0, std::f32::consts::PI, 10);
+        assert_eq!(v.len(), 10);
+        for x in v {
+            assert!((-1.0..std::f32::consts::PI).contains(&x));
         }
     }
 
@@ -262,8 +343,8 @@ mod tests {
     fn test_xorshift64_real_ranged() {
         let mut p = Xorshift64::new(5);
         let v = p.generate_real_ranged(0.0, std::f64::consts::PI);
-        assert!(v > 0.0);
-        assert!(v <= std::f64::consts::PI);
+        assert!(v >= 0.0);
+        assert!(v < std::f64::consts::PI);
     }
 
     #[test]
@@ -271,9 +352,29 @@ mod tests {
         let mut p = Xorshift64::new(5);
         let v = p.generate_real_ranged_list(0.0, std::f64::consts::PI, 10);
         assert_eq!(v.len(), 10);
-        for i in 0..v.len() {
-            assert!(v[i] > 0.0);
-            assert!(v[i] <= std::f64::consts::PI);
+        for x in v {
+            assert!(x >= 0.0);
+            assert!(x < std::f64::consts::PI);
+        }
+    }
+
+    #[test]
+    fn test_xorshift64_real_f32_list() {
+        let mut p = Xorshift64::new(5);
+        let v = p.generate_real_f32_list(10);
+        assert_eq!(v.len(), 10);
+        for x in v {
+            assert!((0.0..1.0).contains(&x));
+        }
+    }
+
+    #[test]
+    fn test_xorshift64_real_ranged_f32_list() {
+        let mut p = Xorshift64::new(5);
+        let v = p.generate_real_ranged_f32_list(-1.0, std::f32::consts::PI, 10);
+        assert_eq!(v.len(), 10);
+        for x in v {
+            assert!((-1.0..std::f32::consts::PI).contains(&x));
         }
     }
 
@@ -284,6 +385,47 @@ mod tests {
         assert_eq!(v.len(), 10);
     }
 
+    /// check the wrapper against the cases of the known-answer file of tiny_prng whose seed fits in its constructor
+    macro_rules! check_known_answers {
+        ($t:ty, $algorithm:expr) => {{
+            let path = format!("{}/{}.txt", tiny_prng::vectors::DIRECTORY, $algorithm);
+            let cases = tiny_prng::vectors::parse(&std::fs::read_to_string(path).unwrap()).unwrap();
+            let seeded = |case: &tiny_prng::vectors::Case| {
+                let mut g = <$t>::new(case.seed[0] as u32);
+                for _ in 0..case.skip {
+                    g.generate();
+                }
+                g
+            };
+            let cases: Vec<_> = cases.iter().filter(|c| c.seed.len() == 1 && c.seed[0] <= u32::MAX as u128).collect();
+            assert!(!cases.is_empty());
+            for case in cases {
+                let outputs: Vec<u128> = seeded(case).generate_list(case.outputs.len()).into_iter().map(u128::from).collect();
+                assert_eq!(case.outputs, outputs);
+                assert_eq!(case.reals, seeded(case).generate_real_list(case.reals.len()));
+                assert_eq!(case.reals_f32, seeded(case).generate_real_f32_list(case.reals_f32.len()));
+            }
+        }};
+    }
+
+    #[test]
+    fn test_known_answers() {
+        // only the wrapped generators; tiny_prng checks the others, xoshiro256++ among them, against the same files
+        check_known_answers!(Pcg, "pcg-xsl-rr");
+        check_known_answers!(Xorshift64, "xorshift64");
+        check_known_answers!(Mt64, "mt19937-64");
+    }
+
+    #[test]
+    fn test_mt64_list_equals_generate() {
+        // the list crosses the block boundary of the state
+        let (mut a, mut b) = (Mt64::new(5), Mt64::new(5));
+        let v = a.generate_list(1000);
+        assert!(v.iter().all(|&x| x == b.generate()));
+        let v = a.generate_real_list(10);
+        assert!(v.iter().all(|&x| x == b.generate_real()));
+    }
+
     #[test]
     fn test_mt64() {
         let mut p = Mt64::new(5);
@@ -310,8 +452,8 @@ mod tests {
     fn test_mt64_real_ranged() {
         let mut p = Mt64::new(5);
         let v = p.generate_real_ranged(0.0, std::f64::consts::PI);
-        assert!(v > 0.0);
-        assert!(v <= std::f64::consts::PI);
+        assert!(v >= 0.0);
+        assert!(v < std::f64::consts::PI);
     }
 
     #[test]
@@ -319,9 +461,29 @@ mod tests {
         let mut p = Mt64::new(5);
         let v = p.generate_real_ranged_list(0.0, std::f64::consts::PI, 10);
         assert_eq!(v.len(), 10);
-        for i in 0..v.len() {
-            assert!(v[i] > 0.0);
-            assert!(v[i] <= std::f64::consts::PI);
+        for x in v {
+            assert!(x >= 0.0);
+            assert!(x < std::f64::consts::PI);
+        }
+    }
+
+    #[test]
+    fn test_mt64_real_f32_list() {
+        let mut p = Mt64::new(5);
+        let v = p.generate_real_f32_list(10);
+        assert_eq!(v.len(), 10);
+        for x in v {
+            assert!((0.0..1.0).contains(&x));
+        }
+    }
+
+    #[test]
+    fn test_mt64_real_ranged_f32_list() {
+        let mut p = Mt64::new(5);
+        let v = p.generate_real_ranged_f32_list(-1.0, std::f32::consts::PI, 10);
+        assert_eq!(v.len(), 10);
+        for x in v {
+            assert!((-1.0..std::f32::consts::PI).contains(&x));
         }
     }
 }
\ No newline at end of file